@@ -76,6 +76,8 @@ fn section_printing() {
     println!("{:_^20}", "centered"); // Center-aligned with underscores
     println!("{:*>10}", "right"); // Right-aligned with asterisks
     println!("{:.<10}", "left"); // Left-aligned with dots
+
+    // To take any other spec apart, e.g. `{:_^12.3}`: format_explain.rs --repl
 }
 
 // ==========================================
@@ -632,7 +634,7 @@ in Rust";
     println!("Mapped option: {:?}", mapped);
     
     let filtered = some_number.filter(|x| *x > 10);
-    println!("Filtered option: {:?}", filtered); // None because 5 is not > 10
+    println!("Filtered option: {:?}", filtered); // => None // because 5 is not > 10
     
     // ---------------------
     // 3.11 RESULT TYPE
@@ -835,7 +837,7 @@ in Rust";
     
     let num = 65;
     let character = num as u8 as char; // Convert to ASCII character
-    let float_to_int = 3.99 as i32; // Truncates to 3
+    let float_to_int = 3.99 as i32; // => 3 // truncates toward zero
     
     println!("Type casting - num to char: {}, float to int: {}", character, float_to_int);
     
@@ -847,7 +849,7 @@ in Rust";
     
     // Casting with potentially lossy conversions
     let large_value: i32 = 1000;
-    let byte_value = large_value as u8; // This will truncate to 232 (1000 % 256)
+    let byte_value = large_value as u8; // => 232 // truncated: 1000 % 256
     
     println!("Lossy conversion - i32 to u8: {}", byte_value);
     
@@ -860,10 +862,10 @@ in Rust";
     // TryFrom and TryInto for fallible conversions (returns Result)
     use std::convert::TryFrom;
     
-    let try_result = i32::try_from(42_i8);
+    let try_result = i32::try_from(42_i8); // => Ok(42)
     println!("TryFrom successful: {:?}", try_result);
     
-    let try_result_err = i32::try_from(300_i8); // Will fail, i8 max is 127
+    let try_result_err = i8::try_from(300_i32); // => Err(TryFromIntError(())) // fails: i8 max is 127
     println!("TryFrom unsuccessful: {:?}", try_result_err);
     
     // ==========================================
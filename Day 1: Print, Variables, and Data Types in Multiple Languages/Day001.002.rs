@@ -1,78 +1,105 @@
+mod drop_trace; // drop_trace.rs: records creation, moves, clones and drops of `Traced` values.
+mod str_inspect; // str_inspect.rs: bytes, chars, grapheme clusters and valid slice indices of a string.
+// When an edit to this file stops compiling, lesson_errors.rs explains the borrow checker's
+// error and points to the section below that teaches the rule.
+
+// Wraps one expression: with `--track-alloc`, prints what it allocated and freed.
+// Without the flag it is just the expression.
+macro_rules! alloc_line {
+    ($e:expr) => {
+        alloc_tracker::line(stringify!($e), || $e)
+    };
+}
+
 // The main function, the entry point of every Rust program.
 fn main() {
+    // `--track-alloc` turns on the heap allocation counter (see the end of this file).
+    if std::env::args().any(|arg| arg == "--track-alloc") {
+        alloc_tracker::enable();
+    }
+    // `--trace-drops` prints a drop timeline after the traced examples in sections 11 and 14.
+    if std::env::args().any(|arg| arg == "--trace-drops") {
+        drop_trace::enable_timelines();
+    }
+    // `--inspect-strings` explains the byte indices used in section 9.
+    if std::env::args().any(|arg| arg == "--inspect-strings") {
+        str_inspect::enable_reports();
+    }
     println!("--- Rust Scope and Ownership Examples ---");
 
     // --- 1. Variable Scopes: The Lifetime of a Binding ---
     println!("\n--- 1. Variable Scopes ---");
-    variable_scopes();
+    alloc_tracker::section("1. Variable Scopes", variable_scopes);
 
     // --- 2. The `String` Type: Heap Allocation and Ownership ---
     println!("\n--- 2. The `String` Type ---");
-    string_type_and_heap();
+    alloc_tracker::section("2. The `String` Type", string_type_and_heap);
 
     // --- 3. Ownership and Moves: Transferring Responsibility (Heap Data) ---
     println!("\n--- 3. Ownership and Moves ---");
-    ownership_and_moves();
+    alloc_tracker::section("3. Ownership and Moves", ownership_and_moves);
 
     // --- 4. The `Copy` Trait: Duplicating Data on the Stack ---
     println!("\n--- 4. The `Copy` Trait ---");
-    copy_trait_behavior();
+    alloc_tracker::section("4. The `Copy` Trait", copy_trait_behavior);
 
     // --- 5. Ownership and Functions: Passing Values Around ---
     println!("\n--- 5. Ownership and Functions ---");
-    ownership_with_functions();
+    alloc_tracker::section("5. Ownership and Functions", ownership_with_functions);
 
     // --- 6. References and Borrowing: Accessing Data Without Ownership ---
     println!("\n--- 6. References and Borrowing ---");
-    references_and_borrowing();
+    alloc_tracker::section("6. References and Borrowing", references_and_borrowing);
 
     // --- 7. Mutable References: Modifying Borrowed Data ---
     println!("\n--- 7. Mutable References ---");
-    mutable_references();
+    alloc_tracker::section("7. Mutable References", mutable_references);
 
     // --- 8. The Rules of References: Ensuring Safety ---
     println!("\n--- 8. The Rules of References ---");
-    rules_of_references();
+    alloc_tracker::section("8. The Rules of References", rules_of_references);
 
     // --- 9. Slices: References to a Portion of a Collection ---
     println!("\n--- 9. Slices ---");
-    slices_example();
+    alloc_tracker::section("9. Slices", slices_example);
 
     // --- 10. Lifetimes: Ensuring References Are Always Valid ---
     println!("\n--- 10. Lifetimes ---");
-    lifetimes_explained();
+    alloc_tracker::section("10. Lifetimes", lifetimes_explained);
 
     // --- 11. Structs and Ownership ---
     println!("\n--- 11. Structs and Ownership ---");
-    structs_and_ownership();
+    alloc_tracker::section("11. Structs and Ownership", structs_and_ownership);
 
     // --- 12. Methods, `self`, and Borrowing ---
     println!("\n--- 12. Methods, `self`, and Borrowing ---");
-    methods_self_and_borrowing();
+    alloc_tracker::section("12. Methods, `self`, and Borrowing", methods_self_and_borrowing);
 
     // --- 13. Enums and Ownership ---
     println!("\n--- 13. Enums and Ownership ---");
-    enums_and_ownership();
+    alloc_tracker::section("13. Enums and Ownership", enums_and_ownership);
 
     // --- 14. Collections and Ownership (`Vec<T>`, `HashMap<K, V>`) ---
     println!("\n--- 14. Collections and Ownership ---");
-    collections_and_ownership();
+    alloc_tracker::section("14. Collections and Ownership", collections_and_ownership);
 
     // --- 15. Error Handling with `Result<T, E>` and Ownership ---
     println!("\n--- 15. Error Handling with `Result<T, E>` ---");
-    result_and_ownership();
+    alloc_tracker::section("15. Error Handling with `Result<T, E>`", result_and_ownership);
 
     // --- 16. Closures and Capturing Environment Variables ---
     println!("\n--- 16. Closures and Capturing ---");
-    closures_and_capturing();
+    alloc_tracker::section("16. Closures and Capturing", closures_and_capturing);
 
     // --- 17. Interior Mutability: `Cell<T>` and `RefCell<T>` (A Brief Look) ---
     println!("\n--- 17. Interior Mutability (Brief Look) ---");
-    interior_mutability_glimpse();
+    alloc_tracker::section("17. Interior Mutability (Brief Look)", interior_mutability_glimpse);
 
     // --- 18. Shared Ownership: `Rc<T>` and `Arc<T>` (A Brief Look) ---
     println!("\n--- 18. Shared Ownership (Brief Look) ---");
-    shared_ownership_glimpse();
+    alloc_tracker::section("18. Shared Ownership (Brief Look)", shared_ownership_glimpse);
+
+    alloc_tracker::print_summary();
 }
 
 // --- 1. Variable Scopes: The Lifetime of a Binding ---
@@ -117,12 +144,12 @@ fn string_type_and_heap() {
     // It's allocated on the heap, so its size can change at runtime.
     // `String::from` creates a `String` from a string literal.
     // This involves allocating memory on the heap to hold "Hello, heap!".
-    let s1: String = String::from("Hello, heap!"); // `s1` is the owner of this heap data.
+    let s1: String = alloc_line!(String::from("Hello, heap!")); // `s1` is the owner of this heap data.
     println!("{} - (length: {}, capacity: {})", s1, s1.len(), s1.capacity());
 
     // We can mutate an owned `String` if it's marked `mut`.
-    let mut s2 = String::from("Initial");
-    s2.push_str(" and appended."); // Modifies the string on the heap.
+    let mut s2 = alloc_line!(String::from("Initial"));
+    alloc_line!(s2.push_str(" and appended.")); // Modifies the string on the heap (it may need a bigger block).
     println!("{}", s2);
 
 } // When `s1` and `s2` go out of scope here, Rust automatically calls a special function
@@ -130,15 +157,20 @@ fn string_type_and_heap() {
   // the memory on the heap that `s1` and `s2` owned. This prevents memory leaks.
 
 // --- 3. Ownership and Moves: Transferring Responsibility (Heap Data) ---
+// To watch the stack and heap change line by line: ownership_viz.rs (sections 6-8 too).
 fn ownership_and_moves() {
-    let str_owner1 = String::from("data on heap"); // `str_owner1` owns the "data on heap".
+    let str_owner1 = alloc_line!(String::from("data on heap")); // `str_owner1` owns the "data on heap".
                                                  // It holds a pointer to the heap, length, and capacity.
 
     // When we assign `str_owner1` to `str_owner2`, a "move" occurs for heap-allocated data.
     // What's copied: The pointer, length, and capacity (which are on the stack).
     // What's NOT copied: The actual data on the heap ("data on heap").
     // Instead, ownership of that heap data is transferred from `str_owner1` to `str_owner2`.
-    let str_owner2 = str_owner1;
+    // The move is measured from a mark rather than wrapped in `alloc_line!`, which would
+    // move `str_owner1` into a closure and blur the error below.
+    let mark = alloc_tracker::mark();
+    let str_owner2 = str_owner1; // A move: no allocation at all.
+    alloc_tracker::line_since(mark, "let str_owner2 = str_owner1;");
 
     // `str_owner1` is no longer considered valid. Rust invalidates it to prevent
     // a "double free" error (where both `str_owner1` and `str_owner2` might try to
@@ -149,8 +181,8 @@ fn ownership_and_moves() {
     println!("str_owner2: {}", str_owner2);
 
     // If you truly need a deep copy of heap data (like a `String`), use the `clone()` method.
-    let original_string = String::from("clone me");
-    let cloned_string = original_string.clone(); // This allocates new memory on the heap
+    let original_string = alloc_line!(String::from("clone me"));
+    let cloned_string = alloc_line!(original_string.clone()); // This allocates new memory on the heap
                                                  // and copies the content of `original_string`.
 
     println!("Original: {} (still valid)", original_string);
@@ -166,7 +198,7 @@ fn copy_trait_behavior() {
 
     // When we assign `x` to `y`, the value `5` is actually copied.
     // This is a bit-for-bit copy because the entire data is on the stack and small.
-    let y: i32 = x;
+    let y: i32 = alloc_line!(x); // A copy of 4 bytes on the stack: nothing on the heap.
 
     // Both `x` and `y` are valid and independent.
     println!("x = {}, y = {}", x, y);
@@ -183,10 +215,12 @@ fn copy_trait_behavior() {
     println!("Point1: {:?}, Point2: {:?}", point1, point2); // Both valid
 
     // A tuple containing a non-`Copy` type (like `String`) is NOT `Copy`.
-    let data1 = (String::from("hello"), 42); // (String, i32) is NOT `Copy`.
+    let data1 = alloc_line!((String::from("hello"), 42)); // (String, i32) is NOT `Copy`.
+    let mark = alloc_tracker::mark();
     let data2 = data1; // This is a MOVE because String is not Copy.
                        // `data1.0` (the String) is moved.
                        // `data1.1` (the i32) is copied.
+    alloc_tracker::line_since(mark, "let data2 = data1;");
     // println!("Data1: {:?}", data1); // COMPILE-TIME ERROR: `data1.0` (the String part) was moved.
     println!("Data2: {:?}", data2);
 }
@@ -338,6 +372,10 @@ fn slices_example() {
     println!("Slice 1: '{}'", hello_slice);
     println!("Slice 2: '{}'", world_slice);
 
+    // The indices are bytes, not characters. This string is ASCII, so each char is one byte;
+    // in "Grüße" the `ü` takes two, and `&s[0..3]` would panic. (`--inspect-strings` explains why.)
+    str_inspect::print_reports(&["Grüße"], &[(&full_string, 0..5), ("Grüße", 0..3), ("Grüße", 0..4), ("ö", 0..1)]);
+
     // String literals are already slices:
     let literal_example: &str = "This is a literal slice.";
     println!("{}", literal_example);
@@ -378,6 +416,7 @@ fn longest_slice<'a>(x: &'a str, y: &'a str) -> &'a str {
     }
 }
 
+// lifetime_timeline.rs draws each binding's scope and each borrow next to this function's lines.
 fn lifetimes_explained() {
     let string1 = String::from("long string is long");
     let result;
@@ -473,6 +512,25 @@ fn structs_and_ownership() {
     };
     println!("Book 3 (from book2 parts): {:?}", book3);
     // println!("Book 2 author after update syntax: {}", book2.author); // COMPILE-TIME ERROR! `book2.author` was moved.
+
+    // When does each of these get dropped? The same moves again, with `Traced` fields
+    // (see drop_trace.rs) so that every drop is recorded. Run with `--trace-drops` to see it.
+    // The fields hold `&str` so this replay adds nothing to the section's `--track-alloc` count;
+    // a `Traced` value is not `Copy`, so it moves exactly like the `String` fields above.
+    struct TracedBook {
+        title: drop_trace::Traced<&'static str>,
+        author: drop_trace::Traced<&'static str>,
+    }
+    drop_trace::section("11. Structs and Ownership", || {
+        let first = TracedBook {
+            title: drop_trace::Traced::new("first.title", "Rust for Rustaceans"),
+            author: drop_trace::Traced::new("first.author", "Jon Gjengset"),
+        };
+        let second = TracedBook { title: drop_trace::Traced::new("second.title", "Another Book"), ..first };
+        // `first.author` now lives in `second`; only `first.title` is still owned by `first`.
+        // Locals drop in reverse order: `second` (title, then author), then what is left of `first`.
+        println!("Traced book: {} by {}", *second.title, *second.author);
+    });
 }
 
 // --- 12. Methods, `self`, and Borrowing ---
@@ -638,6 +696,18 @@ fn collections_and_ownership() {
     for (team_name_ref, score_ref) in &scores { // Iterating borrows `&String` and `&u32`.
         println!("{}: {}", team_name_ref, score_ref);
     }
+
+    // The names example again, traced: moved in, cloned out, and dropped in index order
+    // when the collection goes. Run with `--trace-drops` to see the timeline. It uses an
+    // array of `&str` names so the replay adds nothing to this section's `--track-alloc`
+    // count; a `Vec` drops its elements in the same order.
+    drop_trace::section("14. Collections and Ownership", || {
+        let alice = drop_trace::Traced::new("alice", "Alice");
+        let bob = drop_trace::Traced::new("bob", "Bob");
+        let favourite = alice.clone(); // A copy that lives on its own.
+        let names = [alice.moved_into("names[0]"), bob.moved_into("names[1]")];
+        println!("Traced names: {:?}, favourite: {:?}", names, favourite);
+    }); // `names` is dropped first (declared last): alice, then bob. Then `favourite`.
 }
 
 // --- 15. Error Handling with `Result<T, E>` and Ownership ---
@@ -811,7 +881,7 @@ fn shared_ownership_glimpse() {
 
     // `Rc::clone(&owner1)` doesn't deep-copy `SharedItem`.
     // It creates a new `Rc` pointer to the *same* `SharedItem` and increments the reference count.
-    let owner2 = Rc::clone(&owner1);
+    let owner2 = alloc_line!(Rc::clone(&owner1)); // Only a counter goes up: no allocation.
     println!("Owner2: {:?}, Count: {}", owner2, Rc::strong_count(&owner1)); // or Rc::strong_count(&owner2)
 
     {
@@ -826,3 +896,242 @@ fn shared_ownership_glimpse() {
 
     // The `SharedItem` data itself is only dropped when the strong reference count becomes 0.
 } // `owner2` then `owner1` go out of scope, count becomes 0, `SharedItem` is dropped.
+
+// --- Seeing the Heap: An Opt-In Allocation Counter ---
+// The sections above *claim* that `String::from` allocates, that a move does not, and that
+// `clone()` does. Run with `--track-alloc` to see real numbers:
+//   - every section prints how many heap blocks it allocated and freed,
+//   - the lines wrapped in `alloc_line!(...)` print what that one expression allocated,
+//   - a per-section table is printed at the end.
+// Without the flag the counter is still installed but records nothing, and nothing is printed.
+mod alloc_tracker {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::RefCell;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    // A global allocator is just a type implementing `GlobalAlloc`. This one forwards every
+    // request to the system allocator and counts it on the way through.
+    pub struct CountingAllocator;
+
+    static ENABLED: AtomicBool = AtomicBool::new(false);
+    static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+    static FREES: AtomicUsize = AtomicUsize::new(0);
+    static REALLOCS: AtomicUsize = AtomicUsize::new(0);
+    static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    static BYTES_FREED: AtomicUsize = AtomicUsize::new(0);
+    static BYTES_GROWN_FROM: AtomicUsize = AtomicUsize::new(0);
+    static BYTES_GROWN_TO: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            if ENABLED.load(Ordering::Relaxed) {
+                ALLOCS.fetch_add(1, Ordering::Relaxed);
+                BYTES_ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+            }
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            if ENABLED.load(Ordering::Relaxed) {
+                FREES.fetch_add(1, Ordering::Relaxed);
+                BYTES_FREED.fetch_add(layout.size(), Ordering::Relaxed);
+            }
+            System.dealloc(ptr, layout)
+        }
+
+        // Growing a `String` or `Vec` asks for a bigger block, `shrink_to_fit` for a smaller
+        // one: counted as one realloc, kept apart from the bytes of fresh allocations and frees.
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            if ENABLED.load(Ordering::Relaxed) {
+                REALLOCS.fetch_add(1, Ordering::Relaxed);
+                BYTES_GROWN_FROM.fetch_add(layout.size(), Ordering::Relaxed);
+                BYTES_GROWN_TO.fetch_add(new_size, Ordering::Relaxed);
+            }
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct Counts {
+        pub allocs: usize,
+        pub frees: usize,
+        pub reallocs: usize,
+        pub bytes_allocated: usize,
+        pub bytes_freed: usize,
+        pub bytes_grown_from: usize,
+        pub bytes_grown_to: usize,
+    }
+
+    impl Counts {
+        // The counters only go up, but saturate anyway: a wrong table beats a debug-build panic.
+        fn since(self, before: Counts) -> Counts {
+            Counts {
+                allocs: self.allocs.saturating_sub(before.allocs),
+                frees: self.frees.saturating_sub(before.frees),
+                reallocs: self.reallocs.saturating_sub(before.reallocs),
+                bytes_allocated: self.bytes_allocated.saturating_sub(before.bytes_allocated),
+                bytes_freed: self.bytes_freed.saturating_sub(before.bytes_freed),
+                bytes_grown_from: self.bytes_grown_from.saturating_sub(before.bytes_grown_from),
+                bytes_grown_to: self.bytes_grown_to.saturating_sub(before.bytes_grown_to),
+            }
+        }
+
+        // How much bigger the reallocated blocks got; negative when they shrank.
+        fn bytes_grown(self) -> i128 {
+            self.bytes_grown_to as i128 - self.bytes_grown_from as i128
+        }
+
+        // "1 block of 12 bytes", "3 blocks, 40 bytes in total", "nothing".
+        // Reallocs get their own clause ("grew a block once, from 7 to 21 bytes"), so a grown
+        // `String` is not counted as a second allocation.
+        fn describe(self) -> String {
+            let blocks = |n: usize, bytes: usize| match n {
+                0 => "nothing".to_string(),
+                1 => format!("1 block of {} bytes", bytes),
+                n => format!("{} blocks, {} bytes in total", n, bytes),
+            };
+            let mut text = format!("allocated {}", blocks(self.allocs, self.bytes_allocated));
+            let verb = if self.bytes_grown() < 0 { "shrank" } else { "grew" };
+            match self.reallocs {
+                0 => {}
+                1 => text.push_str(&format!(", {} a block once, from {} to {} bytes", verb, self.bytes_grown_from, self.bytes_grown_to)),
+                n => text.push_str(&format!(
+                    ", {} a block {} times, from {} to {} bytes in total",
+                    verb, n, self.bytes_grown_from, self.bytes_grown_to
+                )),
+            }
+            if self.frees > 0 {
+                text.push_str(&format!(", freed {}", blocks(self.frees, self.bytes_freed)));
+            }
+            text
+        }
+    }
+
+    fn snapshot() -> Counts {
+        Counts {
+            allocs: ALLOCS.load(Ordering::Relaxed),
+            frees: FREES.load(Ordering::Relaxed),
+            reallocs: REALLOCS.load(Ordering::Relaxed),
+            bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed),
+            bytes_freed: BYTES_FREED.load(Ordering::Relaxed),
+            bytes_grown_from: BYTES_GROWN_FROM.load(Ordering::Relaxed),
+            bytes_grown_to: BYTES_GROWN_TO.load(Ordering::Relaxed),
+        }
+    }
+
+    thread_local! {
+        // (section name, counts) for the summary table.
+        static SECTIONS: RefCell<Vec<(&'static str, Counts)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub fn enable() {
+        ENABLED.store(true, Ordering::Relaxed);
+    }
+
+    pub fn enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    // Our own reporting allocates too (`format!`, the summary table); keep it out of the numbers.
+    fn report(f: impl FnOnce()) {
+        ENABLED.store(false, Ordering::Relaxed);
+        f();
+        ENABLED.store(true, Ordering::Relaxed);
+    }
+
+    // Runs one expression and reports what it did to the heap. Used through `alloc_line!`.
+    pub fn line<T>(source: &str, f: impl FnOnce() -> T) -> T {
+        let before = mark();
+        let value = f();
+        line_since(before, source);
+        value
+    }
+
+    // For statements that should stay as written (a move inside a closure changes the
+    // compiler's error): take a mark before, report after.
+    pub fn mark() -> Counts {
+        snapshot()
+    }
+
+    pub fn line_since(before: Counts, source: &str) {
+        if enabled() {
+            let counts = snapshot().since(before);
+            report(|| println!("    [alloc] `{}`: this line {}", source, counts.describe()));
+        }
+    }
+
+    // Runs a whole lesson section and reports its totals (including drops at its end).
+    pub fn section(name: &'static str, f: fn()) {
+        if !enabled() {
+            return f();
+        }
+        let before = snapshot();
+        f();
+        let counts = snapshot().since(before);
+        report(|| {
+            println!("  [alloc] section total: {}", counts.describe());
+            SECTIONS.with(|s| s.borrow_mut().push((name, counts)));
+        });
+    }
+
+    pub fn print_summary() {
+        if !enabled() {
+            return;
+        }
+        println!("\n--- Heap allocations per section ---");
+        println!("  {:<40} {:>7} {:>7} {:>9} {:>12} {:>12} {:>12}", "section", "allocs", "frees", "reallocs", "bytes alloc", "bytes freed", "bytes grown");
+        SECTIONS.with(|s| {
+            for (name, c) in s.borrow().iter() {
+                let grown = match c.bytes_grown() {
+                    0 => "0".to_string(),
+                    bytes => format!("{:+}", bytes),
+                };
+                println!("  {:<40} {:>7} {:>7} {:>9} {:>12} {:>12} {:>12}", name, c.allocs, c.frees, c.reallocs, c.bytes_allocated, c.bytes_freed, grown);
+            }
+        });
+    }
+
+    // Run with: rustc --edition 2021 --test --crate-name day001_002 Day001.002.rs -o target/day001_002_tests && target/day001_002_tests
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn counts(allocs: usize, bytes_allocated: usize, frees: usize, bytes_freed: usize) -> Counts {
+            Counts { allocs, bytes_allocated, frees, bytes_freed, ..Counts::default() }
+        }
+
+        fn resized(reallocs: usize, bytes_grown_from: usize, bytes_grown_to: usize) -> Counts {
+            Counts { reallocs, bytes_grown_from, bytes_grown_to, ..Counts::default() }
+        }
+
+        #[test]
+        fn since_subtracts_each_counter() {
+            let before = Counts { reallocs: 1, bytes_grown_from: 7, bytes_grown_to: 14, ..counts(2, 30, 1, 12) };
+            let after = Counts { reallocs: 2, bytes_grown_from: 21, bytes_grown_to: 7, ..counts(5, 42, 1, 12) };
+            assert_eq!(after.since(before), Counts { reallocs: 1, bytes_grown_from: 14, bytes_grown_to: 0, ..counts(3, 12, 0, 0) });
+        }
+
+        #[test]
+        fn since_saturates_instead_of_panicking() {
+            assert_eq!(Counts::default().since(counts(1, 8, 1, 8)), Counts::default());
+        }
+
+        #[test]
+        fn describe_counts_blocks_and_frees() {
+            assert_eq!(Counts::default().describe(), "allocated nothing");
+            assert_eq!(counts(1, 12, 0, 0).describe(), "allocated 1 block of 12 bytes");
+            assert_eq!(counts(2, 19, 3, 33).describe(), "allocated 2 blocks, 19 bytes in total, freed 3 blocks, 33 bytes in total");
+        }
+
+        #[test]
+        fn describe_says_whether_a_realloc_grew_or_shrank() {
+            assert_eq!(resized(1, 7, 21).describe(), "allocated nothing, grew a block once, from 7 to 21 bytes");
+            assert_eq!(resized(1, 21, 7).describe(), "allocated nothing, shrank a block once, from 21 to 7 bytes");
+            assert_eq!(resized(3, 10, 40).describe(), "allocated nothing, grew a block 3 times, from 10 to 40 bytes in total");
+            assert_eq!((resized(1, 7, 21).bytes_grown(), resized(1, 21, 7).bytes_grown()), (14, -14));
+        }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: alloc_tracker::CountingAllocator = alloc_tracker::CountingAllocator;
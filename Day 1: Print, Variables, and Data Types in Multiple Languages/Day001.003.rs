@@ -8,14 +8,19 @@
 
 // Necessary imports will be introduced as needed, but some common ones for later examples:
 use std::collections::{HashMap, HashSet};
-use std::fmt::{Debug, Display};
+use std::fmt::{self, Debug, Display};
 use std::io; // For input/output examples
+use std::marker::PhantomData;
+use std::ops::{Add, Deref, DerefMut};
 use std::rc::Rc;
 use std::cell::{Cell, RefCell};
 use std::sync::{Arc, Mutex, RwLock};
-use std::thread;
 use std::time::Duration;
 
+#[allow(dead_code)] // This file moves no traced values; `moved_into` is for Day001.002.rs.
+mod drop_trace; // drop_trace.rs (next to this file): records when `Traced` values are created, moved and dropped.
+mod str_inspect; // str_inspect.rs: bytes, chars, grapheme clusters and valid slice indices of a string.
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // LEVEL 0: ABSOLUTE BASICS - THE "HELLO, WORLD" OF CONCEPTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -280,6 +285,7 @@ fn level_2_ownership_borrowing() {
     // 1. Each value in Rust has a variable that’s its *owner*.
     // 2. There can only be *one owner* at a time.
     // 3. When the owner goes out of scope, the value will be *dropped*.
+    // ownership_viz.rs draws this level's stack frames, heap blocks and borrows step by step.
 
     // Example with `String` (which owns heap data):
     let s1_owner = String::from("hello"); // s1_owner owns the String data "hello" on the heap.
@@ -986,6 +992,9 @@ fn level_5_collections_iterators() {
         print!("{} ", b);
     }
     println!();
+    // "स्ते" is 4 chars but one letter on screen (a grapheme cluster); std has no grapheme iterator.
+    // `--inspect-strings` shows the byte, char and grapheme view of both strings above.
+    str_inspect::print_reports(&[&s, &unicode_string], &[(&s, 7..8), (&unicode_string, 0..4), (&unicode_string, 0..6)]);
 
     // --- 5.3. `HashMap<K, V>`: Key-Value Store ---
     // Stores a mapping of keys of type `K` to values of type `V`.
@@ -1391,6 +1400,7 @@ impl<'a> Holder<'a> {
     // that doesn't live as long as `'a`, it would be an error.
 }
 
+// To see the scopes and live borrows below as a timeline: lifetime_timeline.rs.
 fn level_8_advanced_lifetimes() {
     println!("\n--- LEVEL 8: ADVANCED LIFETIMES AND BORROWING NUANCES ---");
     println!("--- 8.1. Explicit Lifetime Annotations ---");
@@ -1620,12 +1630,37 @@ fn level_9_smart_pointers_interior_mutability() {
     println!("  Leaf strong_count = {}, weak_count = {} (parent is weak)", Rc::strong_count(&leaf), Rc::weak_count(&leaf));
 
     // To access parent from leaf:
-    if let Some(parent_rc) = leaf.parent.borrow().upgrade() { // Tries to get an Rc from Weak
+    let parent = leaf.parent.borrow().upgrade(); // Tries to get an Rc from Weak (the RefCell borrow ends here)
+    if let Some(parent_rc) = parent {
         println!("  Leaf's parent value: {}", parent_rc.value);
     } else {
         println!("  Leaf's parent is no longer accessible.");
     }
     // If `branch` were dropped, `leaf.parent.borrow().upgrade()` would return `None`.
+
+    // The same tree with a traced name in each node, to watch who dies when.
+    // Run with `--trace-drops` to print the timeline.
+    struct TracedNode {
+        name: drop_trace::Traced<&'static str>,
+        parent: RefCell<Weak<TracedNode>>,
+        children: RefCell<Vec<Rc<TracedNode>>>,
+    }
+    drop_trace::section("9.5. Rc/Weak tree", || {
+        let leaf = Rc::new(TracedNode {
+            name: drop_trace::Traced::new("leaf", "leaf"),
+            parent: RefCell::new(Weak::new()),
+            children: RefCell::new(vec![]),
+        });
+        let branch = Rc::new(TracedNode {
+            name: drop_trace::Traced::new("branch", "branch"),
+            parent: RefCell::new(Weak::new()),
+            children: RefCell::new(vec![Rc::clone(&leaf)]),
+        });
+        *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
+        let parent_name = leaf.parent.borrow().upgrade().map(|p| *p.name);
+        println!("  Traced tree: {} has {} child(ren); leaf's parent is {:?}", *branch.name, branch.children.borrow().len(), parent_name);
+    }); // `branch` goes first and takes its node with it; the leaf node lives until `leaf` goes.
+    // Had `parent` been an `Rc`, the two nodes would keep each other alive and neither drop would appear.
 }
 
 
@@ -1634,22 +1669,614 @@ fn level_9_smart_pointers_interior_mutability() {
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Rust's ownership and type system provide "Fearless Concurrency".
 
+// Lesson time: every sleep, spawn and join in LEVEL 10 goes through this module.
+//   - Real mode (the default): plain `thread::sleep`, `thread::spawn`, `JoinHandle::join`.
+//     The interleaving of the threads' output changes from run to run.
+//   - Virtual mode (`--virtual-clock [--seed N]`): the demo threads still are real OS
+//     threads, but only one runs at a time and they only switch at `sleep`, `join`, `recv`
+//     or when a thread ends. Time is a counter that jumps straight to the next wake-up,
+//     so nothing really waits, and threads waking at the same instant are ordered by a
+//     seeded random generator. Same seed, same output; another seed, another interleaving.
+mod lesson_clock {
+    use std::sync::mpsc::{Receiver, TryRecvError};
+    use std::sync::{Condvar, Mutex, MutexGuard, OnceLock};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Mode {
+        Real,
+        Virtual { seed: u64 },
+    }
+
+    static MODE: OnceLock<Mode> = OnceLock::new();
+    static START: OnceLock<Instant> = OnceLock::new();
+
+    // Call once at startup; later calls are ignored. Without it the clock is real.
+    pub fn init(mode: Mode) {
+        let _ = MODE.set(mode);
+        START.get_or_init(Instant::now);
+    }
+
+    pub fn mode() -> Mode {
+        *MODE.get_or_init(|| Mode::Real)
+    }
+
+    // Time since `init`: wall-clock in real mode, the virtual counter otherwise.
+    pub fn now() -> Duration {
+        match mode() {
+            Mode::Real => START.get_or_init(Instant::now).elapsed(),
+            Mode::Virtual { .. } => scheduler().now,
+        }
+    }
+
+    // --- The virtual-mode scheduler ---
+    // Whoever is `running` holds the "baton"; everyone else waits on `TURN`.
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum ThreadState {
+        Ready { wake_at: Duration },
+        Joining(usize),  // Waiting for that thread to finish.
+        Receiving,       // Waiting for any other thread to make progress.
+        Finished,
+    }
+
+    struct Scheduler {
+        now: Duration,
+        running: usize,
+        threads: Vec<ThreadState>, // Index = lesson thread id; 0 is the main thread.
+        rng: u64,
+    }
+
+    static SCHEDULER: OnceLock<Mutex<Scheduler>> = OnceLock::new();
+    static TURN: Condvar = Condvar::new();
+
+    thread_local! {
+        static MY_ID: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    fn scheduler() -> MutexGuard<'static, Scheduler> {
+        let seed = match mode() {
+            Mode::Virtual { seed } => seed,
+            Mode::Real => unreachable!("the scheduler only exists in virtual mode"),
+        };
+        SCHEDULER.get_or_init(|| Mutex::new(Scheduler::new(seed))).lock().unwrap()
+    }
+
+    impl Scheduler {
+        // Only the main thread so far, running at time zero.
+        fn new(seed: u64) -> Scheduler {
+            Scheduler {
+                now: Duration::ZERO,
+                running: 0,
+                threads: vec![ThreadState::Ready { wake_at: Duration::ZERO }],
+                // xorshift must not start at 0, which this seed alone would give.
+                rng: match seed ^ 0x9E37_79B9_7F4A_7C15 {
+                    0 => 0x9E37_79B9_7F4A_7C15,
+                    state => state,
+                },
+            }
+        }
+
+        // xorshift64: small, fast, and the same sequence on every machine.
+        fn next_random(&mut self) -> u64 {
+            self.rng ^= self.rng << 13;
+            self.rng ^= self.rng >> 7;
+            self.rng ^= self.rng << 17;
+            self.rng
+        }
+
+        // Hands the baton from `me` to the thread that wakes up first.
+        fn switch_from(&mut self, me: usize) {
+            for (id, state) in self.threads.iter_mut().enumerate() {
+                if id != me && *state == ThreadState::Receiving {
+                    *state = ThreadState::Ready { wake_at: self.now }; // `me` may have sent something.
+                }
+            }
+            let earliest = self.threads.iter().filter_map(|s| match s {
+                ThreadState::Ready { wake_at } => Some(*wake_at),
+                _ => None,
+            }).min();
+            let Some(earliest) = earliest else {
+                if self.threads.iter().all(|s| *s == ThreadState::Finished) {
+                    return;
+                }
+                panic!("lesson_clock: every thread is waiting on another one (deadlock)");
+            };
+            let candidates: Vec<usize> = (0..self.threads.len())
+                .filter(|&id| self.threads[id] == ThreadState::Ready { wake_at: earliest })
+                .collect();
+            let pick = (self.next_random() % candidates.len() as u64) as usize;
+            self.now = earliest;
+            self.running = candidates[pick];
+            TURN.notify_all();
+        }
+    }
+
+    fn wait_for_turn(mut sched: MutexGuard<'static, Scheduler>, me: usize) {
+        while sched.running != me {
+            sched = TURN.wait(sched).unwrap();
+        }
+    }
+
+    fn block(me: usize, state: ThreadState) {
+        let mut sched = scheduler();
+        sched.threads[me] = state;
+        sched.switch_from(me);
+        wait_for_turn(sched, me);
+    }
+
+    // Marks the thread finished when its closure returns or panics.
+    struct FinishGuard(usize);
+
+    impl Drop for FinishGuard {
+        fn drop(&mut self) {
+            let mut sched = scheduler();
+            let now = sched.now;
+            sched.threads[self.0] = ThreadState::Finished;
+            for state in sched.threads.iter_mut() {
+                if *state == ThreadState::Joining(self.0) {
+                    *state = ThreadState::Ready { wake_at: now };
+                }
+            }
+            sched.switch_from(self.0);
+        }
+    }
+
+    // --- The lesson-facing API: drop-in replacements for the std calls ---
+    // In virtual mode these must be called from the main thread or from threads
+    // started with `lesson_clock::spawn`, never from a plain `thread::spawn` thread.
+
+    pub fn sleep(duration: Duration) {
+        match mode() {
+            Mode::Real => thread::sleep(duration),
+            Mode::Virtual { .. } => {
+                let me = MY_ID.with(|id| id.get());
+                let wake_at = scheduler().now + duration;
+                block(me, ThreadState::Ready { wake_at });
+            }
+        }
+    }
+
+    pub struct JoinHandle<T> {
+        inner: thread::JoinHandle<T>,
+        id: Option<usize>, // Lesson thread id in virtual mode.
+    }
+
+    impl<T> JoinHandle<T> {
+        pub fn join(self) -> thread::Result<T> {
+            if let Some(target) = self.id {
+                let me = MY_ID.with(|id| id.get());
+                let finished = scheduler().threads[target] == ThreadState::Finished;
+                if !finished {
+                    block(me, ThreadState::Joining(target));
+                }
+            }
+            self.inner.join()
+        }
+    }
+
+    pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        match mode() {
+            Mode::Real => JoinHandle { inner: thread::spawn(f), id: None },
+            Mode::Virtual { .. } => {
+                let id = {
+                    let mut sched = scheduler();
+                    let now = sched.now;
+                    sched.threads.push(ThreadState::Ready { wake_at: now });
+                    sched.threads.len() - 1
+                };
+                let inner = thread::spawn(move || {
+                    MY_ID.with(|my| my.set(id));
+                    wait_for_turn(scheduler(), id);
+                    let _finish = FinishGuard(id);
+                    f()
+                });
+                JoinHandle { inner, id: Some(id) }
+            }
+        }
+    }
+
+    // `rx.recv().ok()`: blocks until a message arrives or every sender is gone.
+    pub fn recv<T>(rx: &Receiver<T>) -> Option<T> {
+        match mode() {
+            Mode::Real => rx.recv().ok(),
+            Mode::Virtual { .. } => loop {
+                match rx.try_recv() {
+                    Ok(value) => return Some(value),
+                    Err(TryRecvError::Disconnected) => return None,
+                    Err(TryRecvError::Empty) => block(MY_ID.with(|id| id.get()), ThreadState::Receiving),
+                }
+            },
+        }
+    }
+
+    // Run with: rustc --edition 2021 --test --crate-name day001_003 Day001.003.rs -o target/day001_003_tests && target/day001_003_tests
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // Drives the scheduler the way the 10.x demos do, without real threads: the main
+        // thread and two spawned ones each sleep 10ms four times, so at every wake-up all
+        // three are due at once and the seeded generator decides who goes first.
+        // Returns the lesson thread ids in the order they got the baton.
+        fn interleaving(seed: u64) -> Vec<usize> {
+            let mut sched = Scheduler::new(seed);
+            sched.threads.extend([ThreadState::Ready { wake_at: Duration::ZERO }; 2]);
+            let mut turns = [0; 3];
+            let mut order = Vec::new();
+            while sched.threads.iter().any(|s| *s != ThreadState::Finished) {
+                let me = sched.running;
+                order.push(me);
+                turns[me] += 1;
+                sched.threads[me] = if turns[me] == 4 {
+                    ThreadState::Finished
+                } else {
+                    ThreadState::Ready { wake_at: sched.now + Duration::from_millis(10) }
+                };
+                sched.switch_from(me);
+            }
+            order
+        }
+
+        #[test]
+        fn same_seed_same_interleaving() {
+            for seed in [0, 1, 42, u64::MAX] {
+                let first = interleaving(seed);
+                assert_eq!(first.len(), 12, "seed {}: {:?}", seed, first);
+                assert_eq!(interleaving(seed), first, "seed {}", seed);
+            }
+        }
+
+        #[test]
+        fn other_seeds_give_other_interleavings() {
+            let first = interleaving(0);
+            assert!((1..16).any(|seed| interleaving(seed) != first));
+        }
+
+        #[test]
+        fn no_seed_leaves_the_generator_stuck_at_zero() {
+            let mut sched = Scheduler::new(0x9E37_79B9_7F4A_7C15);
+            assert_ne!((sched.next_random(), sched.next_random()), (0, 0));
+        }
+
+        #[test]
+        fn time_only_moves_to_the_next_wake_up() {
+            let mut sched = Scheduler::new(7);
+            sched.threads.push(ThreadState::Ready { wake_at: Duration::from_millis(5) });
+            sched.threads[0] = ThreadState::Ready { wake_at: Duration::from_millis(10) };
+            sched.switch_from(0);
+            assert_eq!((sched.running, sched.now), (1, Duration::from_millis(5)));
+            sched.threads[1] = ThreadState::Finished;
+            sched.switch_from(1);
+            assert_eq!((sched.running, sched.now), (0, Duration::from_millis(10)));
+        }
+    }
+}
+
+// A tiny async executor, used by 10.5 so the `async` examples really run without tokio.
+// It is also a lesson of its own: this is roughly what a runtime does, minus I/O and threads.
+//   - A `Future` does nothing until someone calls its `poll`. `Poll::Pending` means
+//     "not yet, I will tell you when to try again" through the `Waker` in the `Context`.
+//   - The executor keeps a queue of tasks that were woken, and polls them one by one.
+//   - When nothing is ready, it sleeps until the earliest timer is due.
+mod teaching_executor {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::future::Future;
+    use std::pin::{pin, Pin};
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::time::Duration;
+
+    use crate::lesson_clock;
+
+    type Task = Pin<Box<dyn Future<Output = ()>>>;
+    type TimerWaker = Rc<RefCell<Waker>>; // Shared with the `Sleep`, which may swap the waker.
+    const MAIN_TASK: usize = usize::MAX; // The future given to `block_on` (it lives on its stack).
+
+    // Waking a task = putting its id back on the ready queue. A `Waker` may be sent to
+    // other threads, so the queue sits behind `Arc<Mutex<..>>` even though we poll on one.
+    struct TaskWaker {
+        id: usize,
+        ready: Arc<Mutex<VecDeque<usize>>>,
+    }
+
+    impl Wake for TaskWaker {
+        fn wake(self: Arc<Self>) {
+            self.wake_by_ref();
+        }
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.ready.lock().unwrap().push_back(self.id);
+        }
+    }
+
+    struct Executor {
+        ready: Arc<Mutex<VecDeque<usize>>>,
+        tasks: RefCell<Vec<Option<Task>>>, // Indexed by task id; `None` once finished.
+        timers: RefCell<Vec<(Duration, TimerWaker)>>, // Deadlines in `lesson_clock` time.
+    }
+
+    impl Executor {
+        fn waker(&self, id: usize) -> Waker {
+            Waker::from(Arc::new(TaskWaker { id, ready: Arc::clone(&self.ready) }))
+        }
+
+        fn poll_task(&self, id: usize) {
+            // Take the task out while polling it, so it can `spawn` (which pushes to `tasks`).
+            let Some(mut task) = self.tasks.borrow_mut()[id].take() else {
+                return; // Woken again after it already finished.
+            };
+            let waker = self.waker(id);
+            if task.as_mut().poll(&mut Context::from_waker(&waker)).is_pending() {
+                self.tasks.borrow_mut()[id] = Some(task);
+            }
+        }
+
+        // Nothing is ready: sleep until the earliest timer, then wake every timer that is due.
+        fn wait_for_timers(&self) {
+            let mut timers = self.timers.borrow_mut();
+            let earliest = timers.iter().map(|(deadline, _)| *deadline).min()
+                .expect("block_on: deadlock, no task is ready and no timer is pending");
+            let now = lesson_clock::now();
+            if earliest > now {
+                lesson_clock::sleep(earliest - now); // Instant under `--virtual-clock`.
+            }
+            let now = lesson_clock::now();
+            timers.retain(|(deadline, waker)| {
+                if *deadline <= now {
+                    waker.borrow().wake_by_ref();
+                }
+                *deadline > now
+            });
+        }
+    }
+
+    thread_local! {
+        // The executor running on this thread, so `spawn` and `sleep` can find it.
+        static CURRENT: RefCell<Option<Rc<Executor>>> = const { RefCell::new(None) };
+    }
+
+    fn current() -> Rc<Executor> {
+        CURRENT.with(|c| c.borrow().clone()).expect("must be called inside teaching_executor::block_on")
+    }
+
+    // Runs `future` (and everything it spawns) on this thread until `future` completes.
+    // Spawned tasks that are still unfinished at that point are dropped.
+    pub fn block_on<F: Future>(future: F) -> F::Output {
+        let executor = Rc::new(Executor {
+            ready: Arc::new(Mutex::new(VecDeque::from([MAIN_TASK]))),
+            tasks: RefCell::new(Vec::new()),
+            timers: RefCell::new(Vec::new()),
+        });
+        let previous = CURRENT.with(|c| c.replace(Some(Rc::clone(&executor))));
+        let mut future = pin!(future); // Pinned: a future may hold references into itself.
+        let main_waker = executor.waker(MAIN_TASK);
+        let output = loop {
+            let next = executor.ready.lock().unwrap().pop_front();
+            match next {
+                Some(MAIN_TASK) => {
+                    if let Poll::Ready(output) = future.as_mut().poll(&mut Context::from_waker(&main_waker)) {
+                        break output;
+                    }
+                }
+                Some(id) => executor.poll_task(id),
+                None => executor.wait_for_timers(),
+            }
+        };
+        CURRENT.with(|c| c.replace(previous));
+        output
+    }
+
+    // The result of a spawned task, shared between the task and its `JoinHandle`.
+    struct JoinState<T> {
+        output: Option<T>,
+        waiter: Option<Waker>,
+    }
+
+    pub struct JoinHandle<T> {
+        state: Rc<RefCell<JoinState<T>>>,
+    }
+
+    impl<T> Future for JoinHandle<T> {
+        type Output = T;
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+            let mut state = self.state.borrow_mut();
+            match state.output.take() {
+                Some(output) => Poll::Ready(output),
+                None => {
+                    state.waiter = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    // Starts `future` as a separate task. It makes progress whenever the current task
+    // is waiting, even if nobody awaits the handle yet.
+    pub fn spawn<F: Future + 'static>(future: F) -> JoinHandle<F::Output> {
+        let executor = current();
+        let state = Rc::new(RefCell::new(JoinState { output: None, waiter: None }));
+        let task_state = Rc::clone(&state);
+        let task = async move {
+            let output = future.await;
+            let mut state = task_state.borrow_mut();
+            state.output = Some(output);
+            if let Some(waiter) = state.waiter.take() {
+                waiter.wake();
+            }
+        };
+        let mut tasks = executor.tasks.borrow_mut();
+        tasks.push(Some(Box::pin(task)));
+        executor.ready.lock().unwrap().push_back(tasks.len() - 1);
+        JoinHandle { state }
+    }
+
+    // Polls both futures each time either one is woken, and finishes when both have.
+    // This is concurrency without threads: the two take turns inside one task.
+    pub async fn join<A: Future, B: Future>(a: A, b: B) -> (A::Output, B::Output) {
+        let (mut a, mut b) = (pin!(a), pin!(b));
+        let (mut out_a, mut out_b) = (None, None);
+        std::future::poll_fn(|cx| {
+            if out_a.is_none() {
+                if let Poll::Ready(v) = a.as_mut().poll(cx) {
+                    out_a = Some(v);
+                }
+            }
+            if out_b.is_none() {
+                if let Poll::Ready(v) = b.as_mut().poll(cx) {
+                    out_b = Some(v);
+                }
+            }
+            if out_a.is_some() && out_b.is_some() {
+                Poll::Ready((out_a.take().unwrap(), out_b.take().unwrap()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    // A timer future: pending until `duration` has passed, without blocking the thread.
+    pub fn sleep(duration: Duration) -> Sleep {
+        Sleep { deadline: lesson_clock::now() + duration, timer: None }
+    }
+
+    pub struct Sleep {
+        deadline: Duration,
+        timer: Option<TimerWaker>, // Set once the executor knows about this deadline.
+    }
+
+    impl Future for Sleep {
+        type Output = ();
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if lesson_clock::now() >= self.deadline {
+                return Poll::Ready(());
+            }
+            // Not yet: ask the executor to wake us at the deadline. Polled again before then
+            // (`join` polls both halves when either is woken), only the waker is refreshed.
+            match &self.timer {
+                Some(timer) => timer.borrow_mut().clone_from(cx.waker()),
+                None => {
+                    let timer = Rc::new(RefCell::new(cx.waker().clone()));
+                    current().timers.borrow_mut().push((self.deadline, Rc::clone(&timer)));
+                    self.get_mut().timer = Some(timer);
+                }
+            }
+            Poll::Pending
+        }
+    }
+
+    // Run with: rustc --edition 2021 --test --crate-name day001_003 Day001.003.rs -o target/day001_003_tests && target/day001_003_tests
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::sync::MutexGuard;
+
+        // The virtual clock is one per process: tests that move it take turns.
+        fn virtual_clock() -> MutexGuard<'static, ()> {
+            static CLOCK: Mutex<()> = Mutex::new(());
+            let guard = CLOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            lesson_clock::init(lesson_clock::Mode::Virtual { seed: 1 });
+            guard
+        }
+
+        fn ms(n: u64) -> Duration {
+            Duration::from_millis(n)
+        }
+
+        #[test]
+        fn block_on_returns_the_output_once_the_timer_is_due() {
+            let _clock = virtual_clock();
+            let start = lesson_clock::now();
+            assert_eq!(block_on(async { sleep(ms(30)).await; 7 }), 7);
+            assert_eq!(lesson_clock::now() - start, ms(30));
+        }
+
+        #[test]
+        fn join_waits_for_the_later_half_only() {
+            let _clock = virtual_clock();
+            let start = lesson_clock::now();
+            let log = RefCell::new(Vec::new());
+            let halves = block_on(join(
+                async { sleep(ms(20)).await; log.borrow_mut().push("slow"); 1 },
+                async { sleep(ms(10)).await; log.borrow_mut().push("fast"); 2 },
+            ));
+            assert_eq!(halves, (1, 2));
+            assert_eq!(log.into_inner(), ["fast", "slow"]);
+            assert_eq!(lesson_clock::now() - start, ms(20)); // Not 30: they slept side by side.
+        }
+
+        #[test]
+        fn spawned_tasks_finish_in_deadline_order_then_spawn_order() {
+            let _clock = virtual_clock();
+            let log = Rc::new(RefCell::new(Vec::new()));
+            let outputs = block_on(async {
+                let handles: Vec<_> = [(1, 30), (2, 10), (3, 20), (4, 10)]
+                    .into_iter()
+                    .map(|(id, delay)| {
+                        let log = Rc::clone(&log);
+                        spawn(async move {
+                            sleep(ms(delay)).await;
+                            log.borrow_mut().push(id);
+                            id * 100
+                        })
+                    })
+                    .collect();
+                let mut outputs = Vec::new();
+                for handle in handles {
+                    outputs.push(handle.await);
+                }
+                outputs
+            });
+            assert_eq!(outputs, [100, 200, 300, 400]);
+            assert_eq!(*log.borrow(), [2, 4, 3, 1]);
+        }
+
+        #[test]
+        fn a_sleep_polled_again_keeps_one_timer() {
+            let _clock = virtual_clock();
+            block_on(async {
+                let mut nap = pin!(sleep(ms(10)));
+                for _ in 0..3 {
+                    let pending = std::future::poll_fn(|cx| Poll::Ready(nap.as_mut().poll(cx).is_pending())).await;
+                    assert!(pending);
+                }
+                assert_eq!(current().timers.borrow().len(), 1);
+                nap.await;
+                assert!(current().timers.borrow().is_empty());
+            });
+        }
+    }
+}
+
 fn level_10_concurrency() {
     println!("\n--- LEVEL 10: CONCURRENCY ---");
 
     // --- 10.1. Threads (`std::thread::spawn`) ---
     // `spawn` creates a new OS thread. It takes a closure.
     // The closure must take ownership of any captured variables (`move` keyword).
+    // The demos below call `lesson_clock::spawn` / `lesson_clock::sleep` (defined above) instead of
+    // `thread::spawn` / `thread::sleep`. In the default real mode they are exactly those calls;
+    // run with `--virtual-clock --seed N` for a fast, reproducible interleaving.
     println!("--- 10.1. Threads ---");
-    let handle1 = thread::spawn(|| { // No `move` here, as no captured variables are used.
+    if let lesson_clock::Mode::Virtual { seed } = lesson_clock::mode() {
+        println!("  (virtual clock, seed {}: this interleaving is the same on every run)", seed);
+    }
+    let handle1 = lesson_clock::spawn(|| { // No `move` here, as no captured variables are used.
         for i in 1..=5 {
             println!("  Spawned thread 1 says: {}", i);
-            thread::sleep(Duration::from_millis(10));
+            lesson_clock::sleep(Duration::from_millis(10));
         }
     });
 
     let data_for_thread = String::from("hello from main thread");
-    let handle2 = thread::spawn(move || { // `move` takes ownership of `data_for_thread`.
+    let handle2 = lesson_clock::spawn(move || { // `move` takes ownership of `data_for_thread`.
         println!("  Spawned thread 2 received: '{}'", data_for_thread);
         // println!("{}", data_for_thread); // `data_for_thread` is still owned by closure here
     });
@@ -1658,7 +2285,7 @@ fn level_10_concurrency() {
     // Main thread continues execution.
     for i in 1..=3 {
         println!("Main thread says: {}", i);
-        thread::sleep(Duration::from_millis(5));
+        lesson_clock::sleep(Duration::from_millis(5));
     }
 
     // `join()` waits for a thread to finish. Returns a `Result`.
@@ -1672,7 +2299,7 @@ fn level_10_concurrency() {
     let (tx, rx) = std::sync::mpsc::channel(); // tx = transmitter, rx = receiver
 
     let tx_clone = tx.clone(); // Clone transmitter to send from multiple threads if needed.
-    thread::spawn(move || {
+    lesson_clock::spawn(move || {
         let vals = vec![
             String::from("hi from thread A"),
             String::from("more messages"),
@@ -1681,12 +2308,12 @@ fn level_10_concurrency() {
         for val in vals {
             println!("  Thread A sending: '{}'", val);
             tx.send(val).unwrap(); // `send` takes ownership of `val`.
-            thread::sleep(Duration::from_millis(10));
+            lesson_clock::sleep(Duration::from_millis(10));
         }
         // `tx` is dropped when thread A finishes.
     });
 
-    thread::spawn(move || {
+    lesson_clock::spawn(move || {
         let val = String::from("message from thread B");
         println!("  Thread B sending: '{}'", val);
         tx_clone.send(val).unwrap();
@@ -1695,9 +2322,10 @@ fn level_10_concurrency() {
 
     // `rx.recv()` blocks until a message is received. Returns `Result`.
     // `rx.try_recv()` is non-blocking.
-    // The receiver can also be used as an iterator.
+    // The receiver can also be used as an iterator: `for received_msg in rx { ... }`.
+    // `lesson_clock::recv(&rx)` is `rx.recv().ok()`, plus a switch point for the virtual clock.
     println!("  Main thread receiving messages:");
-    for received_msg in rx { // Loop continues until all `tx` are dropped and channel is empty.
+    while let Some(received_msg) = lesson_clock::recv(&rx) { // Ends once all `tx` are dropped and the channel is empty.
         println!("  Main received: {}", received_msg);
     }
     println!("  Channel closed, all messages received.");
@@ -1717,7 +2345,7 @@ fn level_10_concurrency() {
 
     for i in 0..5 { // Spawn 5 threads
         let counter_clone = Arc::clone(&counter_shared); // Clone Arc for each thread
-        let handle = thread::spawn(move || {
+        let handle = lesson_clock::spawn(move || {
             // `lock()` acquires the mutex. Blocks if another thread holds the lock.
             // Returns a `Result<MutexGuard<T>, PoisonError>`.
             // `MutexGuard` is a smart pointer that dereferences to `T` and unlocks on drop.
@@ -1756,29 +2384,33 @@ fn level_10_concurrency() {
     // --- 10.5. Basic `async/await` (Introduction to Asynchronous Programming) ---
     // For non-blocking I/O operations, handling many concurrent tasks efficiently
     // without needing many OS threads.
-    // Requires an async runtime (e.g., `tokio`, `async-std`).
-    // This is a very brief conceptual intro; full async is a large topic.
-    println!("\n--- 10.5. `async/await` (Conceptual Intro) ---");
+    // Requires an async runtime (e.g., `tokio`, `async-std`). Here we use the small
+    // `teaching_executor` defined above LEVEL 10 instead, so the examples really run.
+    println!("\n--- 10.5. `async/await` ---");
     // `async fn` defines an asynchronous function, which returns a `Future`.
+    // Calling it runs nothing yet: the body only runs when the future is polled.
     // `await` pauses execution of the async fn until the `Future` is ready.
-    async fn fetch_data_async(url: &str) -> Result<String, String> {
+    async fn fetch_data_async(url: &str, delay_ms: u64) -> Result<String, String> {
         println!("    Async: Starting to fetch data from {}", url);
         // In a real scenario, this would involve a non-blocking network call.
-        // We simulate it with a sleep.
-        // `tokio::time::sleep(Duration::from_secs(1)).await;` // If using tokio
-        // std::thread::sleep(Duration::from_secs(1)); // This would block the executor thread if not careful
+        // We simulate it with a timer future: the task is parked, the thread is not.
+        // std::thread::sleep(...) here would block every other task on this executor!
+        teaching_executor::sleep(Duration::from_millis(delay_ms)).await;
+        if url.starts_with("bad.") {
+            return Err(format!("could not reach {}", url));
+        }
         println!("    Async: Finished fetching data from {}", url);
         Ok(format!("Data from {}", url))
     }
 
     async fn main_async_logic() {
         println!("  Running async logic...");
-        let future1 = fetch_data_async("url1.com");
-        let future2 = fetch_data_async("url2.com");
+        let future1 = fetch_data_async("url1.com", 30);
+        let future2 = fetch_data_async("url2.com", 10);
+        println!("  Two futures created; nothing has been fetched yet (futures are lazy).");
 
-        // `join!` (from futures crate or tokio) can run multiple futures concurrently.
-        // let (result1, result2) = futures::join!(future1, future2); // If using futures crate
-        // For this simple demo, we'll await them sequentially.
+        // Sequential: the second fetch only starts after the first finished.
+        println!("  Sequential `.await`s:");
         match future1.await {
             Ok(data) => println!("    Async result 1: {}", data),
             Err(e) => println!("    Async error 1: {}", e),
@@ -1787,13 +2419,30 @@ fn level_10_concurrency() {
             Ok(data) => println!("    Async result 2: {}", data),
             Err(e) => println!("    Async error 2: {}", e),
         }
+
+        // Concurrent: `join` polls both, so both fetches are in flight at once and
+        // url2 (the shorter one) finishes first even though it was started second.
+        // (With tokio or the futures crate this is `join!(future1, future2)`.)
+        println!("  Concurrent with `join`:");
+        let (result1, result2) = teaching_executor::join(
+            fetch_data_async("url1.com", 30),
+            fetch_data_async("url2.com", 10),
+        )
+        .await;
+        println!("    Async results: {:?}, {:?}", result1, result2);
+
+        // `spawn` starts an independent task; the handle is itself a future for its result.
+        println!("  Spawned task:");
+        let handle = teaching_executor::spawn(fetch_data_async("bad.example", 5));
+        println!("    Spawned; main task keeps going until it awaits the handle.");
+        match handle.await {
+            Ok(data) => println!("    Spawned result: {}", data),
+            Err(e) => println!("    Spawned task error: {}", e),
+        }
     }
-    // To actually run `main_async_logic`, you need an async runtime:
-    // For example, with tokio:
-    // #[tokio::main]
-    // async fn main() { level_10_concurrency(); main_async_logic().await; }
-    // For this single file demo, we can't easily integrate a full async runtime.
-    println!("  (Full async execution requires an async runtime like tokio or async-std)");
+    // `block_on` is the bridge from normal code into async code. With tokio this would be
+    // `#[tokio::main] async fn main()` or `tokio::runtime::Runtime::new()?.block_on(...)`.
+    teaching_executor::block_on(main_async_logic());
 }
 
 
@@ -1930,14 +2579,145 @@ fn level_11_advanced_traits_types() {
 // It's needed for low-level programming, FFI, or specific performance optimizations.
 // You are responsible for upholding memory safety within `unsafe` blocks.
 
-// For FFI example, we need to tell Rust how to link with C functions.
-// This usually requires a build script or specific linker flags.
-// For this single-file demo, we'll show the Rust side.
-#[cfg(feature = "ffi_example")] // Only compile if a feature flag is set (not by default here)
+// For FFI, we need to tell Rust how to link with C functions.
+// `abs` lives in the C standard library, which every Rust program on Linux already links,
+// so declaring it is enough.
 extern "C" {
     fn abs(input: i32) -> i32; // Declare an external C function
 }
 
+// The rest of the FFI lesson calls our own C code in `ffi_lesson.c` (next to this file).
+// `./build_ffi_lesson.sh` compiles it into `libffi_lesson.a` and runs rustc with
+// `--cfg ffi_lesson`, which switches this module on. A plain `rustc` build skips it.
+#[cfg(ffi_lesson)]
+mod ffi_lesson {
+    use std::ffi::{CString, NulError};
+    use std::os::raw::{c_char, c_void};
+
+    // Layer 1: raw declarations, one-to-one with ffi_lesson.c.
+    // The compiler trusts these signatures blindly; a typo here is undefined behaviour.
+    mod sys {
+        use std::os::raw::{c_char, c_int, c_void};
+
+        #[repr(C)] // Lay the fields out exactly like the C struct.
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub struct Point2D {
+            pub x: f64,
+            pub y: f64,
+        }
+
+        // A C function pointer: `int64_t (*)(int64_t, int32_t, void*)`.
+        pub type FoldFn = extern "C" fn(acc: i64, value: i32, user: *mut c_void) -> i64;
+
+        #[link(name = "ffi_lesson", kind = "static")]
+        extern "C" {
+            pub fn point_distance(a: Point2D, b: Point2D) -> f64;
+            pub fn point_scale(p: *mut Point2D, factor: f64);
+            pub fn count_vowels(s: *const c_char) -> usize;
+            pub fn write_greeting(name: *const c_char, out: *mut c_char, out_len: usize) -> c_int;
+            pub fn fold_ints(data: *const i32, len: usize, init: i64, op: FoldFn, user: *mut c_void) -> i64;
+        }
+    }
+
+    pub use sys::Point2D;
+
+    // Layer 2: safe wrappers. Each one upholds the C side's contract once, here,
+    // so callers never need `unsafe`.
+
+    pub fn distance(a: Point2D, b: Point2D) -> f64 {
+        unsafe { sys::point_distance(a, b) } // Plain values in, plain value out: nothing to uphold.
+    }
+
+    pub fn scale(p: &mut Point2D, factor: f64) {
+        unsafe { sys::point_scale(p, factor) } // `&mut` coerces to a valid, non-null, exclusive `*mut`.
+    }
+
+    // Rust strings are not NUL-terminated and may contain '\0'; `CString` fixes the first
+    // and rejects the second.
+    pub fn count_vowels(s: &str) -> Result<usize, NulError> {
+        let c_string = CString::new(s)?;
+        Ok(unsafe { sys::count_vowels(c_string.as_ptr()) }) // `c_string` outlives the call.
+    }
+
+    // C writes into memory Rust owns. We pass the real buffer length, and grow the
+    // buffer and retry if C reports that the text did not fit.
+    pub fn greeting(name: &str) -> Result<String, NulError> {
+        let c_name = CString::new(name)?;
+        let mut buf = vec![0u8; 16]; // Deliberately small, to show the retry.
+        loop {
+            let needed = unsafe { sys::write_greeting(c_name.as_ptr(), buf.as_mut_ptr() as *mut c_char, buf.len()) };
+            let needed = usize::try_from(needed).expect("snprintf reported an encoding error");
+            if needed < buf.len() {
+                buf.truncate(needed);
+                return Ok(String::from_utf8_lossy(&buf).into_owned());
+            }
+            buf.resize(needed + 1, 0);
+        }
+    }
+
+    // Callbacks: C can only call a plain `extern "C" fn`, not a closure. The usual trick
+    // is a generic "trampoline" function, plus a `void*` that smuggles the closure through C.
+    // Note: a panic inside the closure cannot unwind through C; Rust aborts the process instead.
+    pub fn fold<F: FnMut(i64, i32) -> i64>(data: &[i32], init: i64, mut f: F) -> i64 {
+        extern "C" fn trampoline<F: FnMut(i64, i32) -> i64>(acc: i64, value: i32, user: *mut c_void) -> i64 {
+            let f = unsafe { &mut *(user as *mut F) }; // `user` is the `&mut f` passed below.
+            f(acc, value)
+        }
+        unsafe { sys::fold_ints(data.as_ptr(), data.len(), init, trampoline::<F>, &mut f as *mut F as *mut c_void) }
+    }
+
+    // Run with: ./build_ffi_lesson.sh --test (a plain `rustc --test` leaves this module out).
+    #[cfg(all(test, ffi_lesson))]
+    mod tests {
+        use super::*;
+        use std::ptr;
+
+        #[test]
+        fn c_checks_for_null_and_cstring_rejects_interior_nul() {
+            assert_eq!(unsafe { sys::count_vowels(ptr::null()) }, 0);
+            unsafe { sys::point_scale(ptr::null_mut(), 2.0) }; // Returns without touching anything.
+            assert!(count_vowels("bad\0string").is_err());
+            assert!(greeting("nul\0name").is_err());
+            assert_eq!(count_vowels("Ferris the Rustacean"), Ok(7));
+        }
+
+        #[test]
+        fn greeting_retries_with_the_length_c_reports() {
+            assert_eq!(greeting("").unwrap(), "Hello from C, !"); // 15 bytes + NUL: fits the first buffer.
+            assert_eq!(greeting("X").unwrap(), "Hello from C, X!"); // 16 bytes: one short, retried.
+            assert_eq!(greeting("Ferris the Rustacean").unwrap(), "Hello from C, Ferris the Rustacean!");
+
+            // C writes no more than it is told it may, and still reports the full length.
+            let name = CString::new("Ferris").unwrap();
+            let mut buf = [b'#'; 8];
+            let needed = unsafe { sys::write_greeting(name.as_ptr(), buf.as_mut_ptr() as *mut c_char, 4) };
+            assert_eq!(needed, 21);
+            assert_eq!(&buf, b"Hel\0####");
+        }
+
+        #[test]
+        fn structs_cross_by_value_and_by_pointer() {
+            assert_eq!(distance(Point2D { x: 0.0, y: 0.0 }, Point2D { x: 3.0, y: 4.0 }), 5.0);
+            let mut p = Point2D { x: 1.5, y: -2.0 };
+            scale(&mut p, 2.0);
+            assert_eq!(p, Point2D { x: 3.0, y: -4.0 });
+        }
+
+        #[test]
+        fn the_closure_goes_through_c_and_back() {
+            // The closure borrows `seen`; C only ever holds it as a `void*` and hands it back.
+            let mut seen = Vec::new();
+            let total = fold(&[3, 1, 4], 10, |acc, v| {
+                seen.push(v);
+                acc + i64::from(v)
+            });
+            assert_eq!(total, 18);
+            assert_eq!(seen, [3, 1, 4]);
+            assert_eq!(fold(&[], 7, |_, _| unreachable!("C calls nothing for an empty slice")), 7);
+        }
+    }
+}
+
 fn level_12_unsafe_ffi() {
     println!("\n--- LEVEL 12: UNSAFE RUST AND FFI ---");
     println!("  `unsafe` Rust bypasses some safety checks. Use with extreme caution!");
@@ -1985,19 +2765,48 @@ fn level_12_unsafe_ffi() {
     }
 
     // FFI (Foreign Function Interface) - Calling C code
-    // (This requires linking with the C library where `abs` is defined.
-    //  The `#[cfg(feature = "ffi_example")]` means it won't compile unless that feature is enabled,
-    //  which we are not doing in this simple single-file setup).
-    println!("  FFI Example (conceptual, linking not set up here):");
-    #[cfg(feature = "ffi_example_runnable")] // To actually run, you'd need to set this up.
+    // Every call into C is `unsafe`: Rust cannot check what the C code does.
+    println!("  FFI Example: calling C from Rust");
+    let x = -3;
+    unsafe {
+        println!("    Absolute value of {} (from the C standard library) is: {}", x, abs(x));
+    }
+
+    // Our own C library, through the safe wrapper layer in `mod ffi_lesson`.
+    // No `unsafe` in sight here: the wrappers took care of it.
+    #[cfg(ffi_lesson)]
     {
-        let x = -3;
-        unsafe {
-            println!("    Absolute value of {} (from C) is: {}", x, abs(x));
+        use ffi_lesson::Point2D;
+        let a = Point2D { x: 0.0, y: 0.0 };
+        let mut b = Point2D { x: 3.0, y: 4.0 };
+        println!("    Struct by value:   distance({:?}, {:?}) = {}", a, b, ffi_lesson::distance(a, b));
+        ffi_lesson::scale(&mut b, 2.0);
+        println!("    Struct by pointer: after point_scale(&b, 2.0), b = {:?}", b);
+
+        let text = "Rust and C, together";
+        match ffi_lesson::count_vowels(text) {
+            Ok(n) => println!("    String in:  count_vowels({:?}) = {}", text, n),
+            Err(e) => println!("    String in:  error: {}", e),
+        }
+        // An interior NUL byte would silently cut the string short in C; the wrapper refuses it.
+        if let Err(e) = ffi_lesson::count_vowels("bad\0string") {
+            println!("    String in:  \"bad\\0string\" rejected before reaching C: {}", e);
         }
+        match ffi_lesson::greeting("Ferris the Rustacean") {
+            Ok(s) => println!("    String out: {:?} (C wrote into a Rust-owned buffer)", s),
+            Err(e) => println!("    String out: error: {}", e),
+        }
+
+        let numbers = [1, 2, 3, 4, 5];
+        let mut calls = 0; // Captured state: proves C really called our closure each time.
+        let sum_of_squares = ffi_lesson::fold(&numbers, 0, |acc, v| {
+            calls += 1;
+            acc + i64::from(v) * i64::from(v)
+        });
+        println!("    Callback:   fold_ints({:?}, acc + v*v) = {} ({} calls from C into Rust)", numbers, sum_of_squares, calls);
     }
-    #[cfg(not(feature = "ffi_example_runnable"))]
-    println!("    (FFI `abs` call example is not compiled/run in this demo config)");
+    #[cfg(not(ffi_lesson))]
+    println!("    (Run ./build_ffi_lesson.sh to also call the structs/strings/callbacks examples in ffi_lesson.c)");
 
 
     // --- 12.4. Accessing or Modifying a Mutable `static` Variable ---
@@ -2013,7 +2822,7 @@ fn level_12_unsafe_ffi() {
     increment_static_counter();
     increment_static_counter();
     unsafe {
-        println!("  Value of COUNTER_STATIC_MUT: {}", COUNTER_STATIC_MUT); // Should be 2
+        println!("  Value of COUNTER_STATIC_MUT: {}", COUNTER_STATIC_MUT); // => 2
     }
     // In multi-threaded code, this would be a data race without a Mutex or other sync.
 
@@ -2040,11 +2849,139 @@ fn level_12_unsafe_ffi() {
 }
 
 
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// LEVEL 13: DEBUG VS RELEASE BUILDS - SAME CODE, DIFFERENT BEHAVIOUR
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// `cargo build` (debug) and `cargo build --release` differ in more than speed: debug builds
+// check integer overflow and run `debug_assert!`s; release builds skip both and optimise.
+// This section is meant to be run under both profiles and compared line by line:
+//   rustc --edition 2021 profile_diff.rs -o target/profile_diff
+//   target/profile_diff Day001.003.rs level_13_build_profiles
+//   target/profile_diff Day001.003.rs level_13_timing     (13.3, also run by `--timing`)
+// (A plain `rustc --crate-name day001_003 Day001.003.rs` is a debug-style build: no optimisation,
+// checks on. The crate name is needed because the file name has dots in it.)
+
+// Runs `f` and prints its value, or the panic message if it panicked, without the usual
+// panic report on stderr, so the demo can carry on after a panic.
+fn show_outcome<T: Debug>(label: &str, f: impl FnOnce() -> T + std::panic::UnwindSafe) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = std::panic::catch_unwind(f);
+    std::panic::set_hook(default_hook);
+    match result {
+        Ok(value) => println!("  {:<34} => {:?}", label, value),
+        Err(payload) => {
+            let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            println!("  {:<34} => panicked: {}", label, message);
+        }
+    }
+}
+
+fn level_13_build_profiles() {
+    use std::hint::black_box;
+    println!("\n--- LEVEL 13: DEBUG VS RELEASE BUILDS ---");
+    println!("  This build has debug assertions: {}", cfg!(debug_assertions));
+
+    // --- 13.1. Integer Overflow ---
+    // Plain `+`, `-`, `*` on integers panic on overflow in debug builds and silently wrap
+    // (two's complement) in release builds. `black_box` hides the values from the compiler;
+    // with constants, `255_u8 + 1` would be rejected at compile time instead.
+    println!("\n--- 13.1. Integer Overflow ---");
+    let max_u8: u8 = black_box(u8::MAX);
+    let max_i32: i32 = black_box(i32::MAX);
+    let zero_u32: u32 = black_box(0);
+    show_outcome("255_u8 + 1", || max_u8 + 1);
+    show_outcome("i32::MAX * 2", || max_i32 * 2);
+    show_outcome("0_u32 - 1", || zero_u32 - 1);
+    // Say what you mean and the profile stops mattering: these agree in every build.
+    show_outcome("255_u8.wrapping_add(1)", || max_u8.wrapping_add(1));
+    show_outcome("255_u8.checked_add(1)", || max_u8.checked_add(1));
+    show_outcome("255_u8.saturating_add(1)", || max_u8.saturating_add(1));
+    show_outcome("255_u8.overflowing_add(1)", || max_u8.overflowing_add(1));
+    show_outcome("300_i32 as u8", || black_box(300_i32) as u8); // `as` truncates in every build.
+    // Cargo can keep the checks in release with `overflow-checks = true` under `[profile.release]`.
+
+    // --- 13.2. Bounds Checks and Assertions ---
+    // Indexing is bounds-checked in *every* build: release does not make `v[10]` unchecked.
+    // (The optimiser may remove a check it can prove always passes, never one that can fail.)
+    // `debug_assert!` is compiled out of release builds; `assert!` always stays.
+    println!("\n--- 13.2. Bounds Checks and Assertions ---");
+    let numbers = vec![10, 20, 30];
+    let index = black_box(10);
+    show_outcome("numbers[10]", || numbers[index]);
+    show_outcome("numbers.get(10)", || numbers.get(index).copied());
+    show_outcome("debug_assert!(index < 3)", || {
+        debug_assert!(index < numbers.len(), "index {} out of range", index);
+        "assertion skipped or passed"
+    });
+    show_outcome("assert!(index < 3)", || {
+        assert!(index < numbers.len(), "index {} out of range", index);
+        "assertion passed"
+    });
+
+    println!("\n  The timing loops of 13.3 run only with `--timing` (or: target/profile_diff Day001.003.rs level_13_timing).");
+    println!("  Compare the two builds with target/profile_diff Day001.003.rs level_13_build_profiles");
+}
+
+// Tens of millions of loop steps, so it is kept out of a plain run: pass `--timing`, or
+// let profile_diff.rs build it under both profiles and time it there.
+fn level_13_timing() {
+    use std::hint::black_box;
+    use std::time::Instant;
+    // --- 13.3. What the Optimiser Does to Timings ---
+    // The same loop, timed. Debug code keeps every loop counter, overflow check and function
+    // call; release code inlines, vectorises and may remove whole loops. The results match,
+    // the times do not (and differ run to run, so never compare a debug benchmark to anything).
+    println!("\n--- 13.3. Optimisation and Timing ---");
+    let n: u64 = black_box(20_000_000);
+    let started = Instant::now();
+    let mut total: u64 = 0;
+    for i in 0..n {
+        total = total.wrapping_add(i % 7);
+    }
+    println!("  for loop:      sum of i % 7 for i < {} = {} in {:?}", n, total, started.elapsed());
+    let started = Instant::now();
+    let total: u64 = (0..n).map(|i| i % 7).sum();
+    println!("  iterator sum:  sum of i % 7 for i < {} = {} in {:?}", n, total, started.elapsed());
+    // A result nobody uses lets release builds drop the loop entirely (`black_box(unused)` would keep it).
+    let started = Instant::now();
+    let mut unused: u64 = 0;
+    for i in 0..n {
+        unused = unused.wrapping_mul(31).wrapping_add(i);
+    }
+    let _ = unused;
+    println!("  unused result: loop of {} steps in {:?}", n, started.elapsed());
+}
+
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // MAIN FUNCTION TO RUN ALL DEMOS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 fn main() {
+    // `--virtual-clock [--seed N]` makes the LEVEL 10 thread demos instant and reproducible.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--virtual-clock") {
+        let seed = args.iter().position(|a| a == "--seed")
+            .and_then(|i| args.get(i + 1))
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        lesson_clock::init(lesson_clock::Mode::Virtual { seed });
+    } else {
+        lesson_clock::init(lesson_clock::Mode::Real);
+    }
+    // `--trace-drops` prints the drop timeline in 9.5.
+    if args.iter().any(|a| a == "--trace-drops") {
+        drop_trace::enable_timelines();
+    }
+    // `--inspect-strings` adds string reports to 5.2.
+    if args.iter().any(|a| a == "--inspect-strings") {
+        str_inspect::enable_reports();
+    }
+
     println!("========== COMPREHENSIVE RUST LEARNING GUIDE ==========");
+    // lesson_step.rs pauses after each section and shows its source next to its output.
     println!("TIP: Read through the code comments for each section.");
 
     // Level 0: Absolute Basics
@@ -2078,13 +3015,20 @@ fn main() {
     level_9_smart_pointers_interior_mutability();
 
     // Level 10: Concurrency
-    level_10_concurrency(); // Note: Async part is conceptual without a runtime.
+    level_10_concurrency(); // Note: 10.5 runs its async examples on a small in-file executor.
 
     // Level 11: Advanced Traits and Type System Features
     level_11_advanced_traits_types();
 
     // Level 12: Unsafe Rust and FFI
-    level_12_unsafe_ffi(); // Note: FFI `abs` call is conceptual without linking.
+    level_12_unsafe_ffi(); // Note: build with ./build_ffi_lesson.sh to include the C library examples.
+
+    // Level 13: Debug vs Release Builds
+    level_13_build_profiles(); // Note: compare both builds with profile_diff.rs.
+    // `--timing` adds 13.3, which times three 20-million-step loops (about a second in a debug build).
+    if args.iter().any(|a| a == "--timing") {
+        level_13_timing();
+    }
 
     println!("\n========== END OF COMPREHENSIVE GUIDE ==========");
     println!("Congratulations on working through this! Keep practicing and building!");
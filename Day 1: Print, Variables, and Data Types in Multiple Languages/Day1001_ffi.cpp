@@ -0,0 +1,58 @@
+// C++ Day 1 (FFI): calling the Rust lesson functions from C++
+// Uses the generated header day1_ffi.h and links against libday1_ffi.so.
+// Build and run everything with: ./build_day1_ffi.sh
+
+#include <iostream>     // Required for input/output operations
+#include <string>       // Required for string operations
+#include <vector>       // Required for vectors
+#include "day1_ffi.h"   // C declarations generated from day1_ffi.rs
+
+// Turns the status-code + out-pointer convention back into a C++ value or message
+template <typename T, typename Fn>
+void showParse(const char* label, Fn parse, const std::string& text) {
+    T value{};
+    int32_t code = parse(text.c_str(), &value);
+    if (code == DAY1_OK) {
+        std::cout << label << "(\"" << text << "\") = " << value << std::endl;
+    } else {
+        // Ask for the length first, then fetch the message into a buffer we own
+        std::string message(day1_last_error(nullptr, 0), '\0');
+        day1_last_error(&message[0], message.size() + 1);
+        std::cout << label << "(\"" << text << "\") failed (code " << code << "): " << message << std::endl;
+    }
+}
+
+int main() {
+    // Strings - the result points into one of our own strings, so they must outlive it
+    std::string apple = "apple";
+    std::string banana = "banana";
+    const char* longest = day1_longest_slice(apple.c_str(), banana.c_str());
+    std::cout << "longest_slice(\"apple\", \"banana\") = " << longest
+              << (longest == banana.c_str() ? " (points into `banana`)" : "") << std::endl;
+
+    // Arrays - pass a pointer to the first element plus the length
+    std::vector<int32_t> numbers = {34, 50, 25, 100, 65};
+    const int32_t* big = day1_largest_i32(numbers.data(), numbers.size());
+    std::cout << "largest({34, 50, 25, 100, 65}) = " << *big
+              << " at index " << (big - numbers.data()) << std::endl;
+    std::vector<double> floats = {1.5, -2.0, 9.25};
+    std::cout << "largest({1.5, -2.0, 9.25}) = " << *day1_largest_f64(floats.data(), floats.size()) << std::endl;
+    std::cout << "largest({}) is " << (day1_largest_i32(nullptr, 0) == nullptr ? "NULL" : "not NULL") << std::endl;
+
+    // Structs - Day1Rectangle has the same layout on both sides
+    Day1Rectangle rect1 = {30, 50};
+    Day1Rectangle rect2 = {10, 40};
+    std::cout << "area of 30x50 = " << day1_rectangle_area(rect1) << std::endl;
+    std::cout << std::boolalpha;
+    std::cout << "30x50 can hold 10x40? " << day1_rectangle_can_hold(rect1, rect2) << std::endl;
+    std::cout << "10x40 can hold 30x50? " << day1_rectangle_can_hold(rect2, rect1) << std::endl;
+
+    // Errors - Result<T, String> becomes a status code plus day1_last_error
+    showParse<int32_t>("parse_i32", day1_parse_i32, "42");
+    showParse<int32_t>("parse_i32", day1_parse_i32, "not_a_number");
+    showParse<int32_t>("parse_i32", day1_parse_i32, "3000000000");
+    showParse<int64_t>("parse_i64", day1_parse_i64, "3000000000");
+    showParse<double>("parse_f64", day1_parse_f64, "2.5e3");
+
+    return 0;
+}
@@ -0,0 +1,134 @@
+# Python Day 1 (FFI): calling the Rust lesson functions from Python
+# This program demonstrates:
+# - Loading a Rust cdylib (libday1_ffi.so) with ctypes
+# - Declaring argument and return types for each C function
+# - Passing strings, arrays, structs and out-pointers across the boundary
+#
+# Build the library first: ./build_day1_ffi.sh
+
+import ctypes
+import os
+import sys
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+DEFAULT_LIBRARY = os.path.join(HERE, "target", "libday1_ffi.so")
+
+# Status codes, same values as the DAY1_* #defines in day1_ffi.h
+DAY1_OK = 0
+DAY1_ERR_NULL = 1
+DAY1_ERR_UTF8 = 2
+DAY1_ERR_PARSE = 3
+
+
+# ctypes.Structure mirrors a C struct: same fields, same order, same types
+class Day1Rectangle(ctypes.Structure):
+    _fields_ = [("width", ctypes.c_uint32), ("height", ctypes.c_uint32)]
+
+
+class Day1Error(ValueError):
+    def __init__(self, code, message):
+        super().__init__(message)
+        self.code = code
+
+
+def load(path=None):
+    """Loads the library and declares every function's signature.
+    Without argtypes/restype, ctypes assumes `int` everywhere, which silently
+    truncates pointers and 64-bit values."""
+    lib = ctypes.CDLL(path or os.environ.get("DAY1_FFI_LIB", DEFAULT_LIBRARY))
+
+    lib.day1_longest_slice.argtypes = [ctypes.c_char_p, ctypes.c_char_p]
+    lib.day1_longest_slice.restype = ctypes.c_void_p  # A raw address: it points into our own buffers
+
+    lib.day1_largest_i32.argtypes = [ctypes.POINTER(ctypes.c_int32), ctypes.c_size_t]
+    lib.day1_largest_i32.restype = ctypes.POINTER(ctypes.c_int32)
+    lib.day1_largest_f64.argtypes = [ctypes.POINTER(ctypes.c_double), ctypes.c_size_t]
+    lib.day1_largest_f64.restype = ctypes.POINTER(ctypes.c_double)
+
+    lib.day1_rectangle_area.argtypes = [Day1Rectangle]
+    lib.day1_rectangle_area.restype = ctypes.c_uint64
+    lib.day1_rectangle_can_hold.argtypes = [Day1Rectangle, Day1Rectangle]
+    lib.day1_rectangle_can_hold.restype = ctypes.c_bool
+
+    lib.day1_parse_i32.argtypes = [ctypes.c_char_p, ctypes.POINTER(ctypes.c_int32)]
+    lib.day1_parse_i64.argtypes = [ctypes.c_char_p, ctypes.POINTER(ctypes.c_int64)]
+    lib.day1_parse_f64.argtypes = [ctypes.c_char_p, ctypes.POINTER(ctypes.c_double)]
+    for fn in (lib.day1_parse_i32, lib.day1_parse_i64, lib.day1_parse_f64):
+        fn.restype = ctypes.c_int32
+
+    lib.day1_last_error.argtypes = [ctypes.c_char_p, ctypes.c_size_t]
+    lib.day1_last_error.restype = ctypes.c_size_t
+    return lib
+
+
+# ====================== PYTHON-FRIENDLY WRAPPERS ======================
+# Each wrapper turns C conventions (NULL, status codes, out-pointers) back into
+# ordinary Python values and exceptions.
+
+def longest_slice(lib, x, y):
+    bx, by = x.encode("utf-8"), y.encode("utf-8")
+    # Keep our own buffers alive: the result points into one of them
+    buf_x, buf_y = ctypes.create_string_buffer(bx), ctypes.create_string_buffer(by)
+    address = lib.day1_longest_slice(buf_x, buf_y)
+    return x if address == ctypes.addressof(buf_x) else y
+
+
+def largest(lib, values):
+    if values and all(isinstance(v, int) for v in values):
+        array = (ctypes.c_int32 * len(values))(*values)
+        result = lib.day1_largest_i32(array, len(values))
+    else:
+        array = (ctypes.c_double * len(values))(*values)
+        result = lib.day1_largest_f64(array, len(values))
+    return result.contents.value if result else None  # A NULL pointer is falsy
+
+
+def last_error(lib):
+    needed = lib.day1_last_error(None, 0)
+    buf = ctypes.create_string_buffer(needed + 1)
+    lib.day1_last_error(buf, len(buf))
+    return buf.value.decode("utf-8", errors="replace")
+
+
+def parse_number(lib, text, kind="i32"):
+    c_type, fn = {
+        "i32": (ctypes.c_int32, lib.day1_parse_i32),
+        "i64": (ctypes.c_int64, lib.day1_parse_i64),
+        "f64": (ctypes.c_double, lib.day1_parse_f64),
+    }[kind]
+    out = c_type()
+    code = fn(text.encode("utf-8"), ctypes.byref(out))  # byref = "pointer to this"
+    if code != DAY1_OK:
+        raise Day1Error(code, last_error(lib))
+    return out.value
+
+
+def main():
+    lib = load(sys.argv[1] if len(sys.argv) > 1 else None)
+
+    print("===== STRINGS (longest_slice) =====")
+    print("longest_slice('apple', 'banana') =", longest_slice(lib, "apple", "banana"))
+
+    print("\n===== ARRAYS (largest) =====")
+    print("largest([34, 50, 25, 100, 65]) =", largest(lib, [34, 50, 25, 100, 65]))
+    print("largest([1.5, -2.0, 9.25]) =", largest(lib, [1.5, -2.0, 9.25]))
+    print("largest([]) =", largest(lib, []), "(NULL from Rust, where the original would panic)")
+
+    print("\n===== STRUCTS (Rectangle) =====")
+    rect1 = Day1Rectangle(30, 50)
+    rect2 = Day1Rectangle(10, 40)
+    print("area of 30x50 =", lib.day1_rectangle_area(rect1))
+    print("30x50 can hold 10x40?", lib.day1_rectangle_can_hold(rect1, rect2))
+    print("10x40 can hold 30x50?", lib.day1_rectangle_can_hold(rect2, rect1))
+
+    print("\n===== ERRORS (parse_number) =====")
+    for text, kind in [("42", "i32"), ("-5", "i32"), ("not_a_number", "i32"),
+                       ("3000000000", "i32"), ("3000000000", "i64"), ("2.5e3", "f64")]:
+        try:
+            print(f"parse_{kind}({text!r}) =", parse_number(lib, text, kind))
+        except Day1Error as e:
+            print(f"parse_{kind}({text!r}) failed (code {e.code}): {e}")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,599 @@
+// annotation_check.rs
+// Checks the output claims in lesson comments. A claim is written as `// => EXPECTED` at the
+// end of a statement:
+//
+//   let byte_value = large_value as u8; // => 232 // truncated: 1000 % 256
+//   println!("Filtered option: {:?}", filtered); // => None
+//
+// The checker compiles the file, runs the functions that contain claims, and compares what
+// each claimed line printed with EXPECTED. A `let` line that prints nothing is checked
+// against the `{:?}` of the value it binds. Output matches if it equals EXPECTED or ends
+// with " EXPECTED", so `// => None` fits "Filtered option: None". Anything after a second
+// `//` is a note for the reader and is not compared.
+//
+// Usage:
+//   rustc --edition 2021 annotation_check.rs -o target/annotation_check
+//   target/annotation_check                  # every .rs file here with a `// =>` claim
+//   target/annotation_check Day001.003.rs    # ...or just these files
+//
+// Tests (the checker on a small fixture file):
+//   rustc --edition 2021 --test annotation_check.rs -o target/annotation_check_test && target/annotation_check_test
+//
+// Lesson files don't always compile as a whole (` Day001.001.rs` calls sections that were
+// never written), so each file is cut into its top-level items, and a new `fn main` calls
+// just the functions with claims (the lesson's own `main` is renamed if it has claims).
+// Items that fail to compile are dropped and the rest compiled again; claims in a dropped
+// function are reported, not silently skipped.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+
+const MARKER: &str = "\u{1}annotation";
+const LESSON_MAIN: &str = "lesson_main";
+
+// --- Finding claims and items ---
+
+struct Claim {
+    line: usize, // 1-based, in the lesson file
+    expected: String,
+    binding: Option<String>, // `let NAME = ...;` lines that print nothing show `NAME` instead.
+    problem: Option<String>, // Set if the claim can't be checked as written.
+}
+
+// `code // => expected // note` -> (code, expected)
+fn split_claim(line: &str) -> Option<(&str, String)> {
+    let at = line.find("// =>")?;
+    let code = line[..at].trim_end();
+    let rest = &line[at + 5..];
+    let expected = rest.split(" //").next().unwrap_or("").trim().to_string();
+    Some((code, expected))
+}
+
+fn find_claims(source: &str) -> Vec<Claim> {
+    let mut claims = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        let Some((code, expected)) = split_claim(line) else { continue };
+        let code_trimmed = code.trim_start();
+        if code_trimmed.is_empty() || code_trimmed.starts_with("//") {
+            continue; // A comment *about* the syntax, like the ones at the top of this file.
+        }
+        let problem = if !code.ends_with(';') {
+            Some("a claim must follow a statement ending in `;`".to_string())
+        } else if expected.is_empty() {
+            Some("nothing after `// =>`".to_string())
+        } else {
+            None
+        };
+        let binding = if code_trimmed.starts_with("let ") && !code.contains("print") {
+            let name: String = code_trimmed[4..]
+                .trim_start_matches("mut ")
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect();
+            Some(name).filter(|n| !n.is_empty())
+        } else {
+            None
+        };
+        claims.push(Claim { line: i + 1, expected, binding, problem });
+    }
+    claims
+}
+
+// A top-level item: lines `start..=end` (1-based), and the function name if it is a `fn`.
+#[derive(Debug, Clone)]
+struct Item {
+    start: usize,
+    end: usize,
+    function: Option<String>,
+    takes_arguments: bool,
+    unclosed: bool, // Its `{` is never closed, so it runs to the end of the file.
+}
+
+// Cuts the source into top-level items by tracking `{}` depth, skipping comments, strings
+// and char literals. Comments and attributes before an item belong to it.
+fn split_items(source: &str) -> Vec<Item> {
+    let chars: Vec<char> = source.chars().collect();
+    let at = |i: usize| chars.get(i).copied().unwrap_or('\0');
+    let (mut i, mut line, mut depth) = (0, 1, 0i32);
+    let mut ends = Vec::new(); // Lines where an item ends.
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => line += 1,
+            '/' if at(i + 1) == '/' => {
+                while i + 1 < chars.len() && chars[i + 1] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if at(i + 1) == '*' => {
+                let mut nesting = 0;
+                while i < chars.len() {
+                    if chars[i] == '/' && at(i + 1) == '*' {
+                        nesting += 1;
+                        i += 1;
+                    } else if chars[i] == '*' && at(i + 1) == '/' {
+                        nesting -= 1;
+                        i += 1;
+                        if nesting == 0 {
+                            break;
+                        }
+                    } else if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+            }
+            'r' if (at(i + 1) == '#' || at(i + 1) == '"') && !(i > 0 && (at(i - 1).is_alphanumeric() || at(i - 1) == '_')) => {
+                let mut hashes = 0;
+                let mut j = i + 1;
+                while at(j) == '#' {
+                    hashes += 1;
+                    j += 1;
+                }
+                if at(j) == '"' {
+                    i = j + 1;
+                    while i < chars.len() && !(chars[i] == '"' && (0..hashes).all(|h| at(i + 1 + h) == '#')) {
+                        line += (chars[i] == '\n') as usize;
+                        i += 1;
+                    }
+                    i += hashes;
+                }
+            }
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    line += (at(i) == '\n') as usize;
+                    i += 1;
+                }
+            }
+            '\'' if at(i + 1) == '\\' => {
+                i += 2;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+            }
+            '\'' if at(i + 2) == '\'' => i += 2, // 'x' (a lifetime like 'a has no closing quote)
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    ends.push(line);
+                }
+            }
+            ';' if depth == 0 => ends.push(line),
+            _ => {}
+        }
+        i += 1;
+    }
+    let lines: Vec<&str> = source.lines().collect();
+    let unclosed = depth != 0;
+    if unclosed || ends.last() != Some(&lines.len()) {
+        ends.push(lines.len());
+    }
+    let mut items = Vec::new();
+    let mut start = 1;
+    for end in ends {
+        if end < start {
+            continue; // Two items ended on one line.
+        }
+        let signature = lines[start - 1..end]
+            .iter()
+            .find(|l| l.starts_with("fn ") || l.starts_with("pub fn ")); // At column 0: not a method.
+        let function = signature.map(|l| {
+            l.trim_start_matches("pub ").trim_start_matches("fn ").chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect()
+        });
+        let takes_arguments = signature.is_some_and(|l| !l.contains("()"));
+        items.push(Item { start, end, function, takes_arguments, unclosed: unclosed && end == lines.len() });
+        start = end + 1;
+    }
+    items
+}
+
+// --- Building and running ---
+
+fn rename_main(line: &str) -> String {
+    match line.strip_prefix("fn main(") {
+        Some(rest) => format!("fn {}({}", LESSON_MAIN, rest),
+        None => line.to_string(),
+    }
+}
+
+struct Program {
+    source: String,
+    line_map: Vec<usize>, // Program line (0-based index) -> lesson line, 0 for generated lines.
+}
+
+fn build(lesson: &str, items: &[Item], claims: &[Claim], run: &[String]) -> Program {
+    let lines: Vec<&str> = lesson.lines().collect();
+    let by_line: BTreeMap<usize, &Claim> = claims.iter().filter(|c| c.problem.is_none()).map(|c| (c.line, c)).collect();
+    let mut program = Program { source: String::new(), line_map: Vec::new() };
+    let mut push = |text: &str, origin: usize| {
+        program.source.push_str(text);
+        program.source.push('\n');
+        program.line_map.push(origin);
+    };
+    for item in items {
+        for n in item.start..=item.end {
+            match by_line.get(&n) {
+                Some(claim) => {
+                    push(&format!("println!(\"{} {} begin\");", MARKER, n), n);
+                    push(lines[n - 1], n);
+                    if let Some(name) = &claim.binding {
+                        push(&format!("println!(\"{{:?}}\", {});", name), n);
+                    }
+                    push(&format!("println!(\"{} {} end\");", MARKER, n), n);
+                }
+                None if item.function.as_deref() == Some(LESSON_MAIN) => push(&rename_main(lines[n - 1]), n),
+                None => push(lines[n - 1], n),
+            }
+        }
+    }
+    push("fn main() {", 0);
+    for function in run {
+        push(&format!("    {}();", function), 0);
+    }
+    push("}", 0);
+    program
+}
+
+struct CompileError {
+    line: usize, // In the lesson, 0 if in generated code
+    message: String,
+}
+
+// `rustc --error-format=short` prints `<anon>:LINE:COL: error[E0000]: message`.
+fn compile(program: &Program, dir: &Path, exe: &Path) -> Result<Vec<CompileError>, String> {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let mut child = Command::new(&rustc)
+        .args(["--edition", "2021", "--error-format=short", "-A", "warnings", "--crate-name", "annotations", "-o"])
+        .arg(exe)
+        .arg("-") // The source comes on stdin; `mod` files are found from `dir`.
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run {}: {}", rustc, e))?;
+    child.stdin.take().expect("stdin is piped").write_all(program.source.as_bytes()).map_err(|e| e.to_string())?;
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut errors = Vec::new();
+    for l in stderr.lines() {
+        let Some(rest) = l.strip_prefix("<anon>:") else { continue };
+        let mut parts = rest.splitn(3, ':');
+        let line: usize = parts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+        let message = parts.nth(1).unwrap_or("").trim();
+        if message.starts_with("error") {
+            errors.push(CompileError { line: program.line_map.get(line.wrapping_sub(1)).copied().unwrap_or(0), message: message.to_string() });
+        }
+    }
+    if errors.is_empty() && !output.status.success() {
+        return Err(format!("rustc failed: {}", stderr.trim()));
+    }
+    Ok(errors)
+}
+
+// --- Checking a file ---
+
+#[derive(Debug, PartialEq)]
+enum Verdict {
+    Ok,
+    Stale(String),      // Ran, printed something else.
+    Broken(String),     // The claimed line itself does not compile.
+    Unchecked(String),  // Could not be run: its function does not compile, a panic, ...
+}
+
+fn matches(actual: &str, expected: &str) -> bool {
+    actual == expected || actual.ends_with(&format!(" {}", expected))
+}
+
+fn check_file(path: &Path, work: &Path) -> Result<Vec<(usize, String, Verdict)>, String> {
+    let lesson = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let claims = find_claims(&lesson);
+    let mut verdicts: BTreeMap<usize, Verdict> = BTreeMap::new();
+    for claim in &claims {
+        if let Some(problem) = &claim.problem {
+            verdicts.insert(claim.line, Verdict::Unchecked(problem.clone()));
+        }
+    }
+    let item_of = |items: &[Item], line: usize| items.iter().position(|it| it.start <= line && line <= it.end);
+
+    // The lesson's `fn main` stays only if it has claims, renamed so ours can call it.
+    let mut items: Vec<Item> = split_items(&lesson)
+        .into_iter()
+        .filter(|it| it.function.as_deref() != Some("main") || claims.iter().any(|c| it.start <= c.line && c.line <= it.end))
+        .map(|it| if it.function.as_deref() == Some("main") { Item { function: Some(LESSON_MAIN.to_string()), ..it } } else { it })
+        .collect();
+    // rustc reports an unclosed `{` at the end of the file, which is our generated code.
+    if let Some(item) = items.iter().find(|it| it.unclosed) {
+        for claim in claims.iter().filter(|c| item.start <= c.line) {
+            verdicts.entry(claim.line).or_insert_with(|| Verdict::Unchecked(format!("a `{{` from line {} on is never closed", item.start)));
+        }
+        items.retain(|it| !it.unclosed);
+    }
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let exe = work.join("annotations");
+    loop {
+        let pending: Vec<&Claim> = claims.iter().filter(|c| !verdicts.contains_key(&c.line)).collect();
+        if pending.is_empty() {
+            break;
+        }
+        // The top-level functions to call: the ones holding the pending claims.
+        let mut run = Vec::new();
+        for claim in &pending {
+            match item_of(&items, claim.line).map(|i| &items[i]) {
+                Some(Item { function: Some(name), takes_arguments: false, .. }) => {
+                    if !run.contains(name) {
+                        run.push(name.clone());
+                    }
+                }
+                Some(Item { function: Some(name), .. }) => {
+                    verdicts.insert(claim.line, Verdict::Unchecked(format!("fn {} takes arguments, so it can't be run on its own", name)));
+                }
+                _ => {
+                    verdicts.insert(claim.line, Verdict::Unchecked("not inside a function".to_string()));
+                }
+            }
+        }
+        if run.is_empty() {
+            continue;
+        }
+        let program = build(&lesson, &items, &claims, &run);
+        let errors = compile(&program, dir, &exe)?;
+        if errors.is_empty() {
+            let output = Command::new(&exe).current_dir(dir).output().map_err(|e| e.to_string())?;
+            let stderr = lesson_locations(&String::from_utf8_lossy(&output.stderr), &program, path);
+            record_run(&String::from_utf8_lossy(&output.stdout), &stderr, &pending, &mut verdicts);
+            break;
+        }
+        // Drop every item with an error, and say why for the claims in them.
+        let mut dropped = Vec::new();
+        for error in &errors {
+            if let Some(claim) = claims.iter().find(|c| c.line == error.line) {
+                verdicts.entry(claim.line).or_insert_with(|| Verdict::Broken(error.message.clone()));
+            }
+            match item_of(&items, error.line) {
+                Some(i) if !dropped.contains(&i) => dropped.push(i),
+                Some(_) => {}
+                None => return Err(format!("{}: generated code does not compile: {}", path.display(), error.message)),
+            }
+        }
+        for &i in &dropped {
+            let item = &items[i];
+            let first_error = errors.iter().find(|e| item.start <= e.line && e.line <= item.end).expect("dropped for an error");
+            for claim in claims.iter().filter(|c| item.start <= c.line && c.line <= item.end) {
+                verdicts.entry(claim.line).or_insert_with(|| {
+                    Verdict::Unchecked(format!("line {} in the same item does not compile: {}", first_error.line, first_error.message))
+                });
+            }
+        }
+        dropped.sort_unstable();
+        for i in dropped.into_iter().rev() {
+            items.remove(i);
+        }
+    }
+    Ok(claims.iter().map(|c| (c.line, c.expected.clone(), verdicts.remove(&c.line).unwrap_or(Verdict::Unchecked("not run".to_string())))).collect())
+}
+
+// Rewrites panic locations like `<anon>:31:18` to the lesson's `file:line`.
+fn lesson_locations(stderr: &str, program: &Program, path: &Path) -> String {
+    let mut out = String::new();
+    let mut rest = stderr;
+    while let Some(at) = rest.find("<anon>:") {
+        out.push_str(&rest[..at]);
+        rest = &rest[at + "<anon>:".len()..];
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        let line = digits.parse::<usize>().ok().and_then(|n| program.line_map.get(n.wrapping_sub(1))).copied().unwrap_or(0);
+        out.push_str(&format!("{}:{}", path.display(), line));
+        rest = &rest[digits.len()..];
+        let column: String = rest.strip_prefix(':').unwrap_or("").chars().take_while(char::is_ascii_digit).collect();
+        if !column.is_empty() {
+            rest = &rest[1 + column.len()..]; // The column is for the generated source; drop it.
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// Sorts the program's stdout into the output of each claimed line.
+fn record_run(stdout: &str, stderr: &str, pending: &[&Claim], verdicts: &mut BTreeMap<usize, Verdict>) {
+    let mut outputs: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    let mut finished: Vec<usize> = Vec::new();
+    let mut current: Option<usize> = None;
+    for line in stdout.lines() {
+        if let Some(rest) = line.strip_prefix(MARKER) {
+            let mut words = rest.split_whitespace();
+            let n: usize = words.next().and_then(|w| w.parse().ok()).unwrap_or(0);
+            match words.next() {
+                Some("begin") => {
+                    current = Some(n);
+                    outputs.entry(n).or_default();
+                }
+                _ => {
+                    finished.push(n);
+                    current = None;
+                }
+            }
+        } else if let Some(n) = current {
+            outputs.entry(n).or_default().push(line.to_string());
+        }
+    }
+    let panic_message = stderr.lines().find(|l| l.contains("panicked at")).map(|l| {
+        let detail = stderr.lines().skip_while(|x| *x != l).nth(1).unwrap_or("");
+        format!("{} {}", l.trim(), detail.trim())
+    });
+    for claim in pending {
+        if verdicts.contains_key(&claim.line) {
+            continue;
+        }
+        let verdict = match outputs.get(&claim.line) {
+            _ if !finished.contains(&claim.line) && outputs.contains_key(&claim.line) => {
+                Verdict::Unchecked(format!("the line panicked: {}", panic_message.clone().unwrap_or_default()))
+            }
+            None => match &panic_message {
+                Some(p) => Verdict::Unchecked(format!("never reached; the program panicked earlier: {}", p)),
+                None => Verdict::Unchecked("never reached".to_string()),
+            },
+            Some(lines) => {
+                // A line run in a loop prints once per pass; each pass must match.
+                let actual = lines.join("\n").trim().to_string();
+                if actual.is_empty() {
+                    Verdict::Stale("the line printed nothing".to_string())
+                } else if lines.iter().filter(|l| !l.trim().is_empty()).all(|l| matches(l.trim(), &claim.expected)) {
+                    Verdict::Ok
+                } else {
+                    Verdict::Stale(format!("printed {:?}", actual))
+                }
+            }
+        };
+        verdicts.insert(claim.line, verdict);
+    }
+}
+
+fn report(path: &Path, results: &[(usize, String, Verdict)]) -> usize {
+    let mut failures = 0;
+    for (line, expected, verdict) in results {
+        let (tag, detail) = match verdict {
+            Verdict::Ok => ("ok   ", String::new()),
+            Verdict::Stale(d) => ("STALE", d.clone()),
+            Verdict::Broken(d) => ("FALSE", format!("does not compile: {}", d)),
+            Verdict::Unchecked(d) => ("UNCHECKED", d.clone()),
+        };
+        if *verdict != Verdict::Ok {
+            failures += 1;
+        }
+        let detail = if detail.is_empty() { String::new() } else { format!(": {}", detail) };
+        println!("{}:{}: {} claims {:?}{}", path.display(), line, tag, expected, detail);
+    }
+    failures
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let work = env::temp_dir().join(format!("annotation_check_{}", process::id()));
+    if let Err(e) = fs::create_dir_all(&work) {
+        eprintln!("{}: {}", work.display(), e);
+        process::exit(2);
+    }
+    let files: Vec<PathBuf> = if args.is_empty() {
+        let mut found: Vec<PathBuf> = fs::read_dir(".")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "rs") && p.file_name().is_some_and(|n| n != "annotation_check.rs"))
+            .filter(|p| fs::read_to_string(p).is_ok_and(|t| !find_claims(&t).is_empty()))
+            .collect();
+        found.sort();
+        found
+    } else {
+        args.iter().map(PathBuf::from).collect()
+    };
+    let mut failures = 0;
+    let mut total = 0;
+    for file in &files {
+        match check_file(file, &work) {
+            Ok(results) => {
+                total += results.len();
+                failures += report(file, &results);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                failures += 1;
+            }
+        }
+    }
+    println!("{} claim(s) in {} file(s), {} not confirmed", total, files.len(), failures);
+    let status = if failures == 0 { 0 } else { 1 };
+    fs::remove_dir_all(&work).ok();
+    process::exit(status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_whole_output_or_its_end() {
+        assert!(matches("None", "None"));
+        assert!(matches("Filtered option: None", "None"));
+        assert!(!matches("NoneSuch", "None"));
+        assert!(!matches("None at all", "None"));
+    }
+
+    #[test]
+    fn claims_after_comments_are_ignored() {
+        let claims = find_claims("// let x = 1; // => 1\nlet y = 2; // => 2 // a note\nlet z = 3 // => 3\n");
+        let found: Vec<(usize, &str, bool)> = claims.iter().map(|c| (c.line, c.expected.as_str(), c.problem.is_some())).collect();
+        assert_eq!(found, [(2, "2", false), (3, "3", true)]);
+        assert_eq!(claims[0].binding.as_deref(), Some("y"));
+    }
+
+    #[test]
+    fn fixture_verdicts() {
+        let fixture = r#"
+// A fixture with one claim of each kind.
+fn helper() -> i32 { 2 }
+
+fn section() {
+    let truncated = 1000_i32 as u8; // => 232 // 1000 % 256
+    println!("Filtered: {:?}", Some(5).filter(|x| *x > 10)); // => None
+    let wrong = helper() + 1; // => 2
+    for i in 0..2 {
+        println!("i is small: {}", i < 5); // => true
+    }
+    let quiet = vec![1, 2]; // => [1, 2] // a `let` shows its value
+}
+
+fn broken() {
+    let fine = 1; // => 1
+    let bad = i32::try_from(300_i8); // => Err
+}
+
+fn panics() {
+    let v: Vec<i32> = Vec::new();
+    let first = v[0]; // => 0
+}
+
+fn not_a_statement() -> i32 {
+    helper() // => 2
+}
+
+fn main() {
+    never_written(); // `fn main` is replaced, so this doesn't matter.
+}
+"#;
+        let work = env::temp_dir().join(format!("annotation_check_test_{}", process::id()));
+        fs::create_dir_all(&work).unwrap();
+        let path = work.join("fixture.rs");
+        fs::write(&path, fixture).unwrap();
+        let results = check_file(&path, &work);
+        fs::remove_dir_all(&work).ok();
+
+        let kind = |v: &Verdict| match v {
+            Verdict::Ok => "ok",
+            Verdict::Stale(_) => "stale",
+            Verdict::Broken(_) => "false",
+            Verdict::Unchecked(_) => "unchecked",
+        };
+        let got: Vec<(usize, &str)> = results.unwrap().iter().map(|(line, _, v)| (*line, kind(v))).collect();
+        assert_eq!(got, [
+            (6, "ok"),         // `let` value
+            (7, "ok"),         // "label: value"
+            (8, "stale"),      // 3, not 2
+            (10, "ok"),        // every pass of the loop
+            (12, "ok"),        // Debug of a Vec
+            (16, "unchecked"), // same function as a line that doesn't compile
+            (17, "false"),     // 300_i8 does not compile
+            (22, "unchecked"), // panics
+            (26, "unchecked"), // not a statement
+        ]);
+    }
+}
@@ -0,0 +1,170 @@
+# bench_harness.py - times the same snippets in Rust, C++ and Python
+# Each language has a bench_snippets program (bench_snippets.rs, .cpp, .py) that stays
+# running, reads a benchmark name per line and answers "<nanoseconds>\t<checksum>" after
+# timing one run with its own clock, so process start-up and pipe traffic are not measured.
+# For every benchmark and language the harness:
+#   1. does --warmup runs and throws them away (caches, allocator, CPU frequency settle),
+#   2. collects --runs samples,
+#   3. rejects outliers outside Tukey's fences (Q1 - 1.5 IQR, Q3 + 1.5 IQR),
+#   4. reports the median and p95 of what is left, and how each language compares with the
+#      fastest one.
+# The checksums must agree across languages, or the snippets are not doing the same work.
+#
+#   python3 bench_harness.py [--warmup N] [--runs N] [--only NAME] RUST_BIN CPP_BIN
+# (./build_bench.sh builds the Rust and C++ programs and does this for you.)
+#
+# The statistics only see (nanoseconds, checksum) pairs from a runner, so the tests feed
+# them from a fake clock (see test_bench_harness.py) and get exact, repeatable numbers.
+
+import argparse
+import math
+import os
+import subprocess
+import sys
+
+BENCHMARKS = ["sum_vec", "word_count", "concat"]
+DESCRIPTIONS = {
+    "sum_vec": "sum a Vec<i32> of 1,000,000 values",
+    "word_count": "HashMap word count of 200,000 words",
+    "concat": "s = s + piece, 100,000 times",
+}
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+
+class ProcessRunner:
+    """One long-running bench_snippets program, spoken to over stdin/stdout."""
+
+    def __init__(self, language, command):
+        self.language = language
+        self.process = subprocess.Popen(command, stdin=subprocess.PIPE, stdout=subprocess.PIPE, text=True, cwd=HERE)
+
+    def time(self, name):
+        self.process.stdin.write(name + "\n")
+        self.process.stdin.flush()
+        reply = self.process.stdout.readline().rstrip("\n")
+        first, _, second = reply.partition("\t")
+        if first == "error" or not second:
+            raise RuntimeError("%s: %s" % (self.language, second or "no reply (did it crash?)"))
+        return int(first), int(second)
+
+    def close(self):
+        self.process.stdin.close()
+        self.process.wait()
+        self.process.stdout.close()
+
+
+def percentile(sorted_values, fraction):
+    """Linear interpolation between closest ranks (what numpy calls "linear")."""
+    position = (len(sorted_values) - 1) * fraction
+    low = math.floor(position)
+    high = min(low + 1, len(sorted_values) - 1)
+    return sorted_values[low] + (sorted_values[high] - sorted_values[low]) * (position - low)
+
+
+def nearest_rank(sorted_values, fraction):
+    """The smallest sample with at least `fraction` of the samples at or below it."""
+    return sorted_values[max(0, math.ceil(fraction * len(sorted_values)) - 1)]
+
+
+def reject_outliers(samples):
+    """Splits samples into (kept, rejected) with Tukey's fences."""
+    ordered = sorted(samples)
+    q1, q3 = percentile(ordered, 0.25), percentile(ordered, 0.75)
+    low, high = q1 - 1.5 * (q3 - q1), q3 + 1.5 * (q3 - q1)
+    kept = [s for s in samples if low <= s <= high]
+    rejected = [s for s in samples if not low <= s <= high]
+    return kept, rejected
+
+
+class Result:
+    def __init__(self, language, name, samples, checksum):
+        self.language = language
+        self.name = name
+        self.samples = samples
+        self.checksum = checksum
+        self.kept, self.rejected = reject_outliers(samples)
+        ordered = sorted(self.kept)
+        self.median = percentile(ordered, 0.5)
+        self.p95 = nearest_rank(ordered, 0.95)
+
+
+def measure(runner, name, warmup, runs):
+    for _ in range(warmup):
+        runner.time(name)
+    samples, checksums = [], set()
+    for _ in range(runs):
+        elapsed, checksum = runner.time(name)
+        samples.append(elapsed)
+        checksums.add(checksum)
+    if len(checksums) != 1:
+        raise RuntimeError("%s %s: the checksum changed between runs: %s" % (runner.language, name, sorted(checksums)))
+    return Result(runner.language, name, samples, checksums.pop())
+
+
+def format_ns(ns):
+    for unit, scale in (("s", 1e9), ("ms", 1e6), ("µs", 1e3)):
+        if ns >= scale:
+            return "%.2f %s" % (ns / scale, unit)
+    return "%d ns" % ns
+
+
+def checksums_agree(results):
+    return len({r.checksum for r in results}) == 1
+
+
+def render(results_by_benchmark):
+    """A plain-text table, one block of rows per benchmark, fastest language first."""
+    headings = ["Benchmark", "Language", "Median", "p95", "Kept", "vs fastest"]
+    rows = []
+    problems = []
+    for name, results in results_by_benchmark.items():
+        fastest = min(r.median for r in results)
+        for i, r in enumerate(sorted(results, key=lambda r: r.median)):
+            ratio = "%.1fx" % (r.median / fastest) if fastest else "-"
+            rows.append([name if i == 0 else "", r.language, format_ns(r.median), format_ns(r.p95),
+                         "%d/%d" % (len(r.kept), len(r.samples)), ratio])
+        if not checksums_agree(results):
+            problems.append("%s: checksums differ: %s" % (
+                name, ", ".join("%s=%d" % (r.language, r.checksum) for r in results)))
+    widths = [max(len(row[i]) for row in rows + [headings]) for i in range(len(headings))]
+    lines = ["  ".join(cell.ljust(w) for cell, w in zip(headings, widths)).rstrip(),
+             "  ".join("-" * w for w in widths)]
+    for row in rows:
+        lines.append("  ".join(cell.ljust(w) for cell, w in zip(row, widths)).rstrip())
+    lines.extend("CHECKSUM MISMATCH " + p for p in problems)
+    return "\n".join(lines) + "\n"
+
+
+def run_all(runners, benchmarks, warmup, runs):
+    return {name: [measure(runner, name, warmup, runs) for runner in runners] for name in benchmarks}
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Time equivalent Rust, C++ and Python snippets.")
+    parser.add_argument("rust", help="bench_snippets.rs built with -C opt-level=3")
+    parser.add_argument("cpp", help="bench_snippets.cpp built with -O2")
+    parser.add_argument("--warmup", type=int, default=3, help="runs thrown away first (default 3)")
+    parser.add_argument("--runs", type=int, default=30, help="measured runs (default 30)")
+    parser.add_argument("--only", choices=BENCHMARKS, action="append", help="just this benchmark (repeatable)")
+    args = parser.parse_args()
+    if args.runs < 1 or args.warmup < 0:
+        parser.error("--runs must be at least 1 and --warmup at least 0")
+
+    runners = [ProcessRunner("rust", [args.rust]), ProcessRunner("cpp", [args.cpp]),
+               ProcessRunner("python", [sys.executable, os.path.join(HERE, "bench_snippets.py")])]
+    try:
+        benchmarks = args.only or BENCHMARKS
+        results = run_all(runners, benchmarks, args.warmup, args.runs)
+    finally:
+        for runner in runners:
+            runner.close()
+    for name in benchmarks:
+        print("%-11s %s" % (name, DESCRIPTIONS[name]))
+    print("(%d warm-up + %d measured runs each; outliers outside Tukey's fences are not kept)\n" % (args.warmup, args.runs))
+    sys.stdout.write(render(results))
+    sys.exit(0 if all(checksums_agree(r) for r in results.values()) else 1)
+
+
+if __name__ == "__main__":
+    main()
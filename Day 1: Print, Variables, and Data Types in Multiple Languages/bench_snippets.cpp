@@ -0,0 +1,73 @@
+// bench_snippets.cpp - the C++ side of bench_harness.py
+// Reads a benchmark name per line on stdin, runs that snippet once and answers with
+//   <nanoseconds> <checksum>
+// (tab-separated), exactly like bench_snippets.rs. Inputs are built once at startup.
+// Build: c++ -std=c++17 -O2 bench_snippets.cpp -o target/bench_snippets_cpp
+
+#include <chrono>         // Required for std::chrono::steady_clock
+#include <cstdint>        // Required for std::int64_t
+#include <iostream>       // Required for input/output operations
+#include <numeric>        // Required for std::accumulate
+#include <sstream>        // Required for std::istringstream
+#include <string>         // Required for string operations
+#include <unordered_map>  // Required for std::unordered_map
+#include <vector>         // Required for std::vector
+
+// Sizes and inputs must match bench_snippets.rs and bench_snippets.py.
+const int SUM_LEN = 1000000;
+const int WORD_COUNT = 200000;
+const int CONCAT_PIECES = 100000;
+const char* const WORDS[] = {"the", "quick", "brown", "fox", "jumps", "over", "the", "lazy", "dog"};
+const char* const PIECES[] = {"tic", "tac", "toe"};
+
+// Summing a std::vector<int> into a 64-bit total
+std::int64_t sum_vec(const std::vector<int>& numbers) {
+    return std::accumulate(numbers.begin(), numbers.end(), std::int64_t{0});
+}
+
+// Word count; checksum: distinct words * 10^6 + count of "the"
+std::int64_t word_count(const std::string& text) {
+    std::unordered_map<std::string, std::int64_t> counts;
+    std::istringstream words(text);
+    std::string word;
+    while (words >> word) ++counts[word];
+    auto the = counts.find("the");
+    return static_cast<std::int64_t>(counts.size()) * 1000000 + (the == counts.end() ? 0 : the->second);
+}
+
+// std::move(s) + piece appends in place, like Rust's `s + &piece`. A plain `s + piece`
+// would copy the whole string every time and make the loop quadratic.
+std::int64_t concat() {
+    std::string s;
+    for (int i = 0; i < CONCAT_PIECES; ++i) s = std::move(s) + PIECES[i % 3];
+    return static_cast<std::int64_t>(s.size());
+}
+
+int main() {
+    std::vector<int> numbers(SUM_LEN);
+    for (int i = 0; i < SUM_LEN; ++i) numbers[i] = i % 1000 - 500;
+    std::string text;
+    for (int i = 0; i < WORD_COUNT; ++i) {
+        if (i > 0) text += ' ';
+        text += WORDS[i % 9];
+    }
+
+    std::string name;
+    while (std::getline(std::cin, name)) {
+        auto started = std::chrono::steady_clock::now();
+        std::int64_t checksum;
+        if (name == "sum_vec") {
+            checksum = sum_vec(numbers);
+        } else if (name == "word_count") {
+            checksum = word_count(text);
+        } else if (name == "concat") {
+            checksum = concat();
+        } else {
+            std::cout << "error\tunknown benchmark \"" << name << "\"" << std::endl;
+            continue;
+        }
+        auto elapsed = std::chrono::duration_cast<std::chrono::nanoseconds>(std::chrono::steady_clock::now() - started);
+        std::cout << elapsed.count() << "\t" << checksum << std::endl;  // endl flushes
+    }
+    return 0;
+}
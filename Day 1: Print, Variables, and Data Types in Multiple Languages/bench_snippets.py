@@ -0,0 +1,61 @@
+# bench_snippets.py - the Python side of bench_harness.py
+# Reads a benchmark name per line on stdin, runs that snippet once and answers with
+#   <nanoseconds> <checksum>
+# (tab-separated), exactly like bench_snippets.rs. Inputs are built once at import.
+# run_one() takes the clock as an argument so the tests can drive it with a fake one.
+
+import sys
+import time
+
+# Sizes and inputs must match bench_snippets.rs and bench_snippets.cpp.
+SUM_LEN = 1_000_000
+WORD_COUNT = 200_000
+CONCAT_PIECES = 100_000
+WORDS = ["the", "quick", "brown", "fox", "jumps", "over", "the", "lazy", "dog"]
+PIECES = ["tic", "tac", "toe"]
+
+NUMBERS = [i % 1000 - 500 for i in range(SUM_LEN)]
+TEXT = " ".join(WORDS[i % len(WORDS)] for i in range(WORD_COUNT))
+
+
+def sum_vec():
+    return sum(NUMBERS)
+
+
+def word_count():
+    """Checksum: distinct words * 10**6 + count of "the"."""
+    counts = {}
+    for word in TEXT.split():
+        counts[word] = counts.get(word, 0) + 1
+    return len(counts) * 1_000_000 + counts.get("the", 0)
+
+
+def concat():
+    s = ""
+    for i in range(CONCAT_PIECES):
+        s = s + PIECES[i % len(PIECES)]  # CPython can often extend s in place here.
+    return len(s)
+
+
+SNIPPETS = {"sum_vec": sum_vec, "word_count": word_count, "concat": concat}
+
+
+def run_one(name, clock=time.perf_counter_ns):
+    """Runs one snippet; returns (elapsed nanoseconds by `clock`, checksum)."""
+    snippet = SNIPPETS[name]
+    started = clock()
+    checksum = snippet()
+    return clock() - started, checksum
+
+
+def main():
+    for line in sys.stdin:
+        name = line.strip()
+        if name in SNIPPETS:
+            print("%d\t%d" % run_one(name), flush=True)
+        else:
+            print("error\tunknown benchmark %r" % name, flush=True)
+
+
+if __name__ == "__main__":
+    main()
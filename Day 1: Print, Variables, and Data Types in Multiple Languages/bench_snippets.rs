@@ -0,0 +1,82 @@
+// bench_snippets.rs
+// The Rust side of bench_harness.py. The harness writes a benchmark name per line on stdin;
+// this program runs that snippet once and answers with one tab-separated line:
+//   <nanoseconds> <checksum>
+// Inputs are built once at startup, so only the snippet itself is timed. The checksum lets
+// the harness check that the Rust, C++ and Python versions computed the same thing.
+//
+// Build: rustc --edition 2021 -C opt-level=3 bench_snippets.rs -o target/bench_snippets_rust
+// (A debug build is 10-50x slower; see LEVEL 13 in Day001.003.rs.)
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::io::{self, BufRead, Write};
+use std::time::Instant;
+
+// Sizes and inputs must match bench_snippets.cpp and bench_snippets.py.
+const SUM_LEN: i32 = 1_000_000;
+const WORD_COUNT: usize = 200_000;
+const CONCAT_PIECES: usize = 100_000;
+const WORDS: [&str; 9] = ["the", "quick", "brown", "fox", "jumps", "over", "the", "lazy", "dog"];
+const PIECES: [&str; 3] = ["tic", "tac", "toe"];
+
+struct Inputs {
+    numbers: Vec<i32>,
+    text: String,
+}
+
+// Summing a `Vec<i32>` (into an i64, so a million values can't overflow).
+fn sum_vec(numbers: &[i32]) -> i64 {
+    numbers.iter().map(|&n| i64::from(n)).sum()
+}
+
+// A word count with `HashMap::entry`, as in LEVEL 5. Checksum: distinct words * 10^6 + count of "the".
+fn word_count(text: &str) -> i64 {
+    let mut counts: HashMap<&str, i64> = HashMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts.len() as i64 * 1_000_000 + counts.get("the").copied().unwrap_or(0)
+}
+
+// `s1_concat + &s2_concat` in a loop: `+` takes the left String by value and appends in place.
+#[allow(clippy::assign_op_pattern)] // `s += piece` is the same thing; `+` is what the lesson shows.
+fn concat() -> i64 {
+    let mut s = String::new();
+    for i in 0..CONCAT_PIECES {
+        s = s + PIECES[i % PIECES.len()];
+    }
+    s.len() as i64
+}
+
+fn run(name: &str, inputs: &Inputs) -> Option<i64> {
+    match name {
+        "sum_vec" => Some(sum_vec(black_box(&inputs.numbers))),
+        "word_count" => Some(word_count(black_box(&inputs.text))),
+        "concat" => Some(concat()),
+        _ => None,
+    }
+}
+
+fn main() {
+    let inputs = Inputs {
+        numbers: (0..SUM_LEN).map(|i| i % 1000 - 500).collect(),
+        text: (0..WORD_COUNT).map(|i| WORDS[i % WORDS.len()]).collect::<Vec<_>>().join(" "),
+    };
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        let name = line.trim();
+        let started = Instant::now();
+        let result = run(name, &inputs);
+        let elapsed = started.elapsed().as_nanos();
+        let reply = match result {
+            Some(checksum) => writeln!(out, "{}\t{}", elapsed, black_box(checksum)),
+            None => writeln!(out, "error\tunknown benchmark {:?}", name),
+        };
+        if reply.and_then(|_| out.flush()).is_err() {
+            break; // The harness went away.
+        }
+    }
+}
@@ -0,0 +1,32 @@
+#!/bin/bash
+# Builds the benchmark snippets and runs the cross-language comparison (Linux):
+#   1. bench_snippets.rs  -> target/bench_snippets_rust (optimised, like --release)
+#   2. bench_snippets.cpp -> target/bench_snippets_cpp (-O2)
+#   3. bench_harness.py times them and bench_snippets.py and prints the table
+#
+#   ./build_bench.sh --runs 100     extra arguments go to bench_harness.py
+#   ./build_bench.sh --test         runs test_bench_harness.py instead of the benchmarks
+set -e
+
+cd "$(dirname "$0")"
+CXX="${CXX:-c++}"
+OUT="target"
+
+mkdir -p "$OUT"
+
+echo "Compiling bench_snippets.rs -> $OUT/bench_snippets_rust"
+rustc --edition 2021 -C opt-level=3 bench_snippets.rs -o "$OUT/bench_snippets_rust"
+
+echo "Compiling bench_snippets.cpp -> $OUT/bench_snippets_cpp"
+"$CXX" -std=c++17 -Wall -Wextra -O2 bench_snippets.cpp -o "$OUT/bench_snippets_cpp"
+
+BINARIES=("$OUT/bench_snippets_rust" "$OUT/bench_snippets_cpp")
+
+if [ "$1" = "--test" ]; then
+    echo
+    echo "===== Tests ====="
+    python3 test_bench_harness.py "${BINARIES[@]}"
+else
+    echo
+    python3 bench_harness.py "$@" "${BINARIES[@]}"
+fi
@@ -0,0 +1,50 @@
+#!/bin/bash
+# Builds the Day 1 C ABI library and its Python / C++ demos (Linux):
+#   1. day1_ffi.rs        -> target/libday1_ffi.so  (rustc --crate-type cdylib)
+#   2. day1_ffi_header.rs -> day1_ffi.h             (regenerated from day1_ffi.rs)
+#   3. Day1001_ffi.cpp    -> target/Day1001_ffi     (linked against the library)
+#   4. runs both demos
+#
+#   ./build_day1_ffi.sh --test   also runs test_day1_ffi.py and test_day1_ffi.cpp,
+#                                and fails if the committed day1_ffi.h is stale.
+set -e
+
+cd "$(dirname "$0")"
+CXX="${CXX:-c++}"
+OUT="target"
+RUN_TESTS=0
+[ "$1" = "--test" ] && RUN_TESTS=1
+
+mkdir -p "$OUT"
+
+echo "Compiling day1_ffi.rs -> $OUT/libday1_ffi.so"
+rustc --edition 2021 --crate-type cdylib -O day1_ffi.rs -o "$OUT/libday1_ffi.so"
+
+echo "Generating day1_ffi.h"
+rustc --edition 2021 -O day1_ffi_header.rs -o "$OUT/day1_ffi_header"
+"$OUT/day1_ffi_header" day1_ffi.rs > "$OUT/day1_ffi.h"
+if [ "$RUN_TESTS" = 1 ] && ! cmp -s "$OUT/day1_ffi.h" day1_ffi.h; then
+    echo "day1_ffi.h is out of date; run ./build_day1_ffi.sh and commit the result" >&2
+    diff -u day1_ffi.h "$OUT/day1_ffi.h" >&2 || true
+    exit 1
+fi
+cp "$OUT/day1_ffi.h" day1_ffi.h
+
+# -Wl,-rpath,'$ORIGIN' lets the binary find libday1_ffi.so next to itself.
+echo "Compiling Day1001_ffi.cpp -> $OUT/Day1001_ffi"
+"$CXX" -std=c++17 -Wall -Wextra -I. Day1001_ffi.cpp -L"$OUT" -lday1_ffi -Wl,-rpath,'$ORIGIN' -o "$OUT/Day1001_ffi"
+
+echo
+echo "===== Python (ctypes) ====="
+python3 Day1001_ffi.py "$OUT/libday1_ffi.so"
+echo
+echo "===== C++ ====="
+"$OUT/Day1001_ffi"
+
+if [ "$RUN_TESTS" = 1 ]; then
+    echo
+    echo "===== Tests ====="
+    python3 test_day1_ffi.py "$OUT/libday1_ffi.so"
+    "$CXX" -std=c++17 -Wall -Wextra -I. test_day1_ffi.cpp -L"$OUT" -lday1_ffi -Wl,-rpath,'$ORIGIN' -o "$OUT/test_day1_ffi"
+    "$OUT/test_day1_ffi"
+fi
@@ -0,0 +1,36 @@
+#!/bin/bash
+# Builds Day001.003.rs with the LEVEL 12 FFI lesson switched on:
+#   1. compiles ffi_lesson.c into target/libffi_lesson.a
+#   2. compiles Day001.003.rs with `--cfg ffi_lesson`, linking that library
+#   3. runs the result (extra arguments are passed through)
+#
+#   ./build_ffi_lesson.sh --test   builds and runs the tests instead, including the
+#                                  ffi_lesson ones that a plain `rustc --test` leaves out.
+set -e
+
+cd "$(dirname "$0")"
+CC="${CC:-cc}"
+OUT="target"
+
+mkdir -p "$OUT"
+
+echo "Compiling ffi_lesson.c -> $OUT/libffi_lesson.a"
+"$CC" -O2 -Wall -Wextra -c ffi_lesson.c -o "$OUT/ffi_lesson.o"
+ar rcs "$OUT/libffi_lesson.a" "$OUT/ffi_lesson.o"
+
+if [ "$1" = "--test" ]; then
+    echo "Compiling Day001.003.rs tests with --cfg ffi_lesson"
+    rustc --edition 2021 --test --crate-name day001_003 --cfg ffi_lesson \
+        -L "native=$OUT" -l m \
+        -o "$OUT/day001_003_tests" Day001.003.rs
+    "$OUT/day001_003_tests"
+    exit
+fi
+
+echo "Compiling Day001.003.rs with --cfg ffi_lesson"
+rustc --edition 2021 --crate-name day001_003 --cfg ffi_lesson \
+    -L "native=$OUT" -l m \
+    -o "$OUT/day001_003" Day001.003.rs
+
+echo "Running $OUT/day001_003"
+"$OUT/day001_003" "$@"
@@ -0,0 +1,41 @@
+#!/bin/bash
+# Builds the numeric limits probes and regenerates limits_matrix.md (Linux):
+#   1. limits_probe.rs  -> target/limits_probe_debug and target/limits_probe_release
+#   2. limits_probe.cpp -> target/limits_probe_cpp
+#   3. limits_matrix.py runs them and limits_probe.py -> limits_matrix.md
+#
+#   ./build_limits_matrix.sh --test   also runs test_limits_matrix.py,
+#                                     and fails if the committed limits_matrix.md is stale.
+set -e
+
+cd "$(dirname "$0")"
+CXX="${CXX:-c++}"
+OUT="target"
+RUN_TESTS=0
+[ "$1" = "--test" ] && RUN_TESTS=1
+
+mkdir -p "$OUT"
+
+echo "Compiling limits_probe.rs (debug and release)"
+rustc --edition 2021 limits_probe.rs -o "$OUT/limits_probe_debug"
+rustc --edition 2021 -O limits_probe.rs -o "$OUT/limits_probe_release"
+
+echo "Compiling limits_probe.cpp -> $OUT/limits_probe_cpp"
+"$CXX" -std=c++17 -Wall -Wextra -O2 limits_probe.cpp -o "$OUT/limits_probe_cpp"
+
+PROBES=("$OUT/limits_probe_debug" "$OUT/limits_probe_release" "$OUT/limits_probe_cpp")
+
+echo "Generating limits_matrix.md"
+python3 limits_matrix.py "${PROBES[@]}" > "$OUT/limits_matrix.md"
+if [ "$RUN_TESTS" = 1 ] && ! cmp -s "$OUT/limits_matrix.md" limits_matrix.md; then
+    echo "limits_matrix.md is out of date; run ./build_limits_matrix.sh and commit the result" >&2
+    diff -u limits_matrix.md "$OUT/limits_matrix.md" >&2 || true
+    exit 1
+fi
+cp "$OUT/limits_matrix.md" limits_matrix.md
+
+if [ "$RUN_TESTS" = 1 ]; then
+    echo
+    echo "===== Tests ====="
+    python3 test_limits_matrix.py "${PROBES[@]}"
+fi
@@ -0,0 +1,68 @@
+// day1_ffi.h - GENERATED from day1_ffi.rs by day1_ffi_header.rs. Do not edit by hand;
+// run ./build_day1_ffi.sh after changing day1_ffi.rs.
+
+#ifndef DAY1_FFI_H
+#define DAY1_FFI_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Same fields as `Rectangle` in Day1001.rs; `#[repr(C)]` fixes the layout.
+typedef struct Day1Rectangle {
+    uint32_t width;
+    uint32_t height;
+} Day1Rectangle;
+
+// Status codes returned by the parse functions.
+#define DAY1_OK 0
+#define DAY1_ERR_NULL 1 // A required pointer was NULL.
+#define DAY1_ERR_UTF8 2 // The input is not valid UTF-8.
+#define DAY1_ERR_PARSE 3 // Not a number, or out of range.
+
+// --- longest_slice ---
+// Returns whichever of `x` and `y` has more bytes (`y` on a tie, like the original),
+// or NULL if either is NULL. No copy is made: the result points into one of the
+// inputs, so it is only valid as long as that input is. This is the lifetime
+// `'a` in `fn longest_slice<'a>(x: &'a str, y: &'a str) -> &'a str`, which C
+// cannot express and the caller has to remember.
+const char* day1_longest_slice(const char* x, const char* y);
+
+// Returns a pointer to the largest of `len` elements at `data`, or NULL if `len` is 0.
+const int32_t* day1_largest_i32(const int32_t* data, size_t len);
+
+// As `day1_largest_i32`. NaN is never larger than anything, so it is only
+// returned when it is the first element.
+const double* day1_largest_f64(const double* data, size_t len);
+
+// --- Rectangle ---
+// Methods become plain functions taking the struct explicitly: `rect.area()` is
+// `day1_rectangle_area(rect)`. The area is widened to u64 so it cannot overflow.
+uint64_t day1_rectangle_area(Day1Rectangle rect);
+
+bool day1_rectangle_can_hold(Day1Rectangle rect, Day1Rectangle other);
+
+// --- parse_number family ---
+// `Result<T, String>` has no C equivalent. The C shape is: a DAY1_* status code
+// as the return value, the number through `out` (written only on DAY1_OK), and
+// the error text on request from `day1_last_error`.
+int32_t day1_parse_i32(const char* s, int32_t* out);
+
+int32_t day1_parse_i64(const char* s, int64_t* out);
+
+int32_t day1_parse_f64(const char* s, double* out);
+
+// Copies the last error message of this thread into `buf` (always NUL-terminated
+// when `buf_len > 0`) and returns the full message length, like snprintf. Rust
+// keeps ownership of its String; C only ever sees a copy in its own buffer.
+size_t day1_last_error(char* buf, size_t buf_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // DAY1_FFI_H
@@ -0,0 +1,187 @@
+// day1_ffi.rs
+// A few of the Day 1 Rust examples, exported over the C ABI so that the Python
+// and C++ programs in this folder can call them:
+//
+//   longest_slice       (Day001.002.rs, lifetimes)   -> day1_longest_slice
+//   largest             (Day1001.rs, generics)       -> day1_largest_i32 / day1_largest_f64
+//   Rectangle::area     (Day1001.rs, methods)        -> day1_rectangle_area
+//   Rectangle::can_hold (Day1001.rs, methods)        -> day1_rectangle_can_hold
+//   parse_number family (Day001.002/003.rs, Result)  -> day1_parse_i32 / day1_parse_i64 / day1_parse_f64
+//
+// Build (see build_day1_ffi.sh):
+//   rustc --edition 2021 --crate-type cdylib -O day1_ffi.rs   -> libday1_ffi.so
+//
+// The C declarations live in day1_ffi.h, which is generated from this file by
+// day1_ffi_header.rs. Rules every function here follows:
+//   - `#[no_mangle] pub extern "C" fn`, so the symbol name is exactly the Rust name.
+//   - Only C-compatible types cross the boundary: integers, floats, bool,
+//     `#[repr(C)]` structs and raw pointers.
+//   - Never panic: a panic cannot unwind into C. Bad input becomes a return value.
+
+// The safety contract of each function is in its comment, which is copied into day1_ffi.h
+// where C callers will read it.
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr};
+
+// Same fields as `Rectangle` in Day1001.rs; `#[repr(C)]` fixes the layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Day1Rectangle {
+    pub width: u32,
+    pub height: u32,
+}
+
+// Status codes returned by the parse functions.
+pub const DAY1_OK: i32 = 0;
+pub const DAY1_ERR_NULL: i32 = 1; // A required pointer was NULL.
+pub const DAY1_ERR_UTF8: i32 = 2; // The input is not valid UTF-8.
+pub const DAY1_ERR_PARSE: i32 = 3; // Not a number, or out of range.
+
+thread_local! {
+    // The message for the most recent failed parse on this thread, like C's `errno` + `strerror`.
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+fn set_last_error(message: String) {
+    LAST_ERROR.with(|e| *e.borrow_mut() = message);
+}
+
+// Borrows a C string as `&str`. The caller guarantees `s` is NULL or NUL-terminated.
+unsafe fn str_arg<'a>(s: *const c_char) -> Result<&'a str, i32> {
+    if s.is_null() {
+        set_last_error("input is NULL".to_string());
+        return Err(DAY1_ERR_NULL);
+    }
+    CStr::from_ptr(s).to_str().map_err(|e| {
+        set_last_error(format!("input is not valid UTF-8: {}", e));
+        DAY1_ERR_UTF8
+    })
+}
+
+// --- longest_slice ---
+// Returns whichever of `x` and `y` has more bytes (`y` on a tie, like the original),
+// or NULL if either is NULL. No copy is made: the result points into one of the
+// inputs, so it is only valid as long as that input is. This is the lifetime
+// `'a` in `fn longest_slice<'a>(x: &'a str, y: &'a str) -> &'a str`, which C
+// cannot express and the caller has to remember.
+#[no_mangle]
+pub unsafe extern "C" fn day1_longest_slice(x: *const c_char, y: *const c_char) -> *const c_char {
+    if x.is_null() || y.is_null() {
+        return std::ptr::null();
+    }
+    if CStr::from_ptr(x).to_bytes().len() > CStr::from_ptr(y).to_bytes().len() {
+        x
+    } else {
+        y
+    }
+}
+
+// --- largest ---
+// Generic functions cannot be exported: C needs one concrete symbol per type.
+// So `largest<T>` is written once and instantiated for each exported type.
+// The original panics on an empty slice; here that returns NULL instead.
+fn largest<T: PartialOrd>(list: &[T]) -> Option<&T> {
+    let mut largest = list.first()?;
+    for item in list {
+        if item > largest {
+            largest = item;
+        }
+    }
+    Some(largest)
+}
+
+unsafe fn slice_arg<'a, T>(data: *const T, len: usize) -> &'a [T] {
+    if data.is_null() || len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(data, len)
+    }
+}
+
+// Returns a pointer to the largest of `len` elements at `data`, or NULL if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn day1_largest_i32(data: *const i32, len: usize) -> *const i32 {
+    largest(slice_arg(data, len)).map_or(std::ptr::null(), |x| x as *const i32)
+}
+
+// As `day1_largest_i32`. NaN is never larger than anything, so it is only
+// returned when it is the first element.
+#[no_mangle]
+pub unsafe extern "C" fn day1_largest_f64(data: *const f64, len: usize) -> *const f64 {
+    largest(slice_arg(data, len)).map_or(std::ptr::null(), |x| x as *const f64)
+}
+
+// --- Rectangle ---
+// Methods become plain functions taking the struct explicitly: `rect.area()` is
+// `day1_rectangle_area(rect)`. The area is widened to u64 so it cannot overflow.
+#[no_mangle]
+pub extern "C" fn day1_rectangle_area(rect: Day1Rectangle) -> u64 {
+    u64::from(rect.width) * u64::from(rect.height)
+}
+
+#[no_mangle]
+pub extern "C" fn day1_rectangle_can_hold(rect: Day1Rectangle, other: Day1Rectangle) -> bool {
+    rect.width > other.width && rect.height > other.height
+}
+
+// `parse_number` for any `FromStr` type, reshaped for C (see below).
+unsafe fn parse_into<T: std::str::FromStr>(s: *const c_char, out: *mut T) -> i32
+where
+    T::Err: std::fmt::Display,
+{
+    let text = match str_arg(s) {
+        Ok(text) => text,
+        Err(code) => return code,
+    };
+    if out.is_null() {
+        set_last_error("output pointer is NULL".to_string());
+        return DAY1_ERR_NULL;
+    }
+    match text.parse::<T>() {
+        Ok(num) => {
+            *out = num;
+            DAY1_OK
+        }
+        Err(parse_err) => {
+            set_last_error(format!("Failed to parse '{}': {}", text, parse_err));
+            DAY1_ERR_PARSE
+        }
+    }
+}
+
+// --- parse_number family ---
+// `Result<T, String>` has no C equivalent. The C shape is: a DAY1_* status code
+// as the return value, the number through `out` (written only on DAY1_OK), and
+// the error text on request from `day1_last_error`.
+#[no_mangle]
+pub unsafe extern "C" fn day1_parse_i32(s: *const c_char, out: *mut i32) -> i32 {
+    parse_into(s, out)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn day1_parse_i64(s: *const c_char, out: *mut i64) -> i32 {
+    parse_into(s, out)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn day1_parse_f64(s: *const c_char, out: *mut f64) -> i32 {
+    parse_into(s, out)
+}
+
+// Copies the last error message of this thread into `buf` (always NUL-terminated
+// when `buf_len > 0`) and returns the full message length, like snprintf. Rust
+// keeps ownership of its String; C only ever sees a copy in its own buffer.
+#[no_mangle]
+pub unsafe extern "C" fn day1_last_error(buf: *mut c_char, buf_len: usize) -> usize {
+    LAST_ERROR.with(|e| {
+        let message = e.borrow();
+        if !buf.is_null() && buf_len > 0 {
+            let n = message.len().min(buf_len - 1);
+            std::ptr::copy_nonoverlapping(message.as_ptr(), buf as *mut u8, n);
+            *buf.add(n) = 0;
+        }
+        message.len()
+    })
+}
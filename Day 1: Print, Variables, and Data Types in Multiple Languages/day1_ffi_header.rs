@@ -0,0 +1,161 @@
+// day1_ffi_header.rs
+// Generates day1_ffi.h from day1_ffi.rs, so the C declarations cannot drift
+// from the Rust definitions. A tiny stand-in for cbindgen that only understands
+// the shapes day1_ffi.rs uses:
+//   - `#[repr(C)] pub struct` with primitive fields     -> typedef struct
+//   - `pub const NAME: i32 = value;`                      -> #define
+//   - `#[no_mangle] pub [unsafe] extern "C" fn`           -> prototype
+// The `//` comment lines directly above an item are copied along with it.
+//
+// Usage:
+//   rustc --edition 2021 -O day1_ffi_header.rs -o target/day1_ffi_header
+//   target/day1_ffi_header [day1_ffi.rs] > day1_ffi.h
+
+use std::env;
+use std::fs;
+use std::process;
+
+fn c_type(rust: &str) -> Result<String, String> {
+    let rust = rust.trim();
+    if let Some(inner) = rust.strip_prefix("*const ") {
+        return Ok(format!("const {}*", c_type(inner)?));
+    }
+    if let Some(inner) = rust.strip_prefix("*mut ") {
+        return Ok(format!("{}*", c_type(inner)?));
+    }
+    Ok(match rust {
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "usize" => "size_t",
+        "isize" => "ptrdiff_t",
+        "f32" => "float",
+        "f64" => "double",
+        "bool" => "bool",
+        "c_char" => "char",
+        "()" => "void",
+        other if other.starts_with("Day1") => other,
+        other => return Err(format!("no C equivalent for Rust type `{}`", other)),
+    }
+    .to_string())
+}
+
+// "a: *const i32, len: usize" -> "const int32_t* a, size_t len"
+fn c_params(params: &str) -> Result<String, String> {
+    let params: Vec<&str> = params.split(',').map(str::trim).filter(|p| !p.is_empty()).collect();
+    if params.is_empty() {
+        return Ok("void".to_string());
+    }
+    let mut out = Vec::new();
+    for param in params {
+        let (name, ty) = param.split_once(':').ok_or_else(|| format!("cannot read parameter `{}`", param))?;
+        out.push(format!("{} {}", c_type(ty)?, name.trim()));
+    }
+    Ok(out.join(", "))
+}
+
+fn generate(source: &str) -> Result<String, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut body = String::new();
+    let mut comment: Vec<String> = Vec::new();
+    let mut exported = false;
+    let mut repr_c = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        i += 1;
+        if let Some(text) = line.strip_prefix("//") {
+            comment.push(format!("//{}", text));
+            continue;
+        }
+        match line {
+            "#[no_mangle]" => {
+                exported = true;
+                continue;
+            }
+            "#[repr(C)]" => {
+                repr_c = true;
+                continue;
+            }
+            _ if line.starts_with("#[") => continue, // Other attributes keep the pending comment.
+            _ => {}
+        }
+
+        let mut item = String::new();
+        if repr_c && line.starts_with("pub struct ") {
+            let name = line["pub struct ".len()..].trim_end_matches('{').trim();
+            item.push_str(&format!("typedef struct {} {{\n", name));
+            while i < lines.len() && lines[i].trim() != "}" {
+                let field = lines[i].trim().trim_start_matches("pub ").trim_end_matches(',');
+                let (fname, fty) = field.split_once(':').ok_or_else(|| format!("cannot read field `{}`", field))?;
+                item.push_str(&format!("    {} {};\n", c_type(fty)?, fname.trim()));
+                i += 1;
+            }
+            i += 1;
+            item.push_str(&format!("}} {};\n", name));
+        } else if let Some(rest) = line.strip_prefix("pub const ") {
+            // `pub const NAME: i32 = 1; // note`
+            let (decl, note) = rest.split_once("//").map_or((rest, None), |(d, n)| (d, Some(n.trim())));
+            let (name, value) = decl.split_once(':').and_then(|(n, v)| Some((n.trim(), v.split_once('=')?.1)))
+                .ok_or_else(|| format!("cannot read constant `{}`", line))?;
+            item.push_str(&format!("#define {} {}", name, value.trim().trim_end_matches(';')));
+            if let Some(note) = note {
+                item.push_str(&format!(" // {}", note));
+            }
+            item.push('\n');
+        } else if exported && line.contains("extern \"C\" fn ") {
+            let mut sig = line.to_string();
+            while !sig.contains('{') && i < lines.len() {
+                sig.push(' ');
+                sig.push_str(lines[i].trim());
+                i += 1;
+            }
+            let after_fn = sig.split("extern \"C\" fn ").nth(1).unwrap_or_default();
+            let (name, rest) = after_fn.split_once('(').ok_or_else(|| format!("cannot read `{}`", line))?;
+            let (params, rest) = rest.rsplit_once(')').ok_or_else(|| format!("cannot read `{}`", line))?;
+            let ret = rest.trim().trim_end_matches('{').trim().strip_prefix("->").unwrap_or("()");
+            item.push_str(&format!("{} {}({});\n", c_type(ret)?, name.trim(), c_params(params)?));
+        }
+
+        if !item.is_empty() {
+            if !(comment.is_empty() && item.starts_with("#define")) {
+                body.push('\n'); // Runs of #defines stay together.
+            }
+            for c in &comment {
+                body.push_str(c);
+                body.push('\n');
+            }
+            body.push_str(&item);
+        }
+        comment.clear();
+        exported = false;
+        repr_c = false;
+    }
+
+    Ok(format!(
+        "// day1_ffi.h - GENERATED from day1_ffi.rs by day1_ffi_header.rs. Do not edit by hand;\n\
+         // run ./build_day1_ffi.sh after changing day1_ffi.rs.\n\n\
+         #ifndef DAY1_FFI_H\n#define DAY1_FFI_H\n\n\
+         #include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n\n\
+         #ifdef __cplusplus\nextern \"C\" {{\n#endif\n{}\n\
+         #ifdef __cplusplus\n}}\n#endif\n\n#endif // DAY1_FFI_H\n",
+        body
+    ))
+}
+
+fn main() {
+    let path = env::args().nth(1).unwrap_or_else(|| "day1_ffi.rs".to_string());
+    let result = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e)).and_then(|s| generate(&s));
+    match result {
+        Ok(header) => print!("{}", header),
+        Err(e) => {
+            eprintln!("day1_ffi_header: {}", e);
+            process::exit(1);
+        }
+    }
+}
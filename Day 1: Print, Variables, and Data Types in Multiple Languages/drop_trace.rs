@@ -0,0 +1,267 @@
+// drop_trace.rs
+// Makes ownership visible. `Traced<T>` wraps any value and records, in order:
+//   created   - `Traced::new`
+//   moved     - `.moved_into("...")`, called where the lesson moves the value
+//               (Rust moves are plain memcpys, so nothing can observe them automatically)
+//   cloned    - `.clone()`; the copy gets its own label, e.g. "alice (clone)"
+//   dropped   - the value's `Drop`, i.e. the exact moment Rust frees it
+//
+// `section` runs a block of lesson code and returns its events; when tracing is on
+// (`--trace-drops`) it also prints them as a timeline. The tests at the end run small
+// scenarios whose drop order the language guarantees and compare it with what was recorded:
+//
+//   rustc --edition 2021 --test drop_trace.rs -o target/drop_trace_test && target/drop_trace_test
+//
+// Shared by Day001.002.rs and Day001.003.rs through `mod drop_trace;`.
+
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    Created,
+    Moved(String), // Where to, e.g. "names[0]".
+    Cloned(String), // Label of the original.
+    Dropped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub step: usize,
+    pub label: String,
+    pub kind: EventKind,
+}
+
+thread_local! {
+    static EVENTS: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+    static PRINT_TIMELINES: Cell<bool> = const { Cell::new(false) };
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+    static OPEN_SECTIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+// Only inside a `section`, and only when something will read the events. Otherwise
+// the lesson's own heap numbers (`--track-alloc`) would include the trace's bookkeeping.
+fn recording() -> bool {
+    RECORDING.with(|r| r.get()) && OPEN_SECTIONS.with(|n| n.get()) > 0
+}
+
+fn record(label: &str, kind: EventKind) {
+    EVENTS.with(|events| {
+        let mut events = events.borrow_mut();
+        let step = events.len() + 1;
+        events.push(Event { step, label: label.to_string(), kind });
+    });
+}
+
+pub fn enable_timelines() {
+    PRINT_TIMELINES.with(|p| p.set(true));
+    RECORDING.with(|r| r.set(true));
+}
+
+// Labels are string literals, so a `Traced` value allocates nothing of its own.
+pub struct Traced<T> {
+    label: Cow<'static, str>,
+    value: T,
+}
+
+impl<T> Traced<T> {
+    pub fn new(label: &'static str, value: T) -> Traced<T> {
+        if recording() {
+            record(label, EventKind::Created);
+        }
+        Traced { label: Cow::Borrowed(label), value }
+    }
+
+    // Records a move and hands the value on: `names.push(alice.moved_into("names[0]"))`.
+    pub fn moved_into(self, destination: &str) -> Traced<T> {
+        if recording() {
+            record(&self.label, EventKind::Moved(destination.to_string()));
+        }
+        self
+    }
+}
+
+impl<T: Clone> Clone for Traced<T> {
+    fn clone(&self) -> Traced<T> {
+        if !recording() {
+            return Traced { label: self.label.clone(), value: self.value.clone() };
+        }
+        let label = format!("{} (clone)", self.label);
+        record(&label, EventKind::Cloned(self.label.to_string()));
+        Traced { label: Cow::Owned(label), value: self.value.clone() }
+    }
+}
+
+impl<T> Drop for Traced<T> {
+    fn drop(&mut self) {
+        if recording() {
+            record(&self.label, EventKind::Dropped);
+        }
+    }
+}
+
+impl<T> Deref for Traced<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Traced<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Traced<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f) // Print like the wrapped value, so lessons read the same.
+    }
+}
+
+// Runs `f` and returns the events it produced, numbered from 1 (none unless recording
+// is on). Everything `f` created and did not hand back out has been dropped by the time
+// it returns. The events are taken out of the log, so it is empty between sections.
+pub fn section(title: &str, f: impl FnOnce()) -> Vec<Event> {
+    let start = EVENTS.with(|events| events.borrow().len());
+    OPEN_SECTIONS.with(|n| n.set(n.get() + 1));
+    f();
+    OPEN_SECTIONS.with(|n| n.set(n.get() - 1));
+    let events: Vec<Event> = EVENTS.with(|events| {
+        events
+            .borrow_mut()
+            .drain(start..)
+            .enumerate()
+            .map(|(i, e)| Event { step: i + 1, ..e })
+            .collect()
+    });
+    if PRINT_TIMELINES.with(|p| p.get()) {
+        print!("{}", render(title, &events));
+    }
+    events
+}
+
+pub fn render(title: &str, events: &[Event]) -> String {
+    let width = events.iter().map(|e| e.label.len()).max().unwrap_or(0);
+    let mut out = format!("  [drop trace] {}\n", title);
+    for e in events {
+        let (mark, what) = match &e.kind {
+            EventKind::Created => ('+', "created".to_string()),
+            EventKind::Moved(to) => ('>', format!("moved into {}", to)),
+            EventKind::Cloned(from) => ('=', format!("cloned from {}", from)),
+            EventKind::Dropped => ('-', "dropped".to_string()),
+        };
+        out.push_str(&format!("    {:>3}. {} {:<width$}  {}\n", e.step, mark, e.label, what, width = width));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The labels `f` dropped, in order.
+    fn dropped(f: impl FnOnce()) -> Vec<String> {
+        RECORDING.with(|r| r.set(true));
+        let events = section("test", f);
+        events.into_iter().filter(|e| e.kind == EventKind::Dropped).map(|e| e.label).collect()
+    }
+
+    #[test]
+    fn locals_drop_in_reverse_declaration_order() {
+        assert_eq!(dropped(|| {
+            let _a = Traced::new("a", ());
+            let _b = Traced::new("b", ());
+            let _c = Traced::new("c", ());
+        }), ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn struct_fields_drop_in_declaration_order() {
+        assert_eq!(dropped(|| {
+            struct Book {
+                _title: Traced<&'static str>,
+                _author: Traced<&'static str>,
+            }
+            let _book = Book { _title: Traced::new("title", "Rust"), _author: Traced::new("author", "Jon") };
+        }), ["title", "author"]);
+    }
+
+    #[test]
+    fn vec_elements_drop_in_index_order() {
+        assert_eq!(dropped(|| {
+            let _names: Vec<Traced<&str>> = ["alice", "bob", "carol"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| Traced::new(name, *name).moved_into(&format!("names[{}]", i)))
+                .collect();
+        }), ["alice", "bob", "carol"]);
+    }
+
+    #[test]
+    fn a_moved_value_drops_with_its_new_owner() {
+        assert_eq!(dropped(|| {
+            let a = Traced::new("a", ());
+            let _b = Traced::new("b", ());
+            let _owner = Box::new(a.moved_into("owner")); // Declared last, so dropped first, and `a` with it.
+        }), ["a", "b"]);
+    }
+
+    #[test]
+    fn a_clone_drops_independently() {
+        assert_eq!(dropped(|| {
+            let a = Traced::new("a", 1);
+            let _copy = a.clone();
+            drop(a); // The original can go first; the clone lives on.
+        }), ["a", "a (clone)"]);
+    }
+
+    #[test]
+    fn temporaries_drop_at_the_end_of_their_statement() {
+        assert_eq!(dropped(|| {
+            let _kept = Traced::new("kept", ());
+            let _len = Traced::new("temporary", "abc").len(); // The `Traced` itself is not kept.
+            let _later = Traced::new("later", ());
+        }), ["temporary", "later", "kept"]);
+    }
+
+    #[test]
+    fn an_rc_tree_drops_parent_before_child() {
+        assert_eq!(dropped(|| {
+            use std::rc::{Rc, Weak};
+            struct Node {
+                _name: Traced<&'static str>,
+                _parent: RefCell<Weak<Node>>,
+                children: RefCell<Vec<Rc<Node>>>,
+            }
+            let leaf = Rc::new(Node { _name: Traced::new("leaf", "leaf"), _parent: RefCell::new(Weak::new()), children: RefCell::new(vec![]) });
+            let branch = Rc::new(Node { _name: Traced::new("branch", "branch"), _parent: RefCell::new(Weak::new()), children: RefCell::new(vec![]) });
+            branch.children.borrow_mut().push(Rc::clone(&leaf));
+            *leaf._parent.borrow_mut() = Rc::downgrade(&branch);
+            // Locals drop in reverse, so `branch` goes first. It is the branch node's only
+            // strong owner, so that node dies now; its `children` Vec gives back one of the
+            // leaf's two strong counts. The leaf node dies with the `leaf` local, last.
+        }), ["branch", "leaf"]);
+    }
+
+    #[test]
+    fn nothing_is_recorded_when_off_or_outside_a_section() {
+        let events = section("off", || {
+            let _a = Traced::new("a", ());
+        });
+        assert!(events.is_empty());
+        RECORDING.with(|r| r.set(true));
+        let _outside = Traced::new("outside", ());
+        assert!(EVENTS.with(|events| events.borrow().is_empty()));
+    }
+
+    #[test]
+    fn a_section_takes_its_events_out_of_the_log() {
+        assert_eq!(dropped(|| {
+            let _a = Traced::new("a", ());
+        }), ["a"]);
+        assert!(EVENTS.with(|events| events.borrow().is_empty()));
+    }
+}
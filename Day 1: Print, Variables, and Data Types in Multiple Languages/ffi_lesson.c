@@ -0,0 +1,68 @@
+// ffi_lesson.c
+// The C half of LEVEL 12.3 in Day001.003.rs. Each function shows one thing that
+// crosses the Rust <-> C boundary:
+//   point_distance   - a struct passed by value
+//   point_scale      - a struct passed by pointer and modified in place
+//   count_vowels     - a NUL-terminated string read by C
+//   write_greeting   - a string written by C into a buffer owned by Rust
+//   fold_ints        - a callback into Rust, with a `void*` for its state
+//
+// Built into libffi_lesson.a by build_ffi_lesson.sh.
+
+#include <math.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdio.h>
+
+// Must match `#[repr(C)] struct Point2D` on the Rust side field for field.
+typedef struct {
+    double x;
+    double y;
+} Point2D;
+
+double point_distance(Point2D a, Point2D b) {
+    double dx = a.x - b.x;
+    double dy = a.y - b.y;
+    return sqrt(dx * dx + dy * dy);
+}
+
+void point_scale(Point2D *p, double factor) {
+    if (p == NULL) {
+        return;
+    }
+    p->x *= factor;
+    p->y *= factor;
+}
+
+size_t count_vowels(const char *s) {
+    size_t count = 0;
+    if (s == NULL) {
+        return 0;
+    }
+    for (; *s != '\0'; s++) {
+        switch (*s) {
+            case 'a': case 'e': case 'i': case 'o': case 'u':
+            case 'A': case 'E': case 'I': case 'O': case 'U':
+                count++;
+        }
+    }
+    return count;
+}
+
+// Like snprintf: returns the length the full greeting needs (without the NUL),
+// writes at most out_len - 1 characters plus a NUL. The caller can retry with a
+// bigger buffer when the return value is >= out_len.
+int write_greeting(const char *name, char *out, size_t out_len) {
+    return snprintf(out, out_len, "Hello from C, %s!", name);
+}
+
+// Calls `op(acc, data[i], user)` for every element, left to right.
+typedef int64_t (*fold_fn)(int64_t acc, int32_t value, void *user);
+
+int64_t fold_ints(const int32_t *data, size_t len, int64_t init, fold_fn op, void *user) {
+    int64_t acc = init;
+    for (size_t i = 0; i < len; i++) {
+        acc = op(acc, data[i], user);
+    }
+    return acc;
+}
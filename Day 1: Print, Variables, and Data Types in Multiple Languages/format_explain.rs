@@ -0,0 +1,114 @@
+// format_explain.rs
+// Explains any Rust format spec, part by part, and shows what it does to a value.
+// Section 1 of Day001.001.rs shows `{:b}`, `{:05}`, `{:-^20}` and friends one at a time;
+// this takes whatever placeholder you type.
+//
+// Usage:
+//   rustc --edition 2021 format_explain.rs -o target/format_explain
+//   target/format_explain '{:_^12.3}' 3.14159   # explain one placeholder, with a value
+//   target/format_explain '{:#x}'               # ...or with an example value
+//   target/format_explain --repl                # type `{:_^12.3} 3.14159` at the prompt
+// Tests (format_spec's renderer against format!, its parser against rustc):
+//   rustc --edition 2021 --test format_explain.rs -o target/format_explain_test && target/format_explain_test
+//
+// Values are written as in Rust: 42, -1i8, 255u8, 2.5, 1e3f32, NaN, 'c', "text", true.
+// Anything else, such as an unquoted `[1, 2]`, is refused rather than shown as a string.
+
+#[allow(dead_code)] // Whole format strings are for format_translate.rs.
+mod format_spec;
+
+use format_spec::{Placeholder, Value};
+use std::io::{self, BufRead, Write};
+use std::process;
+
+fn show(input: &str, placeholder: &Placeholder, value: Option<&str>) -> Result<(), String> {
+    println!("{}", input);
+    for (part, words) in format_spec::explain(placeholder) {
+        println!("  {:<10} {}", part, words);
+    }
+    let value = match value {
+        Some(text) => Value::parse(text)?,
+        None => format_spec::example_value(&placeholder.spec),
+    };
+    let rendered = format_spec::render(&placeholder.spec, &value)?;
+    println!("  format!(\"{}\", {}) => [{}]", input, value.to_rust(), rendered);
+    Ok(())
+}
+
+fn explain_line(line: &str) {
+    match format_spec::parse_prefix(line) {
+        Err(e) => print!("{}", e.render(line)),
+        Ok((placeholder, rest)) => {
+            let spec_text = &line[..line.len() - rest.len()];
+            let value = Some(rest.trim()).filter(|v| !v.is_empty());
+            if let Err(e) = show(spec_text, &placeholder, value) {
+                println!("  {}", e);
+            }
+        }
+    }
+}
+
+fn repl() {
+    println!("Type a placeholder and, optionally, a value: {{:_^12.3}} 3.14159   (`quit` to leave)");
+    let stdin = io::stdin();
+    loop {
+        print!("fmt> ");
+        io::stdout().flush().ok();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            println!();
+            break;
+        }
+        let line = line.trim();
+        match line {
+            "" => continue,
+            "quit" | "exit" => break,
+            "help" => println!("Placeholders look like {{[argument]:[[fill]align][sign][#][0][width][.precision][type]}}"),
+            _ => explain_line(line),
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("--repl") => repl(),
+        Some(spec) => {
+            let placeholder = format_spec::parse(spec).unwrap_or_else(|e| {
+                eprint!("{}", e.render(spec));
+                process::exit(1);
+            });
+            if let Err(e) = show(spec, &placeholder, args.get(1).map(String::as_str)) {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        None => {
+            eprintln!("usage: format_explain '{{:_^12.3}}' [value] | --repl");
+            process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shown(spec: &str, value: &str) -> Result<(), String> {
+        show(spec, &format_spec::parse(spec).map_err(|e| e.message)?, Some(value))
+    }
+
+    #[test]
+    fn values_written_as_in_rust_are_shown() {
+        for value in ["42", "-1i8", "2.5", "1e3f32", "NaN", "'c'", "\"[1, 2]\"", "true"] {
+            assert_eq!(shown("{:>8}", value), Ok(()), "{}", value);
+        }
+    }
+
+    #[test]
+    fn an_unquoted_value_is_refused_not_taken_as_a_string() {
+        let err = shown("{:>8}", "[1,2]").unwrap_err();
+        assert!(err.contains("\"[1,2]\""), "{}", err);
+        assert!(shown("{:>8}", "hello").is_err());
+    }
+}
@@ -0,0 +1,833 @@
+// format_spec.rs
+// Takes a Rust format placeholder such as `{:_^12.3}` apart the way rustc does:
+//   {[argument][:[[fill]align][sign]['#']['0'][width]['.' precision][type]]}
+// `parse` rejects invalid placeholders with rustc's own error messages, `explain`
+// says what each part does in words, and `render` formats an example value with it.
+//
+// `format!` only accepts a literal, so `render` cannot hand the spec to std directly.
+// It asks std for the body (digits, exponent, Debug text) and applies fill, alignment,
+// sign and zero padding itself, following core::fmt::Formatter::pad and pad_integral.
+//
+// Used by format_explain.rs and format_translate.rs through `mod format_spec;`, so the
+// tests below (the renderer against format!, the parser against rustc) run in both of
+// their test builds.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Argument {
+    Index(usize),  // {0}
+    Name(String),  // {name}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sign {
+    Plus,
+    Minus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Count {
+    Is(usize),      // 5
+    Param(usize),   // 1$
+    Name(String),   // width$
+    Star,           // .* (precision only)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Spec {
+    pub fill: Option<char>,
+    pub align: Option<Align>,
+    pub sign: Option<Sign>,
+    pub alternate: bool,
+    pub zero_pad: bool,
+    pub width: Option<Count>,
+    pub precision: Option<Count>,
+    pub ty: String, // "", "?", "x?", "X?", "e", "E", "o", "x", "X", "p", "b"
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub argument: Option<Argument>,
+    pub spec: Spec,
+}
+
+// A rustc-style error: the message, the character it points at, the label under
+// the caret and an optional `= note:`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub pos: usize,
+    pub label: String,
+    pub note: Option<String>,
+}
+
+impl ParseError {
+    // Renders like rustc, pointing into `input`.
+    pub fn render(&self, input: &str) -> String {
+        let caret = format!("{}^ {}", " ".repeat(self.pos), self.label);
+        let mut out = format!("error: {}\n  {}\n  {}\n", self.message, input, caret.trim_end());
+        if let Some(note) = &self.note {
+            out.push_str(&format!("  = note: {}\n", note));
+        }
+        out
+    }
+}
+
+const TRAITS: [(&str, &str); 9] = [
+    ("", "Display"),
+    ("?", "Debug"),
+    ("e", "LowerExp"),
+    ("E", "UpperExp"),
+    ("o", "Octal"),
+    ("p", "Pointer"),
+    ("b", "Binary"),
+    ("x", "LowerHex"),
+    ("X", "UpperHex"),
+];
+
+fn trait_name(ty: &str) -> &'static str {
+    match ty {
+        "x?" | "X?" => "Debug",
+        _ => TRAITS.iter().find(|(t, _)| *t == ty).map(|(_, name)| *name).unwrap_or("?"),
+    }
+}
+
+// --- Parsing, after rustc_parse_format ---
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    ty_pos: usize, // Where the type starts, for "unknown format trait".
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn consume(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, pos: usize, message: String, label: &str, note: Option<&str>) -> ParseError {
+        ParseError { message, pos, label: label.to_string(), note: note.map(str::to_string) }
+    }
+
+    fn expected_brace(&self) -> ParseError {
+        let escape = Some("if you intended to print `{`, you can escape it using `{{`");
+        match self.peek() {
+            None => self.error(self.pos, "invalid format string: expected `}` but string was terminated".into(), "expected `}` in format string", escape),
+            Some(',') => self.error(
+                self.pos,
+                "invalid format string: python's numeric grouping `,` is not supported in rust format strings".into(),
+                "expected `}` in format string",
+                Some("to print `{`, you can escape it using `{{`"),
+            ),
+            Some(c) => self.error(self.pos, format!("invalid format string: expected `}}`, found `{}`", c), "expected `}` in format string", escape),
+        }
+    }
+
+    // An identifier, as rustc's `word`: a letter or `_`, then letters, digits or `_`.
+    fn word(&mut self) -> String {
+        let start = self.pos;
+        if matches!(self.peek(), Some(c) if c.is_alphabetic() || c == '_') {
+            self.pos += 1;
+            while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_') {
+                self.pos += 1;
+            }
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn integer(&mut self) -> Option<(String, usize)> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| (self.chars[start..self.pos].iter().collect(), start))
+    }
+
+    // `5`, `1$` or `name$`; anything else is left for the caller.
+    fn count(&mut self) -> Result<Option<Count>, ParseError> {
+        if let Some((digits, start)) = self.integer() {
+            if self.consume('$') {
+                return Ok(Some(Count::Param(digits.parse().unwrap_or(usize::MAX))));
+            }
+            return match digits.parse::<u16>() {
+                Ok(n) => Ok(Some(Count::Is(n as usize))),
+                Err(_) => Err(self.error(
+                    start,
+                    format!("invalid format string: integer `{}` does not fit into the type `u16` whose range is `0..=65535`", digits),
+                    "integer out of range for `u16` in format string",
+                    None,
+                )),
+            };
+        }
+        let start = self.pos;
+        let word = self.word();
+        if !word.is_empty() && self.consume('$') {
+            return Ok(Some(Count::Name(word)));
+        }
+        self.pos = start;
+        Ok(None)
+    }
+
+    fn spec(&mut self) -> Result<Spec, ParseError> {
+        let mut spec = Spec::default();
+        let align = |c| match c {
+            Some('<') => Some(Align::Left),
+            Some('^') => Some(Align::Center),
+            Some('>') => Some(Align::Right),
+            _ => None,
+        };
+
+        if let (Some(fill), Some(a)) = (self.peek(), align(self.peek_at(1))) {
+            spec.fill = Some(fill);
+            spec.align = Some(a);
+            self.pos += 2;
+        } else if let Some(a) = align(self.peek()) {
+            spec.align = Some(a);
+            self.pos += 1;
+        }
+
+        if self.consume('+') {
+            spec.sign = Some(Sign::Plus);
+        } else if self.consume('-') {
+            spec.sign = Some(Sign::Minus);
+        }
+        spec.alternate = self.consume('#');
+
+        // `0$` is argument 0 as the width, not the `0` flag.
+        if self.peek() == Some('0') && self.peek_at(1) == Some('$') {
+            self.pos += 2;
+            spec.width = Some(Count::Param(0));
+        } else {
+            spec.zero_pad = self.consume('0');
+            spec.width = self.count()?;
+        }
+
+        if self.consume('.') {
+            spec.precision = if self.consume('*') { Some(Count::Star) } else { self.count()? };
+        }
+
+        if let Some(c @ ('<' | '^' | '>')) = self.peek() {
+            return Err(self.error(
+                self.pos,
+                "invalid format string: expected alignment specifier after `:` in format string; example: `{:>?}`".into(),
+                &format!("expected `{}` to occur after `:` in format string", c),
+                None,
+            ));
+        }
+
+        self.ty_pos = self.pos;
+        spec.ty = if self.consume('x') {
+            if self.consume('?') { "x?" } else { "x" }.to_string()
+        } else if self.consume('X') {
+            if self.consume('?') { "X?" } else { "X" }.to_string()
+        } else if self.consume('?') {
+            "?".to_string()
+        } else {
+            self.word()
+        };
+        if spec.ty == "_" {
+            return Err(self.error(
+                self.ty_pos,
+                "invalid format string: invalid argument name `_`".into(),
+                "invalid argument name in format string",
+                Some("argument name cannot be a single underscore"),
+            ));
+        }
+        Ok(spec)
+    }
+
+    fn placeholder(&mut self) -> Result<Placeholder, ParseError> {
+        if !self.consume('{') {
+            return Err(self.error(0, "expected a placeholder such as `{:>8}`".into(), "placeholders start with `{`", None));
+        }
+        let argument = if let Some((digits, _)) = self.integer() {
+            Some(Argument::Index(digits.parse().unwrap_or(usize::MAX)))
+        } else {
+            let start = self.pos;
+            let word = self.word();
+            if word == "_" {
+                return Err(self.error(
+                    start,
+                    "invalid format string: invalid argument name `_`".into(),
+                    "invalid argument name in format string",
+                    Some("argument name cannot be a single underscore"),
+                ));
+            }
+            (!word.is_empty()).then_some(Argument::Name(word))
+        };
+        let spec = if self.consume(':') { self.spec()? } else { Spec::default() };
+
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.pos += 1;
+        }
+        if !self.consume('}') {
+            return Err(self.expected_brace());
+        }
+        // Syntax first, then the trait, as in rustc.
+        if spec.ty != "x?" && spec.ty != "X?" && !TRAITS.iter().any(|(t, _)| *t == spec.ty) {
+            let mut note = "the only appropriate formatting traits are:".to_string();
+            for (t, name) in TRAITS {
+                note.push_str(&format!("\n          - `{}`, which uses the `{}` trait", t, name));
+            }
+            return Err(self.error(self.ty_pos, format!("unknown format trait `{}`", spec.ty), "", Some(&note)));
+        }
+        Ok(Placeholder { argument, spec })
+    }
+}
+
+// Parses a placeholder at the start of `input` and returns it with the rest of the input,
+// which the REPL uses as the example value: `{:_^12.3} 3.14159`.
+pub fn parse_prefix(input: &str) -> Result<(Placeholder, &str), ParseError> {
+    let mut parser = Parser { chars: input.chars().collect(), pos: 0, ty_pos: 0 };
+    let placeholder = parser.placeholder()?;
+    let rest = input.char_indices().nth(parser.pos).map(|(i, _)| &input[i..]).unwrap_or("");
+    Ok((placeholder, rest))
+}
+
+fn unmatched_close(pos: usize) -> ParseError {
+    ParseError {
+        message: "invalid format string: unmatched `}` found".into(),
+        pos,
+        label: "unmatched `}` in format string".into(),
+        note: Some("if you intended to print `}`, you can escape it using `}}`".into()),
+    }
+}
+
+// Parses exactly one placeholder.
+pub fn parse(input: &str) -> Result<Placeholder, ParseError> {
+    let (placeholder, rest) = parse_prefix(input)?;
+    if let Some(c) = rest.chars().next() {
+        let pos = input.chars().count() - rest.chars().count();
+        return Err(if c == '}' {
+            unmatched_close(pos)
+        } else {
+            ParseError { message: "expected a single placeholder".into(), pos, label: "nothing may follow the `}`".into(), note: None }
+        });
+    }
+    Ok(placeholder)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Piece {
+    Text(String), // With `{{` and `}}` already turned into `{` and `}`.
+    Placeholder(Placeholder),
+}
+
+// Parses a whole format string, such as `"x = {:>10.2}, n = {}"`.
+pub fn parse_format_string(input: &str) -> Result<Vec<Piece>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1)) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                text.push(chars[i]);
+                i += 2;
+            }
+            ('}', _) => return Err(unmatched_close(i)),
+            ('{', _) => {
+                if !text.is_empty() {
+                    pieces.push(Piece::Text(std::mem::take(&mut text)));
+                }
+                let rest: String = chars[i..].iter().collect();
+                let (placeholder, after) = parse_prefix(&rest).map_err(|e| ParseError { pos: e.pos + i, ..e })?;
+                pieces.push(Piece::Placeholder(placeholder));
+                i = chars.len() - after.chars().count();
+            }
+            (c, _) => {
+                text.push(c);
+                i += 1;
+            }
+        }
+    }
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+    Ok(pieces)
+}
+
+// --- Explaining ---
+
+fn describe_count(count: &Count, what: &str, fixed: impl Fn(usize) -> String) -> String {
+    match count {
+        Count::Is(n) => fixed(*n),
+        Count::Param(i) => format!("{} comes from argument {} (a usize)", what, i),
+        Count::Name(name) => format!("{} comes from the argument named `{}` (a usize)", what, name),
+        Count::Star => format!("{} comes from the next argument, before the value itself", what),
+    }
+}
+
+// One line per part of the placeholder, in the order they appear.
+pub fn explain(placeholder: &Placeholder) -> Vec<(String, String)> {
+    let spec = &placeholder.spec;
+    let mut parts = Vec::new();
+    let mut part = |name: &str, words: String| parts.push((name.to_string(), words));
+
+    part("argument", match &placeholder.argument {
+        None => "the next positional argument".to_string(),
+        Some(Argument::Index(i)) => format!("positional argument {} (counting from 0)", i),
+        Some(Argument::Name(name)) => format!("the argument named `{}`, or a variable `{}` in scope", name, name),
+    });
+    if let Some(fill) = spec.fill {
+        part("fill", format!("`{}` fills the empty space (the default is a space)", fill));
+    }
+    if let Some(align) = spec.align {
+        part("align", match align {
+            Align::Left => "`<` left-aligns within the width".to_string(),
+            Align::Center => "`^` centres within the width; an odd leftover goes on the right".to_string(),
+            Align::Right => "`>` right-aligns within the width".to_string(),
+        });
+    }
+    match spec.sign {
+        Some(Sign::Plus) => part("sign", "`+` always prints a sign, even for positive numbers and zero".to_string()),
+        Some(Sign::Minus) => part("sign", "`-` is accepted but does nothing: negative numbers get a `-` anyway".to_string()),
+        None => {}
+    }
+    if spec.alternate {
+        part("#", match spec.ty.as_str() {
+            "x" | "X" | "x?" | "X?" => "alternate form: adds the `0x` prefix".to_string(),
+            "o" => "alternate form: adds the `0o` prefix".to_string(),
+            "b" => "alternate form: adds the `0b` prefix".to_string(),
+            "?" => "alternate form: pretty-prints Debug output, one field per line".to_string(),
+            _ => "alternate form: has no effect with this type".to_string(),
+        });
+    }
+    if spec.zero_pad {
+        part("0", "pads numbers with zeros after the sign and prefix; overrides fill and alignment, and text ignores it".to_string());
+    }
+    if let Some(width) = &spec.width {
+        part("width", describe_count(width, "the width", |n| format!("at least {} characters wide", n)));
+    }
+    if let Some(precision) = &spec.precision {
+        part("precision", describe_count(precision, "the precision", |n| {
+            format!("{} digits after the point for floats, at most {} characters of text, ignored by integers", n, n)
+        }));
+    }
+    part("type", match spec.ty.as_str() {
+        "" => "none, so the value's `Display` trait (`{}`)".to_string(),
+        "x?" => "`x?`: `Debug`, with integers in lower-case hex".to_string(),
+        "X?" => "`X?`: `Debug`, with integers in upper-case hex".to_string(),
+        ty => format!("`{}`: the `{}` trait", ty, trait_name(ty)),
+    });
+    if spec.fill.is_some() && spec.width.is_none() {
+        part("note", "fill and alignment only matter when a width is given".to_string());
+    }
+    parts
+}
+
+// --- Example values ---
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int { value: i128, ty: &'static str }, // ty is the Rust type, e.g. "i32".
+    Float { value: f64, f32: bool },
+    Str(String),
+    Char(char),
+    Bool(bool),
+}
+
+const INT_TYPES: [(&str, u32, bool); 12] = [
+    ("i8", 8, true), ("i16", 16, true), ("i32", 32, true), ("i64", 64, true), ("i128", 128, true), ("isize", 64, true),
+    ("u8", 8, false), ("u16", 16, false), ("u32", 32, false), ("u64", 64, false), ("u128", 128, false), ("usize", 64, false),
+];
+
+macro_rules! from_int {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Value {
+            fn from(value: $t) -> Value {
+                Value::Int { value: value as i128, ty: stringify!($t) }
+            }
+        }
+    )*};
+}
+from_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Value {
+        Value::Float { value, f32: false }
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Value {
+        Value::Float { value: value as f64, f32: true }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Value {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<char> for Value {
+    fn from(value: char) -> Value {
+        Value::Char(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Value {
+        Value::Bool(value)
+    }
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int { ty, .. } => ty,
+            Value::Float { f32: true, .. } => "f32",
+            Value::Float { .. } => "f64",
+            Value::Str(_) => "str",
+            Value::Char(_) => "char",
+            Value::Bool(_) => "bool",
+        }
+    }
+
+    // The value as Rust source, e.g. `255u8` or `f64::NAN`.
+    pub fn to_rust(&self) -> String {
+        match self {
+            Value::Int { value, ty } => format!("{}{}", value, ty),
+            Value::Float { value, .. } if value.is_nan() => format!("{}::NAN", self.type_name()),
+            Value::Float { value, .. } if value.is_infinite() => {
+                format!("{}{}::INFINITY", if *value < 0.0 { "-" } else { "" }, self.type_name())
+            }
+            Value::Float { value, f32: true } => format!("{:?}f32", *value as f32),
+            Value::Float { value, .. } => format!("{:?}f64", value),
+            Value::Str(s) => format!("{:?}", s),
+            Value::Char(c) => format!("{:?}", c),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+
+    // Reads a value the way it would be written in Rust: `42`, `-1i8`, `255u8`, `3.5`,
+    // `1e3f32`, `NaN`, `'c'`, `"text"`, `true`. Anything else is refused: quietly taking
+    // `[1, 2]` as a string would show a string's formatting under a list's name.
+    pub fn parse(text: &str) -> Result<Value, String> {
+        let text = text.trim();
+        if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+            return Ok(Value::Str(text[1..text.len() - 1].to_string()));
+        }
+        if text.len() >= 3 && text.starts_with('\'') && text.ends_with('\'') {
+            let inner = &text[1..text.len() - 1];
+            let mut chars = inner.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Value::Char(c)),
+                _ => Err(format!("character literal may only contain one codepoint: {}", text)),
+            };
+        }
+        match text {
+            "true" => return Ok(Value::Bool(true)),
+            "false" => return Ok(Value::Bool(false)),
+            "NaN" => return Ok(Value::Float { value: f64::NAN, f32: false }),
+            "inf" => return Ok(Value::Float { value: f64::INFINITY, f32: false }),
+            "-inf" => return Ok(Value::Float { value: f64::NEG_INFINITY, f32: false }),
+            _ => {}
+        }
+        let number = text.replace('_', "");
+        for suffix in ["f32", "f64"] {
+            if let Some(digits) = number.strip_suffix(suffix) {
+                let value: f64 = digits.parse().map_err(|_| format!("not a float literal: {}", text))?;
+                return Ok(Value::Float { value, f32: suffix == "f32" });
+            }
+        }
+        for (ty, bits, signed) in INT_TYPES {
+            if let Some(digits) = number.strip_suffix(ty) {
+                return Value::int(digits, ty, bits, signed, text);
+            }
+        }
+        if number.chars().next().is_some_and(|c| c.is_ascii_digit() || c == '-') {
+            if number.trim_start_matches('-').chars().all(|c| c.is_ascii_digit()) {
+                return Value::int(&number, "i32", 32, true, text); // Rust's default integer type.
+            }
+            if let Ok(value) = number.parse::<f64>() {
+                return Ok(Value::Float { value, f32: false });
+            }
+        }
+        Err(format!("not a value this tool can format: {} (for a string, quote it: \"{}\")", text, text))
+    }
+
+    fn int(digits: &str, ty: &'static str, bits: u32, signed: bool, text: &str) -> Result<Value, String> {
+        let value: i128 = digits.parse().map_err(|_| format!("literal out of range for `{}`: {}", ty, text))?;
+        if !signed && value < 0 {
+            return Err(format!("cannot apply unary operator `-` to type `{}`", ty));
+        }
+        let (min, max) = match (signed, bits) {
+            (true, 128) => (i128::MIN, i128::MAX),
+            (true, b) => (-(1i128 << (b - 1)), (1i128 << (b - 1)) - 1),
+            (false, 128) => (0, i128::MAX), // Larger u128 values do not fit in `Value::Int`.
+            (false, b) => (0, (1i128 << b) - 1),
+        };
+        if value < min || value > max {
+            return Err(format!("literal out of range for `{}`: {}", ty, text));
+        }
+        Ok(Value::Int { value, ty })
+    }
+}
+
+// --- Rendering ---
+
+enum Body {
+    Number { negative: bool, prefix: &'static str, digits: String }, // Right-aligned, zero-paddable.
+    Text(String),  // Left-aligned; precision already applied.
+    Exact(String), // Debug for str and char: ignores width, fill and precision.
+}
+
+fn fixed(count: &Option<Count>, what: &str) -> Result<Option<usize>, String> {
+    match count {
+        None => Ok(None),
+        Some(Count::Is(n)) => Ok(Some(*n)),
+        Some(_) => Err(format!("can't render an example: the {} comes from another argument; use a number instead", what)),
+    }
+}
+
+fn not_implemented(value: &Value, ty: &str) -> String {
+    let name = match trait_name(ty) {
+        "Pointer" => "std::fmt::Pointer",
+        other => other,
+    };
+    format!("error[E0277]: the trait bound `{}: {}` is not satisfied", value.type_name(), name)
+}
+
+fn truncate(text: &str, precision: Option<usize>) -> String {
+    match precision {
+        Some(p) => text.chars().take(p).collect(),
+        None => text.to_string(),
+    }
+}
+
+fn pad(text: &str, width: usize, fill: char, align: Align) -> String {
+    let len = text.chars().count();
+    if len >= width {
+        return text.to_string();
+    }
+    let gap = width - len;
+    let (before, after) = match align {
+        Align::Left => (0, gap),
+        Align::Right => (gap, 0),
+        Align::Center => (gap / 2, gap - gap / 2),
+    };
+    format!("{}{}{}", fill.to_string().repeat(before), text, fill.to_string().repeat(after))
+}
+
+fn float_body<T: fmt::Display + fmt::Debug + fmt::LowerExp + fmt::UpperExp>(abs: T, ty: &str, precision: Option<usize>) -> String {
+    match (ty, precision) {
+        ("e", Some(p)) => format!("{:.*e}", p, abs),
+        ("e", None) => format!("{:e}", abs),
+        ("E", Some(p)) => format!("{:.*E}", p, abs),
+        ("E", None) => format!("{:E}", abs),
+        ("", Some(p)) => format!("{:.*}", p, abs),
+        ("", None) => format!("{}", abs),
+        (_, Some(p)) => format!("{:.*?}", p, abs),
+        (_, None) => format!("{:?}", abs),
+    }
+}
+
+// Formats `value` as `format!("{:<spec>}", value)` would.
+pub fn render(spec: &Spec, value: &Value) -> Result<String, String> {
+    let width = fixed(&spec.width, "width")?;
+    let precision = fixed(&spec.precision, "precision")?;
+    let ty = spec.ty.as_str();
+    if ty == "p" {
+        return Err(match value {
+            Value::Str(_) => "can't render an example: `{:p}` prints an address, which depends on where the value lives".to_string(),
+            _ => not_implemented(value, ty),
+        });
+    }
+
+    let body = match value {
+        Value::Int { value, ty: int_ty } => {
+            let bits = INT_TYPES.iter().find(|(t, _, _)| t == int_ty).map(|(_, b, _)| *b).unwrap_or(32);
+            let mask = if bits == 128 { u128::MAX } else { (1u128 << bits) - 1 };
+            let raw = *value as u128 & mask; // Two's complement, as the radix traits print it.
+            let radix = |prefix, digits| Body::Number { negative: false, prefix: if spec.alternate { prefix } else { "" }, digits };
+            match ty {
+                "" | "?" => Body::Number { negative: *value < 0, prefix: "", digits: value.unsigned_abs().to_string() },
+                "x" | "x?" => radix("0x", format!("{:x}", raw)),
+                "X" | "X?" => radix("0x", format!("{:X}", raw)),
+                "o" => radix("0o", format!("{:o}", raw)),
+                "b" => radix("0b", format!("{:b}", raw)),
+                _ => Body::Number { negative: *value < 0, prefix: "", digits: float_body(value.unsigned_abs(), ty, precision) },
+            }
+        }
+        Value::Float { value: v, f32 } => {
+            if !matches!(ty, "" | "?" | "x?" | "X?" | "e" | "E") {
+                return Err(not_implemented(value, ty));
+            }
+            let digits = if *f32 { float_body((*v as f32).abs(), ty, precision) } else { float_body(v.abs(), ty, precision) };
+            Body::Number { negative: v.is_sign_negative() && !v.is_nan(), prefix: "", digits }
+        }
+        Value::Str(s) => match ty {
+            "" => Body::Text(truncate(s, precision)),
+            "?" | "x?" | "X?" => Body::Exact(format!("{:?}", s)),
+            _ => return Err(not_implemented(value, ty)),
+        },
+        Value::Char(c) => match ty {
+            "" => Body::Text(truncate(&c.to_string(), precision)),
+            "?" | "x?" | "X?" => Body::Exact(format!("{:?}", c)),
+            _ => return Err(not_implemented(value, ty)),
+        },
+        Value::Bool(b) => match ty {
+            "" | "?" | "x?" | "X?" => Body::Text(truncate(&b.to_string(), precision)),
+            _ => return Err(not_implemented(value, ty)),
+        },
+    };
+
+    let fill = spec.fill.unwrap_or(' ');
+    Ok(match body {
+        Body::Exact(text) => text,
+        Body::Text(text) => pad(&text, width.unwrap_or(0), fill, spec.align.unwrap_or(Align::Left)),
+        Body::Number { negative, prefix, digits } => {
+            let sign = if negative { "-" } else if spec.sign == Some(Sign::Plus) { "+" } else { "" };
+            let head = format!("{}{}", sign, prefix);
+            if spec.zero_pad {
+                // Sign and prefix first, zeros after them; fill and alignment are ignored.
+                let zeros = width.unwrap_or(0).saturating_sub(head.chars().count() + digits.chars().count());
+                format!("{}{}{}", head, "0".repeat(zeros), digits)
+            } else {
+                pad(&format!("{}{}", head, digits), width.unwrap_or(0), fill, spec.align.unwrap_or(Align::Right))
+            }
+        }
+    })
+}
+
+// A value that shows the spec off when the learner did not give one.
+pub fn example_value(spec: &Spec) -> Value {
+    match spec.ty.as_str() {
+        "x" | "X" | "x?" | "X?" | "o" | "b" => Value::from(255),
+        "e" | "E" => Value::from(1234.5),
+        "?" => Value::from("hi"),
+        _ if spec.precision.is_some() => Value::from(std::f64::consts::PI),
+        _ if spec.sign.is_some() || spec.zero_pad => Value::from(42),
+        _ => Value::from("text"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{E, PI};
+
+    macro_rules! case {
+        ($spec:literal, $value:expr) => {
+            ($spec, format!($spec, $value), Value::from($value))
+        };
+    }
+
+    #[test]
+    fn render_matches_format() {
+        let cases = vec![
+            case!("{:b}", 10),
+            case!("{:x}", 10),
+            case!("{:o}", 10),
+            case!("{:5}", 42),
+            case!("{:<5}", 42),
+            case!("{:05}", 42),
+            case!("{:.2}", PI),
+            case!("{:.5}", PI),
+            case!("{:-^20}", "centered"),
+            case!("{:_^20}", "centered"),
+            case!("{:*>10}", "right"),
+            case!("{:.<10}", "left"),
+            case!("{:_^12.3}", E),
+            case!("{:05}", "ab"),
+            case!("{:>10?}", "ab"),
+            case!("{:^7?}", true),
+            case!("{:.2}", "héllo"),
+            case!("{:5.1}", 'c'),
+            case!("{:+x}", -1i32),
+            case!("{:#010b}", 5u8),
+            case!("{:08.2}", -PI),
+            case!("{:+}", 0.0),
+            case!("{:e}", 1200),
+            case!("{:.1e}", 1250),
+            case!("{:^9.3e}", -1234.5),
+            case!("{:05}", f64::NAN),
+            case!("{:+.0}", 2.5),
+            case!("{:x?}", 255),
+            case!("{:#X?}", 255u8),
+            case!("{:.3?}", 1.0 / 3.0),
+            case!("{:010}", f64::NEG_INFINITY),
+            case!("{:?}", 1e21),
+            case!("{}", 1e21),
+            case!("{:?}", 0.1f32),
+            case!("{:+05}", -0.0),
+            case!("{:<05}", 7),
+            case!("{:#?}", 1.0),
+            case!("{:-5}", -3),
+            case!("{:>8}", -128i8),
+            case!("{:x}", -128i8),
+            case!("{:E}", 0.00012f32),
+        ];
+        for (spec, expected, value) in &cases {
+            let actual = parse(spec).map_err(|e| e.message).and_then(|p| render(&p.spec, value));
+            assert_eq!(actual.as_ref(), Ok(expected), "{} with {:?}", spec, value);
+        }
+    }
+
+    // What rustc 1.95 says about each spec (the first line of its error).
+    #[test]
+    fn parse_errors_match_rustc() {
+        let errors = [
+            ("{:q}", "unknown format trait `q`"),
+            ("{:éé}", "unknown format trait `éé`"),
+            ("{:1a}", "unknown format trait `a`"),
+            ("{:_^12.3", "invalid format string: expected `}` but string was terminated"),
+            ("{", "invalid format string: expected `}` but string was terminated"),
+            ("{:x?y}", "invalid format string: expected `}`, found `y`"),
+            ("{:?#}", "invalid format string: expected `}`, found `#`"),
+            ("{:.5.3}", "invalid format string: expected `}`, found `.`"),
+            ("{:--5}", "invalid format string: expected `}`, found `-`"),
+            ("{:.*5}", "invalid format string: expected `}`, found `5`"),
+            ("{:5$$}", "invalid format string: expected `}`, found `$`"),
+            ("{:#0>+5}", "invalid format string: expected alignment specifier after `:` in format string; example: `{:>?}`"),
+            ("{:5.3<}", "invalid format string: expected alignment specifier after `:` in format string; example: `{:>?}`"),
+            ("{:,}", "invalid format string: python's numeric grouping `,` is not supported in rust format strings"),
+            ("{:_}", "invalid format string: invalid argument name `_`"),
+            ("{:999999}", "invalid format string: integer `999999` does not fit into the type `u16` whose range is `0..=65535`"),
+            ("{:}}", "invalid format string: unmatched `}` found"),
+        ];
+        for (spec, expected) in errors {
+            assert_eq!(parse(spec).map_err(|e| e.message).err().as_deref(), Some(expected), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn specs_rustc_accepts_parse() {
+        for spec in ["{:5>}", "{:+^}", "{:é^5}", "{:x }", "{:  }", "{:5.}", "{:.x}", "{:1$.2$}", "{:.*}", "{:a$}", "{0:}", "{name:>w$}", "{:0$}"] {
+            if let Err(e) = parse(spec) {
+                panic!("{}: valid in rustc, but we say {}", spec, e.message);
+            }
+        }
+    }
+
+    #[test]
+    fn values_parse_as_rust_literals_and_nothing_else() {
+        assert_eq!(Value::parse("255u8"), Ok(Value::from(255u8)));
+        assert_eq!(Value::parse("\"[1,2]\""), Ok(Value::from("[1,2]")));
+        assert!(Value::parse("[1,2]").is_err());
+        assert!(Value::parse("300u8").is_err());
+    }
+}
@@ -0,0 +1,1050 @@
+// format_translate.rs
+// Translates a Rust format string into the Python f-string, the C++20 `std::format` call
+// and the iostream/iomanip chain that print the same thing, the way Day1001.py and
+// Day1001.cpp write their output:
+//   println!("{:>10.2}", x)
+//   print(f"{x:>10.2f}")
+//   std::cout << std::format("{0:>10.2f}", x) << '\n';
+//   std::cout << std::setw(10) << std::fixed << std::setprecision(2) << x << '\n';
+// What a spec means depends on the value (`.2` is decimals for a float but a truncation
+// for a string, and Python's `.2` alone means significant digits), so every argument
+// comes with a sample value. Translations with no direct equivalent are flagged.
+//
+// Usage:
+//   rustc --edition 2021 format_translate.rs -o target/format_translate
+//   target/format_translate '{:>10.2}' 3.14159          # arguments are named x, y, z, ...
+//   target/format_translate '{name:>8} {:x}' name='"Ann"' 255
+//   target/format_translate --run '{:>10.2}' 3.14159    # also run the Python and C++ output
+//   target/format_translate --check                     # run every sample case in all three languages
+// Tests (the translations themselves, without Python or a C++ compiler):
+//   rustc --edition 2021 --test format_translate.rs -o target/format_translate_test && target/format_translate_test
+//
+// `--run` and `--check` write target/format_translate_check.{py,cpp}, run them with
+// python3 and $CXX (default c++) and compare with Rust. The `std::format` lines are only
+// compiled when the C++ library has <format> (GCC 13+, Clang 17+); otherwise they are skipped.
+
+#[allow(dead_code)] // The explaining half is for format_explain.rs.
+mod format_spec;
+
+use format_spec::{Align, Argument, Count, Piece, Placeholder, Sign, Spec, Value};
+use std::f64::consts::PI;
+use std::fs;
+use std::process::{self, Command};
+
+struct Arg {
+    name: String,
+    value: Value,
+    captured: bool, // Given as name=VALUE: `{name}` captures a variable instead of taking an argument.
+}
+
+// Where a width or precision comes from, once arguments are resolved.
+#[derive(Clone, Copy)]
+enum Amount {
+    Fixed(usize),
+    Arg(usize),
+}
+
+// A placeholder with its arguments looked up.
+struct Resolved<'a> {
+    spec: &'a Spec,
+    value: usize,
+    width: Option<Amount>,
+    precision: Option<Amount>,
+}
+
+// What to tell the learner about a translation.
+#[derive(Default)]
+struct Notes {
+    list: Vec<String>,
+    inexact: bool, // Set when the output is known to differ for some values.
+}
+
+impl Notes {
+    fn note(&mut self, note: String) {
+        if !self.list.contains(&note) {
+            self.list.push(note);
+        }
+    }
+
+    // A feature with no direct equivalent.
+    fn flag(&mut self, note: String) {
+        self.inexact = true;
+        self.note(note);
+    }
+}
+
+// One target language's version of the whole format string.
+struct Translation {
+    code: String,
+    notes: Notes,
+}
+
+// --- Resolving arguments, as rustc does ---
+
+fn arg_index(args: &[Arg], argument: &Argument) -> Result<usize, String> {
+    match argument {
+        Argument::Index(i) if *i < args.len() => Ok(*i),
+        Argument::Index(i) => Err(format!(
+            "invalid reference to positional argument {} ({})",
+            i,
+            match args.len() {
+                0 => "no arguments were given".to_string(),
+                1 => "there is 1 argument".to_string(),
+                n => format!("there are {} arguments", n),
+            }
+        )),
+        Argument::Name(name) => args
+            .iter()
+            .position(|a| &a.name == name)
+            .ok_or_else(|| format!("cannot find value `{}` in this scope (pass it as {}=VALUE)", name, name)),
+    }
+}
+
+fn resolve<'a>(pieces: &'a [Piece], args: &[Arg]) -> Result<Vec<Resolved<'a>>, String> {
+    let mut next = 0; // The implicit position `{}` uses next.
+    let mut resolved = Vec::new();
+    for piece in pieces {
+        let Piece::Placeholder(Placeholder { argument, spec }) = piece else { continue };
+        let amount = |count: &Option<Count>, next: &mut usize| -> Result<Option<Amount>, String> {
+            Ok(match count {
+                None => None,
+                Some(Count::Is(n)) => Some(Amount::Fixed(*n)),
+                Some(Count::Param(i)) => Some(Amount::Arg(arg_index(args, &Argument::Index(*i))?)),
+                Some(Count::Name(name)) => Some(Amount::Arg(arg_index(args, &Argument::Name(name.clone()))?)),
+                Some(Count::Star) => {
+                    *next += 1; // `.*` takes the precision first, then the value.
+                    Some(Amount::Arg(arg_index(args, &Argument::Index(*next - 1))?))
+                }
+            })
+        };
+        let precision = amount(&spec.precision, &mut next)?;
+        let width = amount(&spec.width, &mut next)?;
+        let value = match argument {
+            None => {
+                next += 1;
+                arg_index(args, &Argument::Index(next - 1))?
+            }
+            Some(argument) => arg_index(args, argument)?,
+        };
+        for amount in [width, precision].into_iter().flatten() {
+            if let Amount::Arg(i) = amount {
+                if !matches!(args[i].value, Value::Int { value, .. } if value >= 0) {
+                    return Err(format!("expected `usize` for a width or precision, found `{}`", args[i].value.type_name()));
+                }
+            }
+        }
+        resolved.push(Resolved { spec, value, width, precision });
+    }
+    Ok(resolved)
+}
+
+// The output Rust gives, from format_spec's renderer (checked against format! by its own tests).
+fn rust_output(pieces: &[Piece], resolved: &[Resolved], args: &[Arg]) -> Result<String, String> {
+    let fixed = |amount: Option<Amount>| {
+        amount.map(|a| match a {
+            Amount::Fixed(n) => Count::Is(n),
+            Amount::Arg(i) => match args[i].value {
+                Value::Int { value, .. } => Count::Is(value as usize),
+                _ => Count::Is(0),
+            },
+        })
+    };
+    let mut out = String::new();
+    let mut placeholders = resolved.iter();
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => out.push_str(text),
+            Piece::Placeholder(_) => {
+                let r = placeholders.next().expect("one resolved entry per placeholder");
+                let spec = Spec { width: fixed(r.width), precision: fixed(r.precision), ..r.spec.clone() };
+                out.push_str(&format_spec::render(&spec, &args[r.value].value)?);
+            }
+        }
+    }
+    Ok(out)
+}
+
+// --- Facts about the values ---
+
+fn int_bits(ty: &str) -> u32 {
+    match ty {
+        "i8" | "u8" => 8,
+        "i16" | "u16" => 16,
+        "i32" | "u32" => 32,
+        "i128" | "u128" => 128,
+        _ => 64,
+    }
+}
+
+fn is_text(value: &Value) -> bool {
+    matches!(value, Value::Str(_) | Value::Char(_))
+}
+
+fn radix(ty: &str) -> bool {
+    matches!(ty, "x" | "X" | "o" | "b" | "x?" | "X?")
+}
+
+fn cpp_type(value: &Value) -> String {
+    match value {
+        Value::Int { ty: "isize", .. } => "int64_t".to_string(),
+        Value::Int { ty: "usize", .. } => "uint64_t".to_string(),
+        Value::Int { ty, .. } => format!("{}int{}_t", if ty.starts_with('u') { "u" } else { "" }, int_bits(ty)),
+        Value::Float { f32: true, .. } => "float".to_string(),
+        Value::Float { .. } => "double".to_string(),
+        Value::Str(_) => "std::string".to_string(),
+        Value::Char(_) => "char".to_string(),
+        Value::Bool(_) => "bool".to_string(),
+    }
+}
+
+fn quote(text: &str, quote: char) -> String {
+    let mut out = String::from(quote);
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push(quote);
+    out
+}
+
+fn python_literal(value: &Value) -> String {
+    match value {
+        Value::Int { value, .. } => value.to_string(),
+        Value::Float { value, .. } if value.is_nan() => "float(\"nan\")".to_string(),
+        Value::Float { value, .. } if value.is_infinite() => format!("float(\"{}inf\")", if *value < 0.0 { "-" } else { "" }),
+        Value::Float { value, f32: true } => format!("{:?}", *value as f32),
+        Value::Float { value, .. } => format!("{:?}", value),
+        Value::Str(s) => quote(s, '"'),
+        Value::Char(c) => quote(&c.to_string(), '"'),
+        Value::Bool(b) => if *b { "True" } else { "False" }.to_string(),
+    }
+}
+
+fn cpp_literal(value: &Value) -> String {
+    match value {
+        Value::Int { value, ty } if int_bits(ty) == 64 => format!("{}{}", value, if ty.starts_with('u') { "ULL" } else { "LL" }),
+        Value::Int { value, .. } => value.to_string(),
+        Value::Float { value, f32 } if value.is_nan() => format!("std::numeric_limits<{}>::quiet_NaN()", if *f32 { "float" } else { "double" }),
+        Value::Float { value, f32 } if value.is_infinite() => {
+            format!("{}std::numeric_limits<{}>::infinity()", if *value < 0.0 { "-" } else { "" }, if *f32 { "float" } else { "double" })
+        }
+        Value::Float { value, f32: true } => format!("{:?}f", *value as f32),
+        Value::Float { value, .. } => format!("{:?}", value),
+        Value::Str(s) => quote(s, '"'),
+        Value::Char(c) => quote(&c.to_string(), '\''),
+        Value::Bool(b) => b.to_string(),
+    }
+}
+
+// The `{:e}` note, worked out for this placeholder's value and precision. Rust writes the
+// shortest form (`1.2345e3`); printf-style output (Python, std::format, std::scientific) has
+// six decimals unless a precision is given, and an exponent with a sign and two digits or more.
+fn exponent_note(r: &Resolved, args: &[Arg], other: &str) -> Option<String> {
+    let Value::Float { value, f32 } = args[r.value].value else { return None };
+    let precision = r.precision.map(|a| match a {
+        Amount::Fixed(n) => n,
+        Amount::Arg(i) => match args[i].value {
+            Value::Int { value, .. } => value as usize, // `resolve` checked it is a usize.
+            _ => 0,
+        },
+    });
+    let rust = match (precision, f32) {
+        (Some(p), true) => format!("{:.*e}", p, value as f32),
+        (Some(p), false) => format!("{:.*e}", p, value),
+        (None, true) => format!("{:e}", value as f32),
+        (None, false) => format!("{:e}", value),
+    };
+    let value = if f32 { value as f32 as f64 } else { value };
+    let plain = format!("{:.*e}", precision.unwrap_or(6), value);
+    let printf = match plain.split_once('e') {
+        Some((mantissa, exponent)) => {
+            let exponent: i32 = exponent.parse().expect("Rust writes the exponent as an integer");
+            format!("{}e{}{:02}", mantissa, if exponent < 0 { '-' } else { '+' }, exponent.unsigned_abs())
+        }
+        None => plain.to_lowercase(), // inf, nan
+    };
+    let (rust, printf) = if r.spec.ty == "E" { (rust.replace('e', "E"), printf.to_uppercase()) } else { (rust, printf) };
+    (rust != printf).then(|| format!("Rust writes `{}`; {} writes `{}`", rust, other, printf))
+}
+
+// --- Python f-strings ---
+
+fn python_placeholder(r: &Resolved, args: &[Arg], notes: &mut Notes) -> String {
+    let spec = r.spec;
+    let arg = &args[r.value];
+    let name = &arg.name;
+    let amount = |a: Amount| match a {
+        Amount::Fixed(n) => n.to_string(),
+        Amount::Arg(i) => format!("{{{}}}", args[i].name),
+    };
+
+    if spec.ty == "?" && spec.alternate {
+        notes.flag("`{:#?}` pretty-prints Debug output; Python's closest is pprint.pformat(x) (needs `import pprint`)".into());
+        return format!("{{pprint.pformat({})}}", name);
+    }
+    let debug = spec.ty == "?" || (spec.ty.ends_with('?') && !matches!(arg.value, Value::Int { .. }));
+    let mut expr = name.clone();
+    let mut conversion = "";
+    let mut ty = String::new();
+    let mut precision = r.precision.map(amount);
+    let mut text_like = is_text(&arg.value);
+    match &arg.value {
+        Value::Str(_) | Value::Char(_) if debug => {
+            if matches!(arg.value, Value::Str(_)) {
+                notes.flag("Python's `!r` quotes strings with ' where Rust's Debug uses \"".into());
+            }
+            return format!("{{{}!r}}", name); // Debug for text ignores width and precision.
+        }
+        Value::Bool(_) => {
+            expr = format!("str({}).lower()", name); // Python prints True/False.
+            text_like = true;
+        }
+        Value::Float { .. } if debug && precision.is_none() => {
+            conversion = "!r";
+            text_like = true; // The spec now applies to the repr string.
+            notes.flag("`{:?}` on a float matches Python's repr (`1.0`) except where they switch to exponent form".into());
+        }
+        Value::Float { .. } => match spec.ty.as_str() {
+            "e" | "E" => {
+                ty = spec.ty.clone();
+                if let Some(note) = exponent_note(r, args, "Python") {
+                    notes.flag(note);
+                }
+            }
+            _ if precision.is_some() => ty = "f".into(),
+            _ => notes.flag("`{}` on a float: Python prints whole numbers as `1.0` where Rust prints `1`, and switches to exponents sooner".into()),
+        },
+        Value::Int { ty: int_ty, .. } => {
+            if precision.take().is_some() {
+                notes.note("Rust ignores a precision on integers, Python rejects it: dropped".into());
+            }
+            if radix(&spec.ty) {
+                ty = spec.ty.trim_end_matches('?').to_string();
+                if int_ty.starts_with('i') {
+                    let mask = if int_bits(int_ty) == 128 { u128::MAX } else { (1u128 << int_bits(int_ty)) - 1 };
+                    expr = format!("({} & {:#x})", name, mask);
+                    notes.note(format!("masked with & {:#x}: Rust prints negative {} in two's complement, Python would print a `-`", mask, int_ty));
+                }
+                if spec.alternate && ty == "X" {
+                    notes.flag("`{:#X}` gives `0xFF` in Rust but `0XFF` in Python".into());
+                }
+            } else if spec.ty == "e" || spec.ty == "E" {
+                ty = spec.ty.clone();
+                notes.flag(format!("`{{:{}}}` writes `1.2e3`; Python writes `1.200000e+03`", spec.ty));
+            }
+        }
+        Value::Str(_) | Value::Char(_) => {}
+    }
+
+    let mut out = String::new();
+    if spec.zero_pad && !text_like {
+        // Rust's `0` overrides fill and alignment; Python's `<` would beat its `0`.
+    } else {
+        match spec.fill {
+            Some('{') | Some('}') => notes.flag("a `{` or `}` fill cannot be written in an f-string spec: dropped".into()),
+            Some(fill) => out.push(fill),
+            None => {}
+        }
+        match spec.align {
+            Some(Align::Left) => out.push('<'),
+            Some(Align::Center) => out.push('^'),
+            Some(Align::Right) => out.push('>'),
+            None if conversion == "!r" && r.width.is_some() => out.push('>'), // Numbers right-align, strings don't.
+            None => {}
+        }
+    }
+    if spec.sign == Some(Sign::Plus) && !text_like {
+        out.push('+');
+    }
+    if spec.alternate && matches!(ty.as_str(), "x" | "X" | "o" | "b") {
+        out.push('#');
+    }
+    if spec.zero_pad {
+        if text_like {
+            notes.note("Rust ignores `0` for text (Python would pad with zeros): dropped".into());
+        } else {
+            out.push('0');
+        }
+    }
+    if let Some(width) = r.width {
+        out.push_str(&amount(width));
+    }
+    if let Some(precision) = precision {
+        out.push('.');
+        out.push_str(&precision);
+    }
+    out.push_str(&ty);
+    if out.is_empty() {
+        format!("{{{}{}}}", expr, conversion)
+    } else {
+        format!("{{{}{}:{}}}", expr, conversion, out)
+    }
+}
+
+fn to_python(pieces: &[Piece], resolved: &[Resolved], args: &[Arg]) -> Translation {
+    let mut notes = Notes::default();
+    let mut body = String::new();
+    let mut placeholders = resolved.iter();
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => body.push_str(&text.replace('{', "{{").replace('}', "}}")),
+            Piece::Placeholder(_) => body.push_str(&python_placeholder(placeholders.next().unwrap(), args, &mut notes)),
+        }
+    }
+    Translation { code: format!("f{}", quote(&body, '"')), notes }
+}
+
+// --- C++20 std::format ---
+
+fn cpp_format_placeholder(r: &Resolved, args: &[Arg], call_args: &mut Vec<String>, notes: &mut Notes) -> String {
+    let spec = r.spec;
+    let arg = &args[r.value];
+    // std::format cannot mix `{}` and `{0}`, so every argument gets an explicit index.
+    let mut index_of = |expr: String| {
+        call_args.iter().position(|a| *a == expr).unwrap_or_else(|| {
+            call_args.push(expr);
+            call_args.len() - 1
+        })
+    };
+    let amount = |a: Amount, index_of: &mut dyn FnMut(String) -> usize| match a {
+        Amount::Fixed(n) => n.to_string(),
+        Amount::Arg(i) => format!("{{{}}}", index_of(args[i].name.clone())),
+    };
+
+    if spec.ty == "?" && spec.alternate {
+        notes.flag("`{:#?}` pretty-prints Debug output; std::format has no equivalent".into());
+        return format!("{{{}}}", index_of(arg.name.clone()));
+    }
+    let debug = spec.ty == "?" || (spec.ty.ends_with('?') && !matches!(arg.value, Value::Int { .. }));
+    let mut expr = arg.name.clone();
+    let mut ty = String::new();
+    let mut precision = r.precision;
+    let text_like = is_text(&arg.value) || matches!(arg.value, Value::Bool(_));
+    match &arg.value {
+        Value::Str(_) | Value::Char(_) if debug => {
+            notes.note("`{:?}` on strings and chars needs C++23".into());
+            return format!("{{{}:?}}", index_of(expr)); // Debug for text ignores width and precision.
+        }
+        Value::Str(_) => {}
+        Value::Char(_) | Value::Bool(_) => {
+            if precision.take().is_some() {
+                notes.flag("std::format does not allow a precision for char or bool: dropped".into());
+            }
+        }
+        Value::Float { .. } => match spec.ty.as_str() {
+            "e" | "E" => {
+                ty = spec.ty.clone();
+                if let Some(note) = exponent_note(r, args, "std::format") {
+                    notes.flag(note);
+                }
+            }
+            _ if precision.is_some() => ty = "f".into(),
+            _ if debug => notes.flag("`{:?}` on a float prints `1.0`; std::format has no Debug form and prints `1`".into()),
+            _ => notes.flag("`{}` on a float: std::format switches to exponent form when it is shorter (`1e+15`)".into()),
+        },
+        Value::Int { ty: int_ty, .. } => {
+            if precision.take().is_some() {
+                notes.note("Rust ignores a precision on integers, std::format rejects it: dropped".into());
+            }
+            if int_bits(int_ty) == 128 {
+                notes.flag("standard C++ has no 128-bit integer type".into());
+            }
+            if radix(&spec.ty) {
+                ty = spec.ty.trim_end_matches('?').to_string();
+                if int_ty.starts_with('i') {
+                    expr = format!("static_cast<uint{}_t>({})", int_bits(int_ty), arg.name);
+                    notes.note(format!("cast to unsigned: Rust prints negative {} in two's complement, std::format would print a `-`", int_ty));
+                }
+                if spec.alternate && ty == "X" {
+                    notes.flag("`{:#X}` gives `0xFF` in Rust but `0XFF` in std::format".into());
+                }
+                if spec.alternate && ty == "o" {
+                    notes.flag("`{:#o}` gives `0o10` in Rust but `010` in std::format".into());
+                }
+            } else if spec.ty == "e" || spec.ty == "E" {
+                ty = spec.ty.clone();
+                expr = format!("static_cast<double>({})", arg.name);
+                notes.flag(format!("std::format has no `{}` for integers, so the value is cast to double, and it writes `1.200000e+03`", spec.ty));
+            }
+        }
+    }
+
+    let index = index_of(expr);
+    let mut out = String::new();
+    if !spec.zero_pad || text_like {
+        // With `0`, Rust ignores fill and alignment; std::format would ignore the `0` instead.
+        match spec.fill {
+            Some('{') | Some('}') => notes.flag("a `{` or `}` fill is not allowed in std::format: dropped".into()),
+            Some(fill) => out.push(fill),
+            None => {}
+        }
+        match spec.align {
+            Some(Align::Left) => out.push('<'),
+            Some(Align::Center) => out.push('^'),
+            Some(Align::Right) => out.push('>'),
+            None => {}
+        }
+    }
+    if spec.sign == Some(Sign::Plus) && !text_like {
+        out.push('+');
+    }
+    if spec.alternate && matches!(ty.as_str(), "x" | "X" | "o" | "b") {
+        out.push('#');
+    }
+    if spec.zero_pad {
+        if text_like {
+            notes.note("Rust ignores `0` for text and std::format rejects it: dropped".into());
+        } else {
+            out.push('0');
+        }
+    }
+    if let Some(width) = r.width {
+        out.push_str(&amount(width, &mut index_of));
+    }
+    if let Some(precision) = precision {
+        out.push('.');
+        out.push_str(&amount(precision, &mut index_of));
+    }
+    out.push_str(&ty);
+    if out.is_empty() {
+        format!("{{{}}}", index)
+    } else {
+        format!("{{{}:{}}}", index, out)
+    }
+}
+
+fn to_cpp_format(pieces: &[Piece], resolved: &[Resolved], args: &[Arg]) -> Translation {
+    let mut notes = Notes::default();
+    let mut call_args = Vec::new();
+    let mut body = String::new();
+    let mut placeholders = resolved.iter();
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => body.push_str(&text.replace('{', "{{").replace('}', "}}")),
+            Piece::Placeholder(_) => {
+                body.push_str(&cpp_format_placeholder(placeholders.next().unwrap(), args, &mut call_args, &mut notes))
+            }
+        }
+    }
+    let mut code = format!("std::format({}", quote(&body, '"'));
+    for arg in &call_args {
+        code.push_str(", ");
+        code.push_str(arg);
+    }
+    code.push(')');
+    Translation { code, notes }
+}
+
+// --- iostream and <iomanip> ---
+
+// The stream insertions for one placeholder, and the ones that undo its sticky flags.
+fn iostream_placeholder(r: &Resolved, args: &[Arg], notes: &mut Notes) -> (Vec<String>, Vec<String>) {
+    let spec = r.spec;
+    let arg = &args[r.value];
+    let name = &arg.name;
+    let amount = |a: Amount| match a {
+        Amount::Fixed(n) => n.to_string(),
+        Amount::Arg(i) => args[i].name.clone(),
+    };
+    let mut out: Vec<String> = Vec::new();
+    let mut reset: Vec<String> = Vec::new();
+    let mut set = |manip: &str, undo: &str, out: &mut Vec<String>| {
+        out.push(manip.to_string());
+        if !undo.is_empty() && !reset.iter().any(|u| u == undo) {
+            reset.push(undo.to_string());
+        }
+    };
+
+    if spec.ty == "?" && spec.alternate {
+        notes.flag("`{:#?}` pretty-prints Debug output; iostreams have no equivalent".into());
+        out.push(name.clone());
+        return (out, reset);
+    }
+    let debug = spec.ty == "?" || (spec.ty.ends_with('?') && !matches!(arg.value, Value::Int { .. }));
+    match &arg.value {
+        Value::Str(_) if debug => {
+            out.push(format!("std::quoted({})", name)); // Debug for text ignores width and precision.
+            return (out, reset);
+        }
+        Value::Char(_) if debug => {
+            out.extend(["'\\''".to_string(), name.clone(), "'\\''".to_string()]);
+            return (out, reset);
+        }
+        _ => {}
+    }
+
+    let text_like = is_text(&arg.value) || matches!(arg.value, Value::Bool(_));
+    if spec.zero_pad && !text_like {
+        set("std::setfill('0')", "std::setfill(' ')", &mut out);
+        set("std::internal", "std::right", &mut out); // Zeros go after the sign and the base.
+    } else {
+        if spec.zero_pad {
+            notes.note("Rust ignores `0` for text: dropped".into());
+        }
+        match spec.fill {
+            Some(fill) if fill.is_ascii() => set(&format!("std::setfill({})", quote(&fill.to_string(), '\'')), "std::setfill(' ')", &mut out),
+            Some(fill) => notes.flag(format!("std::setfill takes a single char, so `{}` cannot be the fill", fill)),
+            None => {}
+        }
+        match spec.align {
+            Some(Align::Left) => set("std::left", "std::right", &mut out),
+            Some(Align::Right) => set("std::right", "", &mut out),
+            Some(Align::Center) => notes.flag("iostreams cannot centre (`^`); pad by hand".into()),
+            None if text_like && r.width.is_some() => set("std::left", "std::right", &mut out), // Text left-aligns in Rust.
+            None => {}
+        }
+    }
+    if spec.sign == Some(Sign::Plus) && !text_like {
+        set("std::showpos", "std::noshowpos", &mut out);
+    }
+
+    let mut expr = name.clone();
+    match &arg.value {
+        Value::Bool(_) => {
+            set("std::boolalpha", "std::noboolalpha", &mut out);
+            if r.precision.is_some() {
+                notes.flag("iostreams cannot truncate a bool: precision dropped".into());
+            }
+        }
+        Value::Str(_) => {
+            if let Some(precision) = r.precision {
+                expr = format!("{}.substr(0, {})", name, amount(precision));
+                notes.flag("substr counts bytes, Rust's precision counts chars: text beyond ASCII is cut short".into());
+            }
+        }
+        Value::Char(_) => {
+            if r.precision.is_some() {
+                notes.flag("iostreams cannot truncate a char: precision dropped".into());
+            }
+        }
+        Value::Float { .. } => match (spec.ty.as_str(), r.precision) {
+            ("e" | "E", precision) => {
+                if spec.ty == "E" {
+                    set("std::uppercase", "std::nouppercase", &mut out);
+                }
+                set("std::scientific", "std::defaultfloat", &mut out);
+                if let Some(p) = precision {
+                    set(&format!("std::setprecision({})", amount(p)), "std::setprecision(6)", &mut out);
+                }
+                if let Some(note) = exponent_note(r, args, "std::scientific") {
+                    notes.flag(note);
+                }
+            }
+            (_, Some(p)) => {
+                set("std::fixed", "std::defaultfloat", &mut out);
+                set(&format!("std::setprecision({})", amount(p)), "std::setprecision(6)", &mut out);
+            }
+            (_, None) => notes.flag("iostreams print 6 significant digits by default; Rust prints the shortest exact form".into()),
+        },
+        Value::Int { ty: int_ty, .. } => {
+            if r.precision.is_some() {
+                notes.note("Rust ignores a precision on integers: dropped".into());
+            }
+            if int_bits(int_ty) == 128 {
+                notes.flag("standard C++ has no 128-bit integer type".into());
+            }
+            let bits = int_bits(int_ty);
+            if radix(&spec.ty) && int_ty.starts_with('i') {
+                expr = format!("static_cast<uint{}_t>({})", bits, name);
+                notes.note(format!("cast to unsigned: Rust prints negative {} in two's complement", int_ty));
+            }
+            match spec.ty.trim_end_matches('?') {
+                "x" | "X" => {
+                    set("std::hex", "std::dec", &mut out);
+                    if spec.ty.starts_with('X') {
+                        set("std::uppercase", "std::nouppercase", &mut out);
+                    }
+                    if spec.alternate {
+                        set("std::showbase", "std::noshowbase", &mut out);
+                        notes.flag(if spec.ty.starts_with('X') {
+                            "std::showbase with std::uppercase writes `0XFF`, and a zero gets no prefix".to_string()
+                        } else {
+                            "std::showbase writes no `0x` for zero".to_string()
+                        });
+                    }
+                }
+                "o" => {
+                    set("std::oct", "std::dec", &mut out);
+                    if spec.alternate {
+                        set("std::showbase", "std::noshowbase", &mut out);
+                        notes.flag("`{:#o}` gives `0o10` in Rust but std::showbase writes `010`".into());
+                    }
+                }
+                "b" => {
+                    expr = format!("std::bitset<{}>({})", bits, expr);
+                    notes.flag("iostreams have no binary output; std::bitset prints every bit, leading zeros included".into());
+                }
+                "e" | "E" => {
+                    expr = format!("static_cast<double>({})", name);
+                    set("std::scientific", "std::defaultfloat", &mut out);
+                    notes.flag(format!("`{{:{}}}` writes `1.2e3`; std::scientific writes `1.200000e+03`", spec.ty));
+                }
+                _ => {}
+            }
+            if bits == 8 && !expr.starts_with("std::bitset") {
+                expr = format!("+{}", expr); // Otherwise (u)int8_t prints as a character.
+            }
+        }
+    }
+    if let Some(width) = r.width {
+        out.push(format!("std::setw({})", amount(width))); // setw resets itself after one insertion.
+    }
+    out.push(expr);
+    (out, reset)
+}
+
+fn to_iostream(pieces: &[Piece], resolved: &[Resolved], args: &[Arg]) -> Translation {
+    let mut notes = Notes::default();
+    let mut chain: Vec<String> = Vec::new();
+    let mut placeholders = resolved.iter().peekable();
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => chain.push(quote(text, '"')),
+            Piece::Placeholder(_) => {
+                let (insertions, reset) = iostream_placeholder(placeholders.next().unwrap(), args, &mut notes);
+                chain.extend(insertions);
+                // Flags other than setw stick to the stream; undo them before the next value.
+                if placeholders.peek().is_some() {
+                    chain.extend(reset);
+                }
+            }
+        }
+    }
+    Translation { code: chain.iter().map(|c| format!(" << {}", c)).collect(), notes }
+}
+
+// --- Putting it together ---
+
+struct Case {
+    format: String,
+    args: Vec<Arg>,
+    expected: String, // What Rust prints.
+}
+
+struct Translated {
+    python: Translation,
+    cpp_format: Translation,
+    iostream: Translation,
+}
+
+fn translate(case: &Case) -> Result<Translated, String> {
+    let pieces = format_spec::parse_format_string(&case.format).map_err(|e| e.render(&case.format))?;
+    let resolved = resolve(&pieces, &case.args)?;
+    Ok(Translated {
+        python: to_python(&pieces, &resolved, &case.args),
+        cpp_format: to_cpp_format(&pieces, &resolved, &case.args),
+        iostream: to_iostream(&pieces, &resolved, &case.args),
+    })
+}
+
+fn default_name(i: usize) -> String {
+    ["x", "y", "z", "w"].get(i).map(|s| s.to_string()).unwrap_or_else(|| format!("arg{}", i))
+}
+
+fn named(values: Vec<Value>) -> Vec<Arg> {
+    values.into_iter().enumerate().map(|(i, value)| Arg { name: default_name(i), value, captured: false }).collect()
+}
+
+fn print_translation(case: &Case, t: &Translated) {
+    let rust_args: Vec<&str> = case.args.iter().filter(|a| !a.captured).map(|a| a.name.as_str()).collect();
+    let declarations: Vec<String> = case.args.iter().map(|a| format!("let {} = {};", a.name, a.value.to_rust())).collect();
+    println!("Rust:       println!({}{}{});", quote(&case.format, '"'), if rust_args.is_empty() { "" } else { ", " }, rust_args.join(", "));
+    println!("            // {}  prints {:?}", declarations.join(" "), case.expected);
+    println!("Python:     print({})", t.python.code);
+    println!("C++20:      std::cout << {} << '\\n';", t.cpp_format.code);
+    println!("iostream:   std::cout{} << '\\n';", t.iostream.code);
+    for (label, tr) in [("Python", &t.python), ("C++20", &t.cpp_format), ("iostream", &t.iostream)] {
+        for note in &tr.notes.list {
+            println!("  {:<9} {}", format!("{}:", label), note);
+        }
+    }
+}
+
+// --- Running the translations ---
+
+fn python_program(cases: &[(Case, Translated)]) -> String {
+    let mut out = String::from("# Generated by format_translate.rs; prints `index<TAB>output` per case.\nimport pprint\n\n");
+    out.push_str("def show(i, text):\n    print(i, text.replace(\"\\\\\", \"\\\\\\\\\").replace(\"\\n\", \"\\\\n\").replace(\"\\t\", \"\\\\t\"), sep=\"\\t\")\n\n");
+    for (i, (case, t)) in cases.iter().enumerate() {
+        out.push_str(&format!("def case_{}():\n", i));
+        for arg in &case.args {
+            out.push_str(&format!("    {} = {}\n", arg.name, python_literal(&arg.value)));
+        }
+        out.push_str(&format!("    return {}\n\n", t.python.code));
+    }
+    for i in 0..cases.len() {
+        out.push_str(&format!("show({}, case_{}())\n", i, i));
+    }
+    out
+}
+
+fn cpp_program(cases: &[(Case, Translated)]) -> String {
+    let mut out = String::from(
+        "// Generated by format_translate.rs; prints `kind index<TAB>output` per case.\n\
+         #include <bitset>\n#include <cstdint>\n#include <iomanip>\n#include <iostream>\n#include <limits>\n#include <sstream>\n#include <string>\n\
+         #if __has_include(<format>)\n#include <format>\n#define HAVE_STD_FORMAT 1\n#endif\n\n\
+         static void show(const char* kind, int i, const std::string& text) {\n    std::cout << kind << ' ' << i << '\\t';\n\
+             for (char c : text) {\n        if (c == '\\\\') std::cout << \"\\\\\\\\\";\n        else if (c == '\\n') std::cout << \"\\\\n\";\n\
+                 else if (c == '\\t') std::cout << \"\\\\t\";\n        else std::cout << c;\n    }\n    std::cout << '\\n';\n}\n\nint main() {\n",
+    );
+    for (i, (case, t)) in cases.iter().enumerate() {
+        out.push_str("    {\n");
+        for arg in &case.args {
+            out.push_str(&format!("        {} {} = {};\n", cpp_type(&arg.value), arg.name, cpp_literal(&arg.value)));
+        }
+        out.push_str(&format!("        std::ostringstream out;\n        out{};\n        show(\"iostream\", {}, out.str());\n", t.iostream.code, i));
+        out.push_str(&format!("#ifdef HAVE_STD_FORMAT\n        show(\"format\", {}, {});\n#endif\n", i, t.cpp_format.code));
+        for arg in &case.args {
+            out.push_str(&format!("        (void){};\n", arg.name));
+        }
+        out.push_str("    }\n");
+    }
+    out.push_str("#ifndef HAVE_STD_FORMAT\n    std::cout << \"no <format>\\n\";\n#endif\n    return 0;\n}\n");
+    out
+}
+
+fn run(command: &mut Command) -> Result<String, String> {
+    let output = command.output().map_err(|e| format!("{:?}: {}", command, e))?;
+    if !output.status.success() {
+        return Err(format!("{:?} failed:\n{}", command, String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Runs every translation and compares it with Rust. Fails only when a translation
+// claimed to be exact prints something else.
+fn run_cases(cases: Vec<Case>) -> Result<bool, String> {
+    let mut translated = Vec::new();
+    for case in cases {
+        let t = translate(&case)?;
+        translated.push((case, t));
+    }
+    fs::create_dir_all("target").map_err(|e| e.to_string())?;
+    fs::write("target/format_translate_check.py", python_program(&translated)).map_err(|e| e.to_string())?;
+    fs::write("target/format_translate_check.cpp", cpp_program(&translated)).map_err(|e| e.to_string())?;
+    let python = run(Command::new("python3").arg("target/format_translate_check.py"))?;
+    let cxx = std::env::var("CXX").unwrap_or_else(|_| "c++".to_string());
+    run(Command::new(&cxx).args(["-std=c++20", "-o", "target/format_translate_check", "target/format_translate_check.cpp"]))?;
+    let cpp = run(&mut Command::new("target/format_translate_check"))?;
+
+    let lookup = |output: &str, key: &str| -> Option<String> {
+        output.lines().find_map(|line| {
+            let (k, v) = line.split_once('\t')?;
+            (k == key).then(|| v.replace("\\n", "\n").replace("\\t", "\t").replace("\\\\", "\\"))
+        })
+    };
+    let have_format = !cpp.contains("no <format>");
+    let mut ok = true;
+    let (mut same, mut flagged) = (0, 0);
+    for (i, (case, t)) in translated.iter().enumerate() {
+        println!("{:<28} => {:?}", case.format, case.expected);
+        let results = [
+            ("Python", &t.python, lookup(&python, &i.to_string())),
+            ("C++20", &t.cpp_format, if have_format { lookup(&cpp, &format!("format {}", i)) } else { None }),
+            ("iostream", &t.iostream, lookup(&cpp, &format!("iostream {}", i))),
+        ];
+        for (label, translation, actual) in results {
+            let status = match &actual {
+                None => "skipped (no <format>)".to_string(),
+                Some(a) if *a == case.expected => {
+                    same += 1;
+                    "same".to_string()
+                }
+                Some(a) if translation.notes.inexact => {
+                    flagged += 1;
+                    format!("differs, as flagged: {:?}", a)
+                }
+                Some(a) => {
+                    ok = false;
+                    format!("FAIL: {:?}", a)
+                }
+            };
+            println!("    {:<9} {}", label, status);
+        }
+    }
+    println!("{} outputs identical to Rust, {} differ where the translation says they will", same, flagged);
+    if !have_format {
+        println!("std::format translations were not compiled: this C++ library has no <format>");
+    }
+    Ok(ok)
+}
+
+macro_rules! case {
+    ($format:literal $(, $value:expr)*) => {
+        Case { format: $format.to_string(), args: named(vec![$(Value::from($value)),*]), expected: format!($format $(, $value)*) }
+    };
+}
+
+fn sample_cases() -> Vec<Case> {
+    vec![
+        case!("{:>10.2}", PI),
+        case!("x = {:>10.2}, n = {}", PI, 42),
+        case!("{:.5}", PI),
+        case!("{:05}", 42),
+        case!("{:<5}|", 42),
+        case!("{:+}", 42),
+        case!("{:+.1}", 2.25),
+        case!("{:08.3}", -PI),
+        case!("{:<05}", 7),
+        case!("{:^9}", "mid"),
+        case!("{:*>10}", "right"),
+        case!("{:.<10}", "left"),
+        case!("{:-^20}", "centered"),
+        case!("{:8}|", "text"),
+        case!("{:.3}", "truncate"),
+        case!("{:.3}", "héllo"),
+        case!("{:b}", 10),
+        case!("{:#010b}", 5u8),
+        case!("{:x}", 255),
+        case!("{:#x}", 255),
+        case!("{:#06x}", 255),
+        case!("{:X}", 255),
+        case!("{:#X}", 255),
+        case!("{:x}", -1i32),
+        case!("{:x}", -128i8),
+        case!("{:>5}", -128i8),
+        case!("{:o}", 8),
+        case!("{:#o}", 8),
+        case!("{:e}", 1234.5),
+        case!("{}", 1.0),
+        case!("{}", 0.1),
+        case!("{:?}", 0.1),
+        case!("{:?}", "hi"),
+        case!("{:?}", 'c'),
+        case!("{:#?}", 1.5),
+        case!("{}", true),
+        case!("{:>6}|", false),
+        case!("{}", 'c'),
+        case!("{:>1$}", 7, 5),
+        case!("{:.*}", 3, PI),
+        case!("{{{}}}", 42),
+        case!("{1} {0}", "a", "b"),
+    ]
+}
+
+fn parse_args(words: &[String]) -> Result<Case, String> {
+    let format = words.first().ok_or("missing the format string")?.clone();
+    let (mut args, mut captured) = (Vec::new(), Vec::new());
+    for word in &words[1..] {
+        match word.split_once('=') {
+            Some((name, text)) if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                captured.push(Arg { name: name.to_string(), value: Value::parse(text)?, captured: true })
+            }
+            _ => args.push(Arg { name: default_name(args.len()), value: Value::parse(word)?, captured: false }),
+        }
+    }
+    args.extend(captured); // Positional arguments come first, as in Rust.
+    let pieces = format_spec::parse_format_string(&format).map_err(|e| e.render(&format))?;
+    let resolved = resolve(&pieces, &args)?;
+    let expected = rust_output(&pieces, &resolved, &args)?;
+    Ok(Case { format, args, expected })
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        None => {
+            eprintln!("usage: format_translate [--run] FORMAT [VALUE | name=VALUE]... | --check");
+            process::exit(2);
+        }
+        Some("--check") => run_cases(sample_cases()),
+        Some("--run") => parse_args(&args[1..]).and_then(|case| {
+            print_translation(&case, &translate(&case)?);
+            println!();
+            run_cases(vec![case])
+        }),
+        Some(_) => parse_args(&args).and_then(|case| {
+            print_translation(&case, &translate(&case)?);
+            Ok(true)
+        }),
+    };
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("{}", e.trim_end());
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(t: &Translated) -> [&str; 3] {
+        [&t.python.code, &t.cpp_format.code, &t.iostream.code]
+    }
+
+    fn inexact(t: &Translated) -> [bool; 3] {
+        [t.python.notes.inexact, t.cpp_format.notes.inexact, t.iostream.notes.inexact]
+    }
+
+    #[test]
+    fn width_and_precision_on_a_float() {
+        let t = translate(&case!("{:>10.2}", PI)).unwrap();
+        assert_eq!(
+            codes(&t),
+            [
+                "f\"{x:>10.2f}\"",
+                "std::format(\"{0:>10.2f}\", x)",
+                " << std::right << std::fixed << std::setprecision(2) << std::setw(10) << x",
+            ]
+        );
+        assert_eq!(inexact(&t), [false; 3]);
+    }
+
+    #[test]
+    fn text_precision_is_exact_except_for_substr() {
+        let t = translate(&case!("{:.3}", "héllo")).unwrap();
+        assert_eq!(codes(&t), ["f\"{x:.3}\"", "std::format(\"{0:.3}\", x)", " << x.substr(0, 3)"]);
+        assert_eq!(inexact(&t), [false, false, true]);
+    }
+
+    #[test]
+    fn negative_hex_is_masked_or_cast() {
+        let t = translate(&case!("{:x}", -1i32)).unwrap();
+        assert_eq!(
+            codes(&t),
+            ["f\"{(x & 0xffffffff):x}\"", "std::format(\"{0:x}\", static_cast<uint32_t>(x))", " << std::hex << static_cast<uint32_t>(x)"]
+        );
+        assert_eq!(inexact(&t), [false; 3]);
+    }
+
+    #[test]
+    fn centring_has_no_iostream_equivalent() {
+        let t = translate(&case!("{:^9}", "mid")).unwrap();
+        assert_eq!(inexact(&t), [false, false, true]);
+        assert!(t.iostream.notes.list.iter().any(|n| n.contains("cannot centre")));
+    }
+
+    #[test]
+    fn exponents_are_flagged_with_this_value() {
+        let t = translate(&case!("{:e}", 1234.5)).unwrap();
+        assert_eq!(inexact(&t), [true; 3]);
+        assert!(t.python.notes.list.contains(&"Rust writes `1.2345e3`; Python writes `1.234500e+03`".to_string()));
+    }
+
+    #[test]
+    fn positional_and_runtime_arguments() {
+        let t = translate(&case!("{1} {0}", "a", "b")).unwrap();
+        assert_eq!(codes(&t)[..2], ["f\"{y} {x}\"", "std::format(\"{0} {1}\", y, x)"]);
+        let t = translate(&case!("{:.*}", 3, PI)).unwrap();
+        assert_eq!(codes(&t)[0], "f\"{y:.{x}f}\"");
+    }
+
+    #[test]
+    fn every_sample_case_translates() {
+        for case in sample_cases() {
+            assert!(translate(&case).is_ok(), "{}", case.format);
+        }
+    }
+}
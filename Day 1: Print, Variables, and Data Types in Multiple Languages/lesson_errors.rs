@@ -0,0 +1,659 @@
+// lesson_errors.rs
+// Compiles a lesson file and explains the borrow checker's errors in plain words, each one
+// linked to the section of Day001.002.rs that teaches the rule it enforces.
+// rustc says "borrow of moved value"; this adds "that's section 3, fn ownership_and_moves".
+//
+// Usage:
+//   rustc --edition 2021 lesson_errors.rs -o target/lesson_errors
+//   target/lesson_errors                     # check Day001.002.rs
+//   target/lesson_errors Day001.002.rs       # ...or any file (the sections are looked up in it)
+//   target/lesson_errors --json saved.json   # explain diagnostics saved with --error-format=json
+//   target/lesson_errors --check             # un-comment the lesson's own broken examples and
+//                                            # check each error maps to the right section
+// Tests (the section lookup):
+//   rustc --edition 2021 --test lesson_errors.rs -o target/lesson_errors_test && target/lesson_errors_test
+//
+// rustc runs with `--error-format=json`, which prints one JSON object per diagnostic on stderr.
+// Warnings are counted but not shown; errors without a lesson link are shown as rustc wrote them.
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+
+const DEFAULT_LESSON: &str = "Day001.002.rs";
+
+// --- Error code -> lesson section ---
+
+struct Lesson {
+    code: &'static str,
+    function: &'static str, // The lesson function whose section teaches the rule.
+    plain: &'static str,
+}
+
+const LESSONS: &[Lesson] = &[
+    Lesson {
+        code: "E0382",
+        function: "ownership_and_moves",
+        plain: "The value was moved to a new owner, and then used through its old name. \
+                After a move only the new owner may use it. Borrow it with `&` instead of \
+                moving it, or `.clone()` it if you really need two copies.",
+    },
+    Lesson {
+        code: "E0499",
+        function: "rules_of_references",
+        plain: "Two `&mut` borrows of the same value are alive at the same time. Only one \
+                mutable reference may exist at once, so nothing can change the data behind \
+                another reference's back. Stop using the first one before making the second.",
+    },
+    Lesson {
+        code: "E0502",
+        function: "rules_of_references",
+        plain: "The value is borrowed with `&` and `&mut` at the same time. While anyone is \
+                reading through a shared reference, nobody may change the value. Finish with \
+                the `&` borrow (its last use) before taking the `&mut` one.",
+    },
+    Lesson {
+        code: "E0106",
+        function: "lifetimes_explained",
+        plain: "A function returns a reference, but Rust can't tell which input it borrows from. \
+                Name a lifetime, as `longest_slice<'a>` does, to say which. If the data is \
+                created inside the function, return it owned (`String`) instead.",
+    },
+    Lesson {
+        code: "E0597",
+        function: "lifetimes_explained",
+        plain: "A reference is still in use after the value it points to was dropped at the end \
+                of its scope. Keep the value alive longer (declare it in the outer scope), or \
+                stop using the reference before the value goes away.",
+    },
+    Lesson {
+        code: "E0373",
+        function: "closures_and_capturing",
+        plain: "A closure that may run after this function returns (a thread, or a closure \
+                that is returned) borrows a local variable, which will be gone by then. \
+                Write `move ||` so the closure takes ownership of what it uses.",
+    },
+];
+
+fn lesson_for(code: &str) -> Option<&'static Lesson> {
+    LESSONS.iter().find(|l| l.code == code)
+}
+
+// Where `fn name` is in the source, and the `// --- N. Title ---` header of its section.
+fn find_section(source: &str, function: &str) -> Option<(usize, Option<String>)> {
+    let lines: Vec<&str> = source.lines().collect();
+    let fn_line = lines.iter().position(|l| l.starts_with(&format!("fn {}(", function)))?;
+    let title = |l: &&str| l.trim_start().strip_prefix("// --- ").map(|t| t.trim_end_matches(" ---").to_string());
+    // Day001.003.rs has its `// --- N.M.` headings inside the function, so the first one in the
+    // body wins; otherwise the nearest one above it, but not past a `// ~~~` level banner.
+    let header = lines[fn_line + 1..]
+        .iter()
+        .take_while(|l| !l.starts_with('}'))
+        .find_map(title)
+        .or_else(|| lines[..fn_line].iter().rev().take_while(|l| !l.starts_with("// ~~~")).find_map(title))
+        .filter(|t| !t.is_empty());
+    Some((fn_line + 1, header))
+}
+
+// --- A small JSON reader (enough for rustc's diagnostics; std has none) ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn get(&self, key: &str) -> &Json {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v).unwrap_or(&Json::Null),
+            _ => &Json::Null,
+        }
+    }
+    fn str(&self) -> Option<&str> {
+        match self {
+            Json::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+    fn num(&self) -> usize {
+        match self {
+            Json::Number(n) => *n as usize,
+            _ => 0,
+        }
+    }
+    fn items(&self) -> &[Json] {
+        match self {
+            Json::Array(items) => items,
+            _ => &[],
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_space(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, word: &str) -> Result<(), String> {
+        for wanted in word.chars() {
+            if self.chars.next() != Some(wanted) {
+                return Err(format!("expected `{}`", word));
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_space();
+        match self.chars.peek().copied() {
+            Some('n') => self.expect("null").map(|_| Json::Null),
+            Some('t') => self.expect("true").map(|_| Json::Bool(true)),
+            Some('f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some('"') => self.string().map(Json::Str),
+            Some('[') => {
+                self.chars.next();
+                let mut items = Vec::new();
+                loop {
+                    self.skip_space();
+                    if self.chars.peek() == Some(&']') {
+                        self.chars.next();
+                        return Ok(Json::Array(items));
+                    }
+                    if !items.is_empty() {
+                        self.expect(",")?;
+                    }
+                    items.push(self.value()?);
+                }
+            }
+            Some('{') => {
+                self.chars.next();
+                let mut fields = Vec::new();
+                loop {
+                    self.skip_space();
+                    if self.chars.peek() == Some(&'}') {
+                        self.chars.next();
+                        return Ok(Json::Object(fields));
+                    }
+                    if !fields.is_empty() {
+                        self.expect(",")?;
+                        self.skip_space();
+                    }
+                    let key = self.string()?;
+                    self.skip_space();
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                }
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let mut text = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if !(c.is_ascii_digit() || "+-.eE".contains(c)) {
+                        break;
+                    }
+                    text.push(c);
+                    self.chars.next();
+                }
+                text.parse().map(Json::Number).map_err(|_| format!("bad number `{}`", text))
+            }
+            other => Err(format!("unexpected {:?}", other)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut out = String::new();
+        loop {
+            match self.chars.next().ok_or("unterminated string")? {
+                '"' => return Ok(out),
+                '\\' => match self.chars.next().ok_or("unterminated string")? {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    'r' => out.push('\r'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'u' => {
+                        let mut code = self.hex4()?;
+                        if (0xD800..0xDC00).contains(&code) {
+                            self.expect("\\u")?; // A surrogate pair: the second half follows.
+                            code = 0x10000 + ((code - 0xD800) << 10) + (self.hex4()? - 0xDC00);
+                        }
+                        out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                    }
+                    c => out.push(c), // `\"`, `\\` and `\/`
+                },
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let digits: String = (0..4).filter_map(|_| self.chars.next()).collect();
+        u32::from_str_radix(&digits, 16).map_err(|_| format!("bad escape `\\u{}`", digits))
+    }
+}
+
+fn parse_json(text: &str) -> Result<Json, String> {
+    let mut parser = Parser { chars: text.chars().peekable() };
+    let value = parser.value()?;
+    parser.skip_space();
+    match parser.chars.next() {
+        None => Ok(value),
+        Some(c) => Err(format!("trailing {:?}", c)),
+    }
+}
+
+// --- Diagnostics ---
+
+struct Span {
+    line: usize,
+    column_start: usize,
+    column_end: usize,
+    text: String, // The source line, as rustc quotes it.
+    label: Option<String>,
+    primary: bool,
+}
+
+struct Diagnostic {
+    level: String,
+    code: Option<String>,
+    message: String,
+    spans: Vec<Span>,
+    children: Vec<(String, String)>, // (level, message), e.g. ("help", "consider cloning the value...")
+    rendered: String,
+}
+
+fn diagnostic_from_json(json: &Json) -> Diagnostic {
+    let spans = json
+        .get("spans")
+        .items()
+        .iter()
+        .map(|span| {
+            let text = span.get("text").items().first().and_then(|t| t.get("text").str()).unwrap_or("").to_string();
+            let line_start = span.get("line_start").num();
+            let multi_line = span.get("line_end").num() != line_start;
+            Span {
+                line: line_start,
+                column_start: span.get("column_start").num(),
+                // A span over several lines is underlined to the end of its first line.
+                column_end: if multi_line { text.chars().count() + 1 } else { span.get("column_end").num() },
+                text,
+                label: span.get("label").str().map(str::to_string),
+                primary: span.get("is_primary") == &Json::Bool(true),
+            }
+        })
+        .collect();
+    let children = json
+        .get("children")
+        .items()
+        .iter()
+        .map(|c| (c.get("level").str().unwrap_or("note").to_string(), c.get("message").str().unwrap_or("").to_string()))
+        .collect();
+    Diagnostic {
+        level: json.get("level").str().unwrap_or("error").to_string(),
+        code: json.get("code").get("code").str().map(str::to_string),
+        message: json.get("message").str().unwrap_or("").to_string(),
+        spans,
+        children,
+        rendered: json.get("rendered").str().unwrap_or("").to_string(),
+    }
+}
+
+fn parse_diagnostics(stderr: &str) -> Result<Vec<Diagnostic>, String> {
+    stderr
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| parse_json(line).map(|json| diagnostic_from_json(&json)))
+        .collect()
+}
+
+// Runs rustc on `source`, with `dir` as the directory `mod` files are found in.
+fn compile(source: &str, dir: &Path) -> Result<Vec<Diagnostic>, String> {
+    let out_dir = env::temp_dir().join(format!("lesson_errors_{}", process::id()));
+    fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let mut child = Command::new(&rustc)
+        .args(["--edition", "2021", "--error-format=json", "--emit=metadata", "--crate-name", "lesson", "-o"])
+        .arg(out_dir.join("lesson.rmeta"))
+        .arg("-") // The source comes on stdin, so `--check` can compile edited copies.
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run {}: {}", rustc, e))?;
+    child.stdin.take().expect("stdin is piped").write_all(source.as_bytes()).map_err(|e| e.to_string())?;
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    fs::remove_dir_all(&out_dir).ok();
+    parse_diagnostics(&String::from_utf8_lossy(&output.stderr))
+}
+
+// --- Rendering ---
+
+// Breaks `text` into lines of at most 72 columns, indenting the continuation lines.
+fn wrap(text: &str, indent: usize) -> String {
+    let mut lines = vec![String::new()];
+    for word in text.split_whitespace() {
+        let line = lines.last_mut().expect("never empty");
+        if !line.is_empty() && line.len() + 1 + word.len() > 72 {
+            lines.push(word.to_string());
+        } else {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+    }
+    lines.join(&format!("\n{:indent$}", "", indent = indent))
+}
+
+fn render(d: &Diagnostic, file_name: &str, source: &str) -> String {
+    let code = d.code.as_deref().unwrap_or("");
+    let lesson = lesson_for(code);
+    if lesson.is_none() {
+        return d.rendered.replace("<anon>", file_name).trim_end().to_string() + "\n"; // No lesson: rustc's own words.
+    }
+    let mut out = format!("{}[{}]: {}\n", d.level, code, d.message);
+    let mut spans: Vec<&Span> = d.spans.iter().collect();
+    spans.sort_by_key(|s| (s.line, s.column_start));
+    let width = spans.iter().map(|s| s.line.to_string().len()).max().unwrap_or(1);
+    if let Some(first) = spans.iter().find(|s| s.primary).or(spans.first()) {
+        out.push_str(&format!("{:width$}--> {}:{}:{}\n", "", file_name, first.line, first.column_start, width = width));
+        out.push_str(&format!("{:width$} |\n", "", width = width));
+    }
+    let mut last_line = 0;
+    for span in &spans {
+        if span.line != last_line {
+            if last_line != 0 && span.line > last_line + 1 {
+                out.push_str(&format!("{:width$} ...\n", "", width = width));
+            }
+            out.push_str(&format!("{:>width$} | {}\n", span.line, span.text, width = width));
+            last_line = span.line;
+        }
+        let mark = if span.primary { "^" } else { "-" };
+        let underline = mark.repeat(span.column_end.saturating_sub(span.column_start).max(1));
+        out.push_str(&format!(
+            "{:width$} | {:indent$}{} {}\n",
+            "",
+            "",
+            underline,
+            span.label.as_deref().unwrap_or(""),
+            width = width,
+            indent = span.column_start.saturating_sub(1)
+        ));
+    }
+    for (level, message) in &d.children {
+        if !message.is_empty() {
+            out.push_str(&format!("{:width$} = {}: {}\n", "", level, message, width = width));
+        }
+    }
+    let lesson = lesson.expect("checked above");
+    out.push_str(&format!("{:width$} = in plain words: {}\n", "", wrap(lesson.plain, width + 19), width = width));
+    match find_section(source, lesson.function) {
+        Some((line, None)) => out.push_str(&format!(
+            "{:width$} = learn it in: fn {} ({}:{})\n",
+            "",
+            lesson.function,
+            file_name,
+            line,
+            width = width
+        )),
+        Some((line, Some(header))) => out.push_str(&format!(
+            "{:width$} = learn it in: {}, fn {} ({}:{})\n",
+            "",
+            header,
+            lesson.function,
+            file_name,
+            line,
+            width = width
+        )),
+        None => out.push_str(&format!(
+            "{:width$} = learn it in: fn {} in {}\n",
+            "",
+            lesson.function,
+            DEFAULT_LESSON,
+            width = width
+        )),
+    }
+    out
+}
+
+// Prints the errors, returns how many there were.
+fn report(diagnostics: &[Diagnostic], file_name: &str, source: &str) -> usize {
+    let errors: Vec<&Diagnostic> = diagnostics
+        .iter()
+        .filter(|d| d.level == "error" && !d.message.starts_with("aborting due to"))
+        .collect();
+    let warnings = diagnostics.iter().filter(|d| d.level == "warning" && !d.message.ends_with("warnings emitted")).count();
+    for d in &errors {
+        println!("{}", render(d, file_name, source));
+    }
+    let linked = errors.iter().filter(|d| d.code.as_deref().and_then(lesson_for).is_some()).count();
+    println!(
+        "{}: {} error(s), {} linked to a lesson section; {} warning(s) not shown",
+        file_name,
+        errors.len(),
+        linked,
+        warnings
+    );
+    errors.len()
+}
+
+// --- --check: the lesson's own broken examples ---
+
+// Each case un-comments (or adds) code in Day001.002.rs that must fail with `code`.
+fn check_cases() -> Vec<(&'static str, Vec<(&'static str, &'static str)>)> {
+    vec![
+        ("E0382", vec![("    // println!(\"str_owner1: {}\", str_owner1);", "    println!(\"str_owner1: {}\", str_owner1);")]),
+        (
+            "E0502",
+            vec![
+                ("    // let mut s_err = String::from", "    let mut s_err = String::from"),
+                ("    // let ref_immut = &s_err;", "    let ref_immut = &s_err;"),
+                ("    // let ref_mut = &mut s_err;", "    let ref_mut = &mut s_err;"),
+                ("    // println!(\"{}, {}\", ref_immut, ref_mut);", "    println!(\"{}, {}\", ref_immut, ref_mut);"),
+            ],
+        ),
+        (
+            "E0499",
+            vec![
+                ("    // let mut s_err2 = String::from", "    let mut s_err2 = String::from"),
+                ("    // let ref_mut1 = &mut s_err2;", "    let ref_mut1 = &mut s_err2;"),
+                ("    // let ref_mut2 = &mut s_err2;", "    let ref_mut2 = &mut s_err2;"),
+                ("    // println!(\"{}, {}\", ref_mut1, ref_mut2);", "    println!(\"{}, {}\", ref_mut1, ref_mut2);"),
+            ],
+        ),
+        (
+            "E0106",
+            vec![
+                ("    // fn dangling_reference_example() -> &String {", "    fn dangling_reference_example() -> &String {"),
+                ("    //     let s = String::from(\"temporary\");", "        let s = String::from(\"temporary\");"),
+                ("    //     &s // Returning", "        &s // Returning"),
+                ("    // } // But `s` is dropped", "    } // But `s` is dropped"),
+            ],
+        ),
+        (
+            "E0597",
+            vec![(
+                "    // println!(\"The longest string outside inner scope: {}\", result);",
+                "    println!(\"The longest string outside inner scope: {}\", result);",
+            )],
+        ),
+        (
+            "E0373",
+            vec![(
+                "    print_vars();\n",
+                "    print_vars();\n    std::thread::spawn(|| println!(\"{}\", a_string)).join().unwrap();\n",
+            )],
+        ),
+    ]
+}
+
+fn check(dir: &Path) -> bool {
+    let path = dir.join(DEFAULT_LESSON);
+    let original = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) => {
+            println!("FAIL cannot read {}: {}", path.display(), e);
+            return false;
+        }
+    };
+    let mut ok = true;
+    let mut fail = |what: String| {
+        println!("FAIL {}", what);
+        ok = false;
+    };
+
+    // The JSON reader on the escapes rustc uses.
+    let sample = r#"{"a": [1, -2.5e1, true, null], "b": "q\"\\\nö🦀", "c": {}}"#;
+    match parse_json(sample) {
+        Ok(json) if json.get("b").str() == Some("q\"\\\nö🦀") && json.get("a").items().len() == 4 => {}
+        other => fail(format!("JSON reader on {}: {:?}", sample, other)),
+    }
+
+    match compile(&original, dir) {
+        Ok(diagnostics) if diagnostics.iter().all(|d| d.level != "error") => println!("ok   {} compiles as committed", DEFAULT_LESSON),
+        Ok(_) => fail(format!("{} does not compile as committed", DEFAULT_LESSON)),
+        Err(e) => fail(e),
+    }
+
+    for (code, edits) in check_cases() {
+        let mut source = original.clone();
+        for (from, to) in &edits {
+            if !source.contains(from) {
+                fail(format!("{}: the lesson no longer contains {:?}", code, from));
+            }
+            source = source.replacen(from, to, 1);
+        }
+        let diagnostics = match compile(&source, dir) {
+            Ok(d) => d,
+            Err(e) => {
+                fail(e);
+                continue;
+            }
+        };
+        let codes: Vec<&str> = diagnostics.iter().filter_map(|d| d.code.as_deref()).filter(|c| c.starts_with('E')).collect();
+        let Some(found) = diagnostics.iter().find(|d| d.code.as_deref() == Some(code)) else {
+            fail(format!("{}: rustc gave {:?} instead", code, codes));
+            continue;
+        };
+        let lesson = lesson_for(code).expect("every case has a lesson");
+        let rendered = render(found, DEFAULT_LESSON, &source);
+        let section = find_section(&source, lesson.function);
+        // Some span (where the error is, or where the borrow is used) must be on an edited line.
+        let edited_lines: Vec<usize> = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !original.lines().any(|o| o == *line))
+            .map(|(i, _)| i + 1)
+            .collect();
+        if section.is_none() || !rendered.contains(&format!("fn {} (", lesson.function)) {
+            fail(format!("{}: no link to fn {}", code, lesson.function));
+        } else if codes.len() != 1 {
+            fail(format!("{}: expected exactly one error, rustc gave {:?}", code, codes));
+        } else if !found.spans.iter().any(|s| edited_lines.contains(&s.line)) {
+            let lines: Vec<usize> = found.spans.iter().map(|s| s.line).collect();
+            fail(format!("{}: spans on lines {:?}, but the edit is on {:?}", code, lines, edited_lines));
+        } else {
+            println!("ok   {} -> {} (fn {})", code, section.and_then(|s| s.1).unwrap_or_default(), lesson.function);
+        }
+    }
+    println!("{}", if ok { "all checks passed" } else { "some checks FAILED" });
+    ok
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let here = PathBuf::from(".");
+    match args.first().map(String::as_str) {
+        Some("--check") => process::exit(if check(&here) { 0 } else { 1 }),
+        Some("--json") => {
+            let Some(json_path) = args.get(1) else {
+                eprintln!("usage: lesson_errors --json saved.json [lesson.rs]");
+                process::exit(2);
+            };
+            let lesson = args.get(2).map(String::as_str).unwrap_or(DEFAULT_LESSON);
+            let text = fs::read_to_string(json_path).unwrap_or_else(|e| {
+                eprintln!("{}: {}", json_path, e);
+                process::exit(2);
+            });
+            let source = fs::read_to_string(lesson).unwrap_or_default();
+            match parse_diagnostics(&text) {
+                Ok(diagnostics) => process::exit(if report(&diagnostics, lesson, &source) == 0 { 0 } else { 1 }),
+                Err(e) => {
+                    eprintln!("{}: not rustc JSON output: {}", json_path, e);
+                    process::exit(2);
+                }
+            }
+        }
+        file => {
+            let file = file.unwrap_or(DEFAULT_LESSON);
+            let source = fs::read_to_string(file).unwrap_or_else(|e| {
+                eprintln!("{}: {}", file, e);
+                process::exit(2);
+            });
+            let dir = Path::new(file).parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(&here);
+            match compile(&source, dir) {
+                Ok(diagnostics) => process::exit(if report(&diagnostics, file, &source) == 0 { 0 } else { 1 }),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(2);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+// --- 3. Ownership and Moves ---
+fn ownership_and_moves() {
+}
+
+fn helper() {
+}
+
+// ~~~ LEVEL 8 ~~~
+fn level_8_lifetimes() {
+    // --- 8.1. Explicit Lifetime Annotations ---
+}
+
+// ~~~ LEVEL 9 ~~~
+fn no_heading() {
+}
+";
+
+    #[test]
+    fn heading_above_the_function() {
+        let moves = Some("3. Ownership and Moves".to_string());
+        assert_eq!(find_section(SOURCE, "ownership_and_moves"), Some((2, moves.clone())));
+        assert_eq!(find_section(SOURCE, "helper"), Some((5, moves)));
+    }
+
+    #[test]
+    fn heading_inside_the_function() {
+        let section = find_section(SOURCE, "level_8_lifetimes");
+        assert_eq!(section, Some((9, Some("8.1. Explicit Lifetime Annotations".to_string()))));
+    }
+
+    #[test]
+    fn no_heading_means_no_title() {
+        assert_eq!(find_section(SOURCE, "no_heading"), Some((14, None)));
+        assert_eq!(find_section(SOURCE, "missing"), None);
+    }
+}
@@ -0,0 +1,464 @@
+// lesson_health.rs
+// Compiles every lesson file with its own toolchain and reports which ones build, with each
+// error and warning attributed to the function (lesson section) it is in.
+//
+//   Rust    rustc --emit=metadata (type and borrow checking, no code generation)
+//   C++     $CXX -std=c++17 -fsyntax-only -Wall -Wextra
+//   Python  compile() with all warnings on (syntax only; nothing is imported or run)
+//
+// Usage:
+//   rustc --edition 2021 lesson_health.rs -o target/lesson_health
+//   target/lesson_health            # a table for the lessons in this directory
+//   target/lesson_health --json     # the same, as JSON
+//   target/lesson_health DIR        # lessons in another directory
+// Tests (sections and table rows for a small fixture lesson):
+//   rustc --edition 2021 --test lesson_health.rs -o target/lesson_health_test && target/lesson_health_test
+//
+// Lessons are the files whose names start with "Day" (after trimming), ending in .rs, .cpp or
+// .py. Exits with 1 if any lesson fails to compile.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Language {
+    Rust,
+    Cpp,
+    Python,
+}
+
+impl Language {
+    fn of(file: &str) -> Option<Language> {
+        match Path::new(file).extension()?.to_str()? {
+            "rs" => Some(Language::Rust),
+            "cpp" => Some(Language::Cpp),
+            "py" => Some(Language::Python),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Cpp => "c++",
+            Language::Python => "python",
+        }
+    }
+}
+
+struct Diagnostic {
+    line: usize,
+    column: usize,
+    error: bool,
+    message: String, // e.g. "error[E0425]: cannot find function `x` in this scope"
+}
+
+struct Section {
+    name: String,
+    errors: usize,
+    warnings: usize,
+    first: Option<String>, // The first error, or the first warning if there are none.
+}
+
+struct Report {
+    file: String,
+    language: Language,
+    tool: Result<(), String>, // Err if the toolchain could not be run.
+    diagnostics: Vec<Diagnostic>,
+    sections: Vec<Section>,
+    notes: Vec<String>,
+}
+
+impl Report {
+    fn errors(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.error).count()
+    }
+    fn warnings(&self) -> usize {
+        self.diagnostics.iter().filter(|d| !d.error).count()
+    }
+    fn status(&self) -> &'static str {
+        match (&self.tool, self.errors()) {
+            (Err(_), _) => "SKIPPED",
+            (Ok(()), 0) => "builds",
+            _ => "FAILS",
+        }
+    }
+}
+
+// --- Running the toolchains ---
+
+const PYTHON_CHECK: &str = r#"
+import sys, warnings
+path = sys.argv[1]
+with warnings.catch_warnings(record=True) as caught:
+    warnings.simplefilter("always")
+    try:
+        with open(path, encoding="utf-8") as f:
+            compile(f.read(), path, "exec")
+    except SyntaxError as e:
+        print(f"{path}:{e.lineno}:{e.offset or 0}: error: {type(e).__name__}: {e.msg}")
+for w in caught:
+    print(f"{path}:{w.lineno}:0: warning: {w.category.__name__}: {w.message}")
+"#;
+
+// Runs the checker for one file (from inside `dir`, so paths stay short) and returns its
+// `file:line:col: level: message` lines, from stdout and stderr together.
+fn run_tool(language: Language, file: &str, dir: &Path) -> Result<String, String> {
+    let out_dir = env::temp_dir().join(format!("lesson_health_{}", process::id()));
+    fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+    let mut command = match language {
+        Language::Rust => {
+            let mut c = Command::new(env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()));
+            c.args(["--edition", "2021", "--error-format=short", "--emit=metadata", "--crate-name", "lesson", "--out-dir"])
+                .arg(&out_dir)
+                .arg(file);
+            c
+        }
+        Language::Cpp => {
+            let mut c = Command::new(env::var("CXX").unwrap_or_else(|_| "c++".to_string()));
+            c.args(["-std=c++17", "-fsyntax-only", "-Wall", "-Wextra", file]);
+            c
+        }
+        Language::Python => {
+            let mut c = Command::new("python3");
+            c.args(["-c", PYTHON_CHECK, file]);
+            c
+        }
+    };
+    let output = command.current_dir(dir).output().map_err(|e| format!("could not run {:?}: {}", command.get_program(), e));
+    fs::remove_dir_all(&out_dir).ok();
+    let output = output?;
+    Ok(format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)))
+}
+
+fn parse_diagnostics(output: &str, file: &str) -> Vec<Diagnostic> {
+    let prefix = format!("{}:", file);
+    let mut diagnostics = Vec::new();
+    for line in output.lines() {
+        let Some(rest) = line.strip_prefix(&prefix) else { continue };
+        let mut parts = rest.splitn(3, ':');
+        let (Some(l), Some(c), Some(message)) = (parts.next(), parts.next(), parts.next()) else { continue };
+        let (Ok(l), Ok(c)) = (l.trim().parse(), c.trim().parse()) else { continue };
+        let message = message.trim();
+        let error = message.starts_with("error") || message.starts_with("fatal error");
+        if error || message.starts_with("warning") {
+            diagnostics.push(Diagnostic { line: l, column: c, error, message: message.to_string() });
+        }
+    }
+    diagnostics
+}
+
+// --- Sections ---
+
+// The name of the function starting on this line, if it is a top-level function header.
+fn function_header(language: Language, line: &str) -> Option<String> {
+    let ident = |s: &str| -> String { s.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect() };
+    match language {
+        Language::Rust => {
+            let mut rest = line;
+            for prefix in ["pub ", "const ", "async ", "unsafe ", "extern \"C\" "] {
+                rest = rest.strip_prefix(prefix).unwrap_or(rest);
+            }
+            rest.strip_prefix("fn ").map(ident)
+        }
+        Language::Python => line.strip_prefix("def ").or_else(|| line.strip_prefix("class ")).map(ident),
+        Language::Cpp => {
+            // `int main() {`, `void Shape::draw() const {`: a type, then a name, then `(`.
+            let starts_like_code = line.starts_with(|c: char| c.is_alphabetic() || c == '_');
+            let keyword = ["struct ", "class ", "enum ", "using ", "typedef ", "namespace ", "template", "return "].iter().any(|k| line.starts_with(k));
+            if !starts_like_code || keyword || line.trim_end().ends_with(';') {
+                return None;
+            }
+            let before_paren = &line[..line.find('(')?];
+            let name = before_paren.trim_end().rsplit([' ', '*', '&']).next()?;
+            (before_paren.contains(' ') && !name.is_empty()).then(|| name.to_string())
+        }
+    }
+}
+
+// The top-level function each line is in ("(top level)" outside any), by scanning headers
+// at column 0 and the column-0 `}` (or, in Python, column-0 code) that ends them.
+fn section_of_lines(language: Language, source: &str) -> Vec<String> {
+    let mut current: Option<String> = None;
+    let mut sections = Vec::new();
+    for line in source.lines() {
+        if let Some(name) = function_header(language, line) {
+            current = Some(name);
+        }
+        sections.push(current.clone().unwrap_or_else(|| "(top level)".to_string()));
+        let ends = match language {
+            Language::Rust | Language::Cpp => line.starts_with('}'),
+            Language::Python => {
+                !line.is_empty() && !line.starts_with([' ', '\t', '#', ')', ']', '}']) && function_header(language, line).is_none() && !line.starts_with('@')
+            }
+        };
+        if ends {
+            if language == Language::Python {
+                *sections.last_mut().expect("just pushed") = "(top level)".to_string();
+            }
+            current = None;
+        }
+    }
+    sections
+}
+
+fn check(dir: &Path, file: &str) -> Report {
+    let language = Language::of(file).expect("only lesson files are checked");
+    let mut notes = Vec::new();
+    if file != file.trim() {
+        notes.push(format!("the file name {:?} has leading or trailing spaces, so it is easy to miss and awkward to type", file));
+    }
+    let source = fs::read_to_string(dir.join(file)).unwrap_or_default();
+    let tool = run_tool(language, file, dir);
+    let diagnostics = match &tool {
+        Ok(output) => parse_diagnostics(output, file),
+        Err(_) => Vec::new(),
+    };
+    let errors = diagnostics.iter().filter(|d| d.error);
+    if language == Language::Rust && errors.clone().count() > 0 && errors.clone().all(|d| !d.message.starts_with("error[")) {
+        notes.push("rustc stopped at a syntax error; errors found by later passes (names, types, borrows) will show once it is fixed".to_string());
+    }
+    let line_sections = section_of_lines(language, &source);
+    let mut sections: Vec<Section> = Vec::new();
+    for d in &diagnostics {
+        let name = line_sections.get(d.line.saturating_sub(1)).cloned().unwrap_or_else(|| "(top level)".to_string());
+        let index = match sections.iter().position(|s| s.name == name) {
+            Some(i) => i,
+            None => {
+                sections.push(Section { name, errors: 0, warnings: 0, first: None });
+                sections.len() - 1
+            }
+        };
+        let section = &mut sections[index];
+        let first_error = d.error && section.errors == 0;
+        if d.error {
+            section.errors += 1;
+        } else {
+            section.warnings += 1;
+        }
+        if section.first.is_none() || first_error {
+            section.first = Some(format!("{}:{} {}", d.line, d.column, d.message));
+        }
+    }
+    Report { file: file.to_string(), language, tool: tool.map(|_| ()), diagnostics, sections, notes }
+}
+
+// --- Output ---
+
+fn versions() -> Vec<String> {
+    let version = |program: &str| {
+        Command::new(program)
+            .arg("--version")
+            .output()
+            .ok()
+            .and_then(|o| String::from_utf8_lossy(&o.stdout).lines().next().map(str::to_string).filter(|l| !l.is_empty()))
+            .or_else(|| Command::new(program).arg("--version").output().ok().map(|o| String::from_utf8_lossy(&o.stderr).trim().to_string()))
+            .unwrap_or_else(|| format!("{} not found", program))
+    };
+    let cxx = env::var("CXX").unwrap_or_else(|_| "c++".to_string());
+    vec![version(&env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string())), version(&cxx), version("python3")]
+}
+
+// One file's lines of the table: its status, then a row per section, then the notes.
+fn table_rows(r: &Report) -> Vec<String> {
+    let mut rows = Vec::new();
+    match &r.tool {
+        Err(e) => rows.push(format!("{:<18} {:<7} SKIPPED  {}", format!("{:?}", r.file), r.language.name(), e)),
+        Ok(()) => rows.push(format!("{:<18} {:<7} {:<8} {} error(s), {} warning(s)", format!("{:?}", r.file), r.language.name(), r.status(), r.errors(), r.warnings())),
+    }
+    if !r.sections.is_empty() {
+        let width = r.sections.iter().map(|s| s.name.len()).max().unwrap_or(0).max("section".len());
+        rows.push(format!("    {:<width$} {:>6} {:>8}  first problem", "section", "errors", "warnings", width = width));
+        for s in &r.sections {
+            rows.push(format!("    {:<width$} {:>6} {:>8}  {}", s.name, s.errors, s.warnings, s.first.as_deref().unwrap_or(""), width = width));
+        }
+    }
+    for note in &r.notes {
+        rows.push(format!("    note: {}", note));
+    }
+    rows
+}
+
+fn print_table(reports: &[Report]) {
+    println!("Lesson health ({})", versions().join("; "));
+    for r in reports {
+        println!();
+        for row in table_rows(r) {
+            println!("{}", row);
+        }
+    }
+    let failing = reports.iter().filter(|r| r.status() == "FAILS").count();
+    println!();
+    println!("{} of {} lesson file(s) build", reports.iter().filter(|r| r.status() == "builds").count(), reports.len());
+    if failing > 0 {
+        println!("{} fail to compile", failing);
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn print_json(reports: &[Report]) {
+    let files: Vec<String> = reports
+        .iter()
+        .map(|r| {
+            let sections: Vec<String> = r
+                .sections
+                .iter()
+                .map(|s| format!("{{\"name\": {}, \"errors\": {}, \"warnings\": {}}}", json_string(&s.name), s.errors, s.warnings))
+                .collect();
+            let line_sections = section_of_lines(r.language, &fs::read_to_string(&r.file).unwrap_or_default());
+            let diagnostics: Vec<String> = r
+                .diagnostics
+                .iter()
+                .map(|d| {
+                    let section = line_sections.get(d.line.saturating_sub(1)).map(String::as_str).unwrap_or("(top level)");
+                    format!(
+                        "{{\"line\": {}, \"column\": {}, \"level\": \"{}\", \"section\": {}, \"message\": {}}}",
+                        d.line,
+                        d.column,
+                        if d.error { "error" } else { "warning" },
+                        json_string(section),
+                        json_string(&d.message)
+                    )
+                })
+                .collect();
+            let notes: Vec<String> = r.notes.iter().map(|n| json_string(n)).collect();
+            format!(
+                "    {{\"file\": {}, \"language\": \"{}\", \"status\": \"{}\", \"errors\": {}, \"warnings\": {},{}\n     \"sections\": [{}],\n     \"diagnostics\": [\n       {}\n     ],\n     \"notes\": [{}]}}",
+                json_string(&r.file),
+                r.language.name(),
+                r.status().to_lowercase(),
+                r.errors(),
+                r.warnings(),
+                match &r.tool {
+                    Err(e) => format!(" \"skipped\": {},", json_string(e)),
+                    Ok(()) => String::new(),
+                },
+                sections.join(", "),
+                diagnostics.join(",\n       "),
+                notes.join(", ")
+            )
+        })
+        .collect();
+    let tools: Vec<String> = versions().iter().map(|v| json_string(v)).collect();
+    println!("{{\n  \"tools\": [{}],\n  \"files\": [\n{}\n  ]\n}}", tools.join(", "), files.join(",\n"));
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let json = args.iter().any(|a| a == "--json");
+    let dir = args.iter().find(|a| !a.starts_with("--")).map(String::as_str).unwrap_or(".");
+    let dir = Path::new(dir);
+    let mut files: Vec<String> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .filter_map(|e| e.file_name().into_string().ok())
+            .filter(|name| name.trim_start().starts_with("Day") && Language::of(name).is_some())
+            .collect(),
+        Err(e) => {
+            eprintln!("{}: {}", dir.display(), e);
+            process::exit(2);
+        }
+    };
+    files.sort_by(|a, b| a.trim().cmp(b.trim()));
+    if env::set_current_dir(dir).is_err() {
+        eprintln!("cannot enter {}", dir.display());
+        process::exit(2);
+    }
+    let here = Path::new(".");
+    let reports: Vec<Report> = files.iter().map(|f| check(here, f)).collect();
+    if json {
+        print_json(&reports);
+    } else {
+        print_table(&reports);
+    }
+    process::exit(if reports.iter().any(|r| r.status() == "FAILS") { 1 } else { 0 });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = "\
+// A fixture lesson: a clean section, one with a warning and one with an error.
+fn clean() {
+    println!(\"fine\");
+}
+
+fn warns() {
+    let unused = 1;
+}
+
+fn breaks() {
+    let x: i32 = \"text\";
+}
+
+fn main() {
+    clean();
+    warns();
+    breaks();
+}
+";
+
+    fn fixture_report(test: &str) -> Report {
+        let work = env::temp_dir().join(format!("lesson_health_test_{}_{}", process::id(), test));
+        fs::create_dir_all(&work).unwrap();
+        fs::write(work.join("Day999.rs"), FIXTURE).unwrap();
+        let report = check(&work, "Day999.rs");
+        fs::remove_dir_all(&work).ok();
+        report
+    }
+
+    #[test]
+    fn lines_belong_to_the_function_around_them() {
+        let sections = section_of_lines(Language::Rust, FIXTURE);
+        let at = |line: usize| sections[line - 1].as_str();
+        assert_eq!((at(1), at(2), at(4), at(5)), ("(top level)", "clean", "clean", "(top level)"));
+        assert_eq!((at(7), at(11), at(15)), ("warns", "breaks", "main"));
+
+        let python = "import sys\n\ndef greet():\n    print('hi')\n\nprint('done')\n";
+        assert_eq!(section_of_lines(Language::Python, python), ["(top level)", "(top level)", "greet", "greet", "greet", "(top level)"]);
+    }
+
+    #[test]
+    fn short_format_lines_become_diagnostics() {
+        let output = "Day9.rs:3:9: warning: unused variable: `x`\nnote: elsewhere\nDay9.rs:7:18: error[E0308]: mismatched types\nOther.rs:1:1: error: not ours\n";
+        let found: Vec<(usize, usize, bool)> = parse_diagnostics(output, "Day9.rs").iter().map(|d| (d.line, d.column, d.error)).collect();
+        assert_eq!(found, [(3, 9, false), (7, 18, true)]);
+    }
+
+    #[test]
+    fn diagnostics_are_counted_per_section() {
+        let report = fixture_report("sections");
+        assert!(report.tool.is_ok(), "{:?}", report.tool);
+        let counts: Vec<(&str, usize, usize)> = report.sections.iter().map(|s| (s.name.as_str(), s.errors, s.warnings)).collect();
+        assert_eq!(counts, [("breaks", 1, 0), ("warns", 0, 1)]); // `clean` and `main` have nothing to report.
+        assert!(report.sections[0].first.as_deref().unwrap().starts_with("11:18 error[E0308]: mismatched types"));
+        assert_eq!((report.errors(), report.warnings(), report.status()), (1, 1, "FAILS"));
+    }
+
+    #[test]
+    fn table_rows_for_the_fixture() {
+        let rows = table_rows(&fixture_report("table"));
+        assert_eq!(rows.len(), 4, "{:#?}", rows);
+        assert_eq!(rows[0], "\"Day999.rs\"        rust    FAILS    1 error(s), 1 warning(s)");
+        assert_eq!(rows[1], "    section errors warnings  first problem");
+        assert!(rows[2].starts_with("    breaks       1        0  11:18 error[E0308]: mismatched types"), "{}", rows[2]);
+        assert!(rows[3].starts_with("    warns        0        1  7:9 warning: unused variable: `unused`"), "{}", rows[3]);
+    }
+}
@@ -0,0 +1,628 @@
+// lesson_step.rs
+// Steps through a lesson one sub-section at a time instead of printing everything at once.
+// After each section (`--- 5.1. ...`, `--- 5.2. ...`) it stops and shows the source that
+// ran next to the output it printed, then waits for a command:
+//
+//   n, next, or Enter   the next section          b, back      the previous one
+//   r, repeat           show this one again       q, quit      stop
+//   5.3                 jump to a section         l, list      list the sections
+//
+// Usage:
+//   rustc --edition 2021 lesson_step.rs -o target/lesson_step
+//   target/lesson_step                                   # Day001.003.rs
+//   target/lesson_step Day001.003.rs -- --virtual-clock  # arguments after -- go to the lesson
+//   printf 'n\nn\nb\nq\n' | target/lesson_step           # commands can come from a script
+//
+// Tests (a fixture and Day001.003.rs):
+//   rustc --edition 2021 --test lesson_step.rs -o target/lesson_step_test && target/lesson_step_test
+//
+// The lesson is built and run once, with a marker line printed at every `// --- N.M.`
+// section comment inside a function, and its output is cut at those markers and at the
+// `--- N.M. ... ---` headings it prints itself. Stepping back or repeating only redraws
+// what was captured, so nothing runs twice. A section is the source from its comment (or
+// heading) to the next one; a heading with no output of its own, like a level's, is shown
+// together with the section after it.
+//
+// When stdin is not a terminal, each command is echoed after the prompt so a transcript
+// reads like an interactive session. Set COLUMNS to change the width (default 160).
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::process::{self, Command, Stdio};
+
+const MARKER: &str = "\u{1}lesson_step ";
+const MARKER_IN_SOURCE: &str = "\\u{1}lesson_step ";
+
+// --- Sections in the source ---
+
+// `// --- 5.1. Vectors ---`, `println!("\n--- LEVEL 5: ... ---")` and the output lines they
+// print all start with a heading: "5.1. Vectors ---" -> ("5.1", "5.1. Vectors").
+fn heading(text: &str) -> Option<(String, String)> {
+    let rest = text.trim_start().strip_prefix("--- ")?;
+    let title = rest.split(" ---").next().unwrap_or(rest).trim().to_string();
+    let id = if let Some(level) = rest.strip_prefix("LEVEL ") {
+        let number: String = level.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if number.is_empty() {
+            return None;
+        }
+        format!("LEVEL {}", number)
+    } else {
+        let number: String = rest.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+        if !number.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        number.trim_end_matches('.').to_string()
+    };
+    Some((id, title))
+}
+
+// The string a `println!("...")` line prints, without leading `\n`s.
+fn printed(code: &str) -> Option<&str> {
+    let start = code.find("println!(\"")? + "println!(\"".len();
+    let text = &code[start..];
+    let mut text = &text[..text.find('"').unwrap_or(text.len())];
+    while let Some(rest) = text.strip_prefix("\\n") {
+        text = rest;
+    }
+    Some(text)
+}
+
+enum Boundary {
+    Comment(String), // `// --- N.M. ...`
+    Heading(String), // a `println!` of `--- N.M. ... ---`
+    Banner,          // `// ~~~~`, between levels
+}
+
+fn boundary(line: &str) -> Option<Boundary> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("// ~~~") || trimmed.starts_with("fn main(") {
+        return Some(Boundary::Banner);
+    }
+    if let Some(comment) = trimmed.strip_prefix("//") {
+        return heading(comment).map(|(id, _)| Boundary::Comment(id));
+    }
+    heading(printed(trimmed)?).map(|(id, _)| Boundary::Heading(id))
+}
+
+// (id, first line, last line) for every piece of a section, in file order. A piece runs from
+// a boundary to the line before the next boundary with another id, without trailing blank
+// lines. A section can have several: `// --- 3.1.` above the structs, and the code after
+// `println!("\n--- 3.1. ...")` in the level's function.
+fn section_ranges(lines: &[&str]) -> Vec<(String, usize, usize)> {
+    let marks: Vec<(usize, Option<String>)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| {
+            boundary(l).map(|b| match b {
+                Boundary::Comment(id) | Boundary::Heading(id) => (i + 1, Some(id)),
+                Boundary::Banner => (i + 1, None),
+            })
+        })
+        .collect();
+    let mut ranges: Vec<(String, usize, usize)> = Vec::new();
+    for (k, (start, id)) in marks.iter().enumerate() {
+        let Some(id) = id else { continue };
+        if k > 0 && marks[k - 1].1.as_ref() == Some(id) {
+            continue; // The heading printed right after its own comment.
+        }
+        let next = marks[k + 1..].iter().find(|(_, other)| other.as_ref() != Some(id)).map_or(lines.len() + 1, |(l, _)| *l);
+        let mut end = next - 1;
+        while end > *start && lines[end - 1].trim().is_empty() {
+            end -= 1;
+        }
+        ranges.push((id.clone(), *start, end));
+    }
+    ranges
+}
+
+// The lesson with a marker `println!` before every section comment that sits where a
+// statement can go: inside a function, after a line ending in `{`, `;` or `}`.
+fn instrument(source: &str) -> String {
+    let mut out = String::new();
+    let mut blocks: Vec<bool> = Vec::new(); // Open braces; true for a function body.
+    let mut last_code = String::new();
+    for line in source.lines() {
+        if let Some(Boundary::Comment(id)) = boundary(line) {
+            if blocks.contains(&true) && (last_code.ends_with('{') || last_code.ends_with(';') || last_code.ends_with('}')) {
+                out.push_str(&format!("println!(\"{}{}\");\n", MARKER_IN_SOURCE, id));
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+        let code = strip_strings(split_comment(line));
+        let mut function = code.trim_start().starts_with("fn ") || code.contains(" fn ");
+        for c in code.chars() {
+            match c {
+                '{' => {
+                    blocks.push(function);
+                    function = false;
+                }
+                '}' => {
+                    blocks.pop();
+                }
+                _ => {}
+            }
+        }
+        let code = code.trim();
+        if !code.is_empty() && !code.starts_with("/*") && !code.starts_with("* ") && !code.ends_with("*/") {
+            last_code = code.to_string();
+        }
+    }
+    out
+}
+
+// `code // comment` -> code, ignoring `//` inside string literals.
+fn split_comment(line: &str) -> &str {
+    let bytes = line.as_bytes();
+    let mut in_string = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'/' if !in_string && bytes.get(i + 1) == Some(&b'/') => return line[..i].trim_end(),
+            _ => {}
+        }
+        i += 1;
+    }
+    line.trim_end()
+}
+
+// The code with every string literal emptied, so braces inside them don't count.
+fn strip_strings(code: &str) -> String {
+    let mut out = String::new();
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if c == '"' {
+            while let Some(d) = chars.next() {
+                if d == '\\' {
+                    chars.next();
+                } else if d == '"' {
+                    out.push('"');
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+// --- Steps ---
+
+#[derive(Debug)]
+struct Step {
+    ids: Vec<String>,
+    title: String,
+    output: Vec<String>,
+    source: Vec<(usize, usize)>, // Line ranges, in file order.
+}
+
+fn start_section(steps: &mut Vec<Step>, id: String, title: Option<String>) {
+    let current = steps.last_mut().expect("there is always a step");
+    let title = title.unwrap_or_else(|| id.clone());
+    let has_output = current.output.iter().any(|l| !l.trim().is_empty() && heading(l).is_none());
+    if current.ids.last() == Some(&id) {
+        // The heading printed after the section's own marker.
+        current.title = match current.title.rsplit_once(" + ") {
+            Some((before, _)) => format!("{} + {}", before, title),
+            None => title,
+        };
+    } else if has_output || (current.ids.is_empty() && !current.output.is_empty()) {
+        steps.push(Step { ids: vec![id], title, output: Vec::new(), source: Vec::new() });
+    } else {
+        // Nothing printed yet but a heading: show it with this section.
+        let only_levels = current.ids.iter().all(|i| i.starts_with("LEVEL"));
+        current.title = if only_levels { title } else { format!("{} + {}", current.title, title) };
+        current.ids.push(id);
+    }
+}
+
+// Cuts the captured output at the markers and headings, and gives every piece its source.
+fn steps_of(stdout: &str, lines: &[&str]) -> Vec<Step> {
+    let ranges = section_ranges(lines);
+    let mut steps: Vec<Step> = vec![Step { ids: Vec::new(), title: "Start".to_string(), output: Vec::new(), source: Vec::new() }];
+    for line in stdout.lines() {
+        let (before, marker) = match line.find(MARKER) {
+            Some(i) => (&line[..i], Some(line[i + MARKER.len()..].trim().to_string())),
+            None => (line, None),
+        };
+        if let Some(id) = marker {
+            if !before.is_empty() {
+                steps.last_mut().expect("there is always a step").output.push(before.to_string());
+            }
+            let title = ranges.iter().find(|(r, _, _)| *r == id).and_then(|(_, start, _)| heading(lines[start - 1].trim_start().trim_start_matches("//")).map(|(_, t)| t));
+            start_section(&mut steps, id, title);
+            continue;
+        }
+        if let Some((id, title)) = heading(line).filter(|_| line.trim_end().ends_with("---")) {
+            start_section(&mut steps, id, Some(title));
+        }
+        let step = steps.last_mut().expect("there is always a step");
+        if !(step.output.is_empty() && line.trim().is_empty()) {
+            step.output.push(line.to_string());
+        }
+    }
+    if steps[0].output.is_empty() && steps.len() > 1 {
+        steps.remove(0);
+    }
+    // Trailing blank lines belong to the gap before the next heading.
+    for step in &mut steps {
+        while step.output.last().is_some_and(|l| l.trim().is_empty()) {
+            step.output.pop();
+        }
+    }
+    // A section that never showed up (its code printed no heading and got no marker) is
+    // shown with the section before it.
+    for (id, _, _) in &ranges {
+        if steps.iter().any(|s| s.ids.contains(id)) {
+            continue;
+        }
+        let before = ranges.iter().take_while(|(r, _, _)| r != id).filter_map(|(r, _, _)| steps.iter().position(|s| s.ids.contains(r))).last();
+        if let Some(k) = before {
+            steps[k].ids.push(id.clone());
+        }
+    }
+    for step in &mut steps {
+        let mut source: Vec<(usize, usize)> = ranges.iter().filter(|(r, _, _)| step.ids.contains(r)).map(|&(_, a, b)| (a, b)).collect();
+        if step.ids.is_empty() {
+            // Before the first section: the lines that print this output verbatim.
+            for (i, l) in lines.iter().enumerate() {
+                if printed(l).is_some_and(|p| !p.is_empty() && step.output.iter().any(|o| o == p)) {
+                    source.push((i + 1, i + 1));
+                }
+            }
+        }
+        source.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::new();
+        for (a, b) in source {
+            match merged.last_mut() {
+                Some(last) if (last.1 + 1..a).all(|n| lines[n - 1].trim().is_empty()) => last.1 = last.1.max(b),
+                _ => merged.push((a, b)),
+            }
+        }
+        step.source = merged;
+    }
+    steps
+}
+
+// --- Showing a step ---
+
+fn fit(text: &str, width: usize) -> String {
+    let count = text.chars().count();
+    if count <= width {
+        format!("{}{}", text, " ".repeat(width - count))
+    } else {
+        format!("{}~", text.chars().take(width - 1).collect::<String>())
+    }
+}
+
+fn render_step(steps: &[Step], index: usize, lines: &[&str], file: &str, columns: usize) -> String {
+    let step = &steps[index];
+    let mut source: Vec<String> = Vec::new();
+    for (k, &(a, b)) in step.source.iter().enumerate() {
+        if k > 0 {
+            source.push("      ...".to_string());
+        }
+        source.extend((a..=b).map(|n| format!("{:>5} {}", n, lines[n - 1].replace('\t', "    "))));
+    }
+    let output: Vec<String> = step.output.iter().map(|l| l.replace('\t', "    ")).collect();
+    let widest = source.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let left = widest.clamp(20, (columns.saturating_sub(3) * 3 / 5).max(20));
+    let right = columns.saturating_sub(left + 3).max(20);
+    let place = match (step.source.first(), step.source.last()) {
+        (Some(first), Some(last)) => format!("{}:{}-{}", file, first.0, last.1),
+        _ => file.to_string(),
+    };
+    let mut out = format!("\n[{}/{}] {}  ({})\n", index + 1, steps.len(), step.title, place);
+    out.push_str(&format!("{} | output\n", fit("source", left)));
+    out.push_str(&format!("{}-+-{}\n", "-".repeat(left), "-".repeat(right)));
+    for row in 0..source.len().max(output.len()) {
+        let l = source.get(row).map_or("", String::as_str);
+        let r = output.get(row).map_or("", String::as_str);
+        out.push_str(format!("{} | {}", fit(l, left), fit(r, right)).trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    Next,
+    Back,
+    Repeat,
+    Quit,
+    List,
+    Goto(usize),
+    Unknown(String),
+}
+
+fn parse_command(text: &str, steps: &[Step]) -> Action {
+    match text.trim() {
+        "" | "n" | "next" => Action::Next,
+        "b" | "back" => Action::Back,
+        "r" | "repeat" => Action::Repeat,
+        "q" | "quit" => Action::Quit,
+        "l" | "list" => Action::List,
+        other => {
+            let wanted = other.trim_end_matches('.');
+            let found = steps.iter().position(|s| s.ids.iter().any(|id| id == wanted || id.eq_ignore_ascii_case(&format!("LEVEL {}", wanted))));
+            found.map_or_else(|| Action::Unknown(other.to_string()), Action::Goto)
+        }
+    }
+}
+
+const PROMPT: &str = "-- [n]ext, [b]ack, [r]epeat, [q]uit, [l]ist, or a section number > ";
+
+// The interactive loop. Returns the steps in the order they were shown.
+fn session(steps: &[Step], lines: &[&str], file: &str, input: impl BufRead, out: &mut impl Write, echo: bool, columns: usize) -> io::Result<Vec<usize>> {
+    let mut shown = vec![0];
+    let mut current = 0;
+    write!(out, "{}", render_step(steps, current, lines, file, columns))?;
+    let mut commands = input.lines();
+    loop {
+        write!(out, "{}", PROMPT)?;
+        out.flush()?;
+        let Some(command) = commands.next().transpose()? else {
+            writeln!(out)?;
+            return Ok(shown);
+        };
+        if echo {
+            writeln!(out, "{}", command.trim())?;
+        }
+        let target = match parse_command(&command, steps) {
+            Action::Next if current + 1 == steps.len() => {
+                writeln!(out, "End of the lesson.")?;
+                return Ok(shown);
+            }
+            Action::Next => current + 1,
+            Action::Back if current == 0 => {
+                writeln!(out, "(this is the first section)")?;
+                continue;
+            }
+            Action::Back => current - 1,
+            Action::Repeat => current,
+            Action::Goto(k) => k,
+            Action::Quit => return Ok(shown),
+            Action::List => {
+                for (k, step) in steps.iter().enumerate() {
+                    writeln!(out, "{} {:>3}. {}", if k == current { '>' } else { ' ' }, k + 1, step.title)?;
+                }
+                continue;
+            }
+            Action::Unknown(text) => {
+                writeln!(out, "unknown command {:?}", text)?;
+                continue;
+            }
+        };
+        current = target;
+        shown.push(current);
+        write!(out, "{}", render_step(steps, current, lines, file, columns))?;
+    }
+}
+
+// --- Building and running the lesson ---
+
+struct Run {
+    stdout: String,
+    stderr: String,
+    success: bool,
+}
+
+fn build_and_run(source: &str, dir: &Path, exe: &Path, args: &[String]) -> Result<Run, String> {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let mut child = Command::new(&rustc)
+        .args(["--edition", "2021", "--error-format=short", "-A", "warnings", "--crate-name", "lesson_step_lesson", "-o"])
+        .arg(exe)
+        .arg("-") // The source comes on stdin; `mod` files are found from `dir`.
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run {}: {}", rustc, e))?;
+    child.stdin.take().expect("stdin is piped").write_all(source.as_bytes()).map_err(|e| e.to_string())?;
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let errors: Vec<&str> = stderr.lines().filter(|l| l.contains(": error")).collect();
+        return Err(format!("the lesson did not build:\n  {}", errors.join("\n  ").replace("<anon>:", "line ")));
+    }
+    // The lesson gets no stdin: the step commands are ours.
+    let output = Command::new(exe).args(args).current_dir(dir).stdin(Stdio::null()).output().map_err(|e| format!("could not run {}: {}", exe.display(), e))?;
+    Ok(Run {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        success: output.status.success(),
+    })
+}
+
+fn load(file: &str, args: &[String], work: &Path) -> Result<(String, Run), String> {
+    let lesson = fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+    let dir = Path::new(file).parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let run = build_and_run(&instrument(&lesson), dir, &work.join("lesson"), args)?;
+    Ok((lesson, run))
+}
+
+fn main() {
+    let all: Vec<String> = env::args().skip(1).collect();
+    let split = all.iter().position(|a| a == "--").unwrap_or(all.len());
+    let (args, lesson_args) = (&all[..split], all.get(split + 1..).unwrap_or(&[]));
+    let work = env::temp_dir().join(format!("lesson_step_{}", process::id()));
+    if let Err(e) = fs::create_dir_all(&work) {
+        eprintln!("{}: {}", work.display(), e);
+        process::exit(2);
+    }
+    let status = match args {
+        [] | [_] => {
+            let file = args.first().map_or("Day001.003.rs", String::as_str);
+            match load(file, lesson_args, &work) {
+                Ok((lesson, run)) => {
+                    let lines: Vec<&str> = lesson.lines().collect();
+                    let steps = steps_of(&run.stdout, &lines);
+                    let columns = env::var("COLUMNS").ok().and_then(|c| c.parse().ok()).unwrap_or(160);
+                    let stdin = io::stdin();
+                    let echo = !stdin.is_terminal();
+                    match session(&steps, &lines, file, stdin.lock(), &mut io::stdout().lock(), echo, columns) {
+                        Ok(_) if !run.success || !run.stderr.is_empty() => {
+                            println!("\nThe lesson {} and wrote to stderr:", if run.success { "ran" } else { "failed" });
+                            for l in run.stderr.lines() {
+                                println!("! {}", l);
+                            }
+                            if run.success { 0 } else { 1 }
+                        }
+                        Ok(_) => 0,
+                        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => 0,
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            2
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    2
+                }
+            }
+        }
+        _ => {
+            eprintln!("usage: lesson_step [FILE] [-- LESSON ARGS...]");
+            2
+        }
+    };
+    fs::remove_dir_all(&work).ok();
+    process::exit(status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"// ~~~~~~~~
+// LEVEL 1: FIXTURE
+// ~~~~~~~~
+// --- 1.1. Printing ---
+struct Unit;
+
+fn level_1() {
+    println!("\n--- LEVEL 1: FIXTURE ---");
+    println!("\n--- 1.1. Printing ---");
+    let _unit = Unit;
+    println!("one");
+    // --- 1.2. Silent section ---
+    let x = 2;
+    // --- 1.3. Loop ---
+    for i in 0..x {
+        println!("loop {}", i);
+    }
+    println!("\n--- 1.4. A printed heading ---");
+    println!("four");
+}
+
+fn main() {
+    println!("intro");
+    level_1();
+}
+"#;
+
+    // A scratch directory per test, removed on drop.
+    struct Work(std::path::PathBuf);
+
+    impl Work {
+        fn new(test: &str) -> Work {
+            let dir = env::temp_dir().join(format!("lesson_step_test_{}_{}", process::id(), test));
+            fs::create_dir_all(&dir).unwrap();
+            Work(dir)
+        }
+    }
+
+    impl Drop for Work {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn fixture_steps(test: &str) -> Vec<Step> {
+        let work = Work::new(test);
+        let run = build_and_run(&instrument(FIXTURE), &work.0, &work.0.join("lesson"), &[]).unwrap();
+        steps_of(&run.stdout, &FIXTURE.lines().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn headings_give_ids_and_titles() {
+        assert_eq!(heading("--- 5.1. Vectors `Vec<T>` ---"), Some(("5.1".to_string(), "5.1. Vectors `Vec<T>`".to_string())));
+        assert_eq!(heading("--- LEVEL 12: UNSAFE RUST ---").map(|h| h.0), Some("LEVEL 12".to_string()));
+    }
+
+    #[test]
+    fn markers_go_before_section_comments_inside_functions_only() {
+        assert_eq!(instrument(FIXTURE).matches(MARKER_IN_SOURCE).count(), 2);
+    }
+
+    #[test]
+    fn fixture_output_is_cut_into_sections() {
+        let steps = fixture_steps("sections");
+        let ids: Vec<Vec<&str>> = steps.iter().map(|s| s.ids.iter().map(String::as_str).collect()).collect();
+        assert_eq!(ids, [vec![], vec!["LEVEL 1", "1.1"], vec!["1.2", "1.3"], vec!["1.4"]]);
+        // The level heading is shown with its first section.
+        assert_eq!(steps[1].output, ["--- LEVEL 1: FIXTURE ---", "", "--- 1.1. Printing ---", "one"]);
+        // A section's top-level comment and its code are both its source.
+        assert_eq!(steps[1].source, [(4, 11)]);
+        // A section that prints nothing is shown with the next one.
+        assert_eq!(steps[2].source, [(12, 17)]);
+        assert_eq!(steps[2].title, "1.2. Silent section + 1.3. Loop");
+        // The code before the first section is found by what it prints.
+        assert_eq!(steps[0].source, [(23, 23)]);
+        // The last section ends before main.
+        assert_eq!(steps[3].source, [(18, 20)]);
+    }
+
+    #[test]
+    fn session_commands() {
+        let steps = fixture_steps("session");
+        let lines: Vec<&str> = FIXTURE.lines().collect();
+        let script = "n\nr\nb\nb\nzzz\n1.3\nl\nn\nn\n";
+        let mut transcript = Vec::new();
+        let shown = session(&steps, &lines, "fixture.rs", script.as_bytes(), &mut transcript, true, 100).unwrap();
+        let transcript = String::from_utf8_lossy(&transcript);
+        assert_eq!(shown, [0, 1, 1, 0, 2, 3]); // next, repeat, back, back, goto 1.3, next
+        assert!(transcript.contains("(this is the first section)"));
+        assert!(transcript.contains("unknown command \"zzz\""));
+        assert!(transcript.trim_end().ends_with("End of the lesson."));
+        // Source and output sit side by side.
+        assert!(transcript.lines().any(|l| l.starts_with("   12     // --- 1.2.") && l.ends_with("| loop 0")), "{}", transcript);
+
+        let shown = session(&steps, &lines, "fixture.rs", "q\nn\n".as_bytes(), &mut Vec::new(), false, 100).unwrap();
+        assert_eq!(shown, [0]); // Quit stops at once.
+    }
+
+    // The real lesson, with the virtual clock so the thread demos are repeatable.
+    #[test]
+    fn day001_003_steps() {
+        let work = Work::new("day001_003");
+        let file = Path::new(file!()).with_file_name("Day001.003.rs");
+        let (lesson, run) = load(file.to_str().unwrap(), &["--virtual-clock".to_string()], &work.0).unwrap();
+        assert!(run.success, "{}", run.stderr);
+        let lines: Vec<&str> = lesson.lines().collect();
+        let steps = steps_of(&run.stdout, &lines);
+
+        let missing: Vec<String> = section_ranges(&lines)
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .filter(|id| !steps.iter().any(|s| s.ids.contains(id)))
+            .collect();
+        assert!(missing.is_empty(), "sections in no step: {:?}", missing);
+        let order: Vec<usize> = steps.iter().filter(|s| !s.ids.is_empty()).filter_map(|s| s.source.first().map(|r| r.0)).collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]), "the steps follow the file: {:?}", order);
+        let level_0 = steps.iter().filter(|s| s.ids.iter().any(|id| id.starts_with("0."))).count();
+        assert!(level_0 >= 5, "level 0 is split at its section comments ({} steps)", level_0);
+
+        // No output is lost except blank lines between sections.
+        let plain: Vec<&str> = run.stdout.lines().filter(|l| !l.contains(MARKER)).collect();
+        let shown: usize = steps.iter().map(|s| s.output.len()).sum();
+        let blank = plain.iter().filter(|l| l.trim().is_empty()).count();
+        assert!(shown + blank >= plain.len() && shown <= plain.len());
+    }
+}
@@ -0,0 +1,913 @@
+// lifetime_timeline.rs
+// Prints a function's source with a gutter on the left: one column per binding, showing
+// where its lexical scope starts and ends and, for references, where the borrow is live.
+// It makes the prose in `variable_scopes`, `lifetimes_explained` and level 8 visible,
+// in particular why `longest_slice` with a shorter-lived argument is rejected.
+//
+// Usage (Cargo.toml is at the top of the repository; run these from this folder):
+//   cargo build --release
+//   ../target/release/lifetime_timeline Day001.002.rs lifetimes_explained
+//   ../target/release/lifetime_timeline Day001.002.rs variable_scopes
+//   ../target/release/lifetime_timeline Day001.003.rs level_8_advanced_lifetimes
+// Tests (fixtures, and the lessons' own examples):
+//   cargo test --bin lifetime_timeline
+//
+// Gutter, one column per binding (a column is reused once its binding is gone):
+//   o  declared here             :  declared, no value yet (`let result;`)
+//   |  in scope                  x  end of its block: dropped, the name is gone
+//   &  a borrow is created       #  the borrow is live (up to the reference's last use)
+//   !  used here, but something it borrows from is already dropped
+//
+// A reference borrows from the bindings it was made from: `&s`, `s.as_str()`, `&s[0..5]`,
+// a copy of another reference, a struct literal of a struct with a lifetime parameter, or a
+// call to a function in the same file that returns a reference. For the call, the
+// signature decides: with `fn longest_slice<'a>(x: &'a str, y: &'a str) -> &'a str` the
+// result borrows from both arguments, so it must not be used after either is dropped
+// (E0597). Elided signatures follow the elision rules (one reference parameter, or `&self`).
+// Borrows end at their last use, as with non-lexical lifetimes.
+//
+// Commented-out examples marked as errors, like
+//   // println!("The longest string outside inner scope: {}", result); // COMPILE-TIME ERROR!
+// are put back one at a time and shown as "what if" timelines with the error they cause.
+//
+// The file is parsed with `syn` and the function body walked in source order: blocks, `for`
+// bodies, match arms and closures open scopes, and macro arguments (with `{name}` in format
+// strings) count as uses. The examples live in comments, so those are still found by line.
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use std::env;
+use std::fs;
+use std::process;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Expr, FnArg, Lit, Pat, ReturnType, Token, Type};
+
+// --- Reading the lesson ---
+
+fn line_of(span: Span) -> usize {
+    span.start().line
+}
+
+// The source a span covers, joined onto one line: a return type as written.
+fn text_of(lines: &[&str], span: Span) -> String {
+    let (start, end) = (span.start(), span.end());
+    (start.line..=end.line)
+        .map(|no| {
+            let chars: Vec<char> = lines[no - 1].chars().collect();
+            let to = if no == end.line { end.column.min(chars.len()) } else { chars.len() };
+            let from = if no == start.line { start.column.min(to) } else { 0 };
+            chars[from..to].iter().collect::<String>().trim().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// `code // comment` -> (code, comment), ignoring `//` inside string literals.
+fn split_comment(line: &str) -> (&str, &str) {
+    let bytes = line.as_bytes();
+    let mut in_string = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'/' if !in_string && bytes.get(i + 1) == Some(&b'/') => {
+                return (line[..i].trim_end(), line[i + 2..].trim());
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    (line.trim_end(), "")
+}
+
+// The names a pattern binds, with their lines: `(mut a, Some(b))` -> [a, b]. Capitalised
+// names are unit variants and constants (`None`), not bindings.
+fn pattern_names(pat: &Pat) -> Vec<(String, usize)> {
+    struct Names(Vec<(String, usize)>);
+    impl<'ast> Visit<'ast> for Names {
+        fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+            let name = pat.ident.to_string();
+            if !name.starts_with(|c: char| c.is_uppercase()) {
+                self.0.push((name, line_of(pat.ident.span())));
+            }
+            visit::visit_pat_ident(self, pat);
+        }
+    }
+    let mut names = Names(Vec::new());
+    names.visit_pat(pat);
+    names.0
+}
+
+// `{name}` and `{name:>5}` in a format string: implicit captures, which are uses too.
+fn format_names(format: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = format;
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open + 1..];
+        if let Some(escaped) = rest.strip_prefix('{') {
+            rest = escaped;
+            continue;
+        }
+        let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        let name = &rest[..end];
+        let closes = rest[end..].starts_with('}') || rest[end..].starts_with(':');
+        if closes && name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+// The variable an expression starts from: `s` in `&s[0..5]`, `s.as_str()`, `s.part`, `*s`.
+fn root(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(p) => p.path.get_ident().map(|i| i.to_string()),
+        Expr::Field(f) => root(&f.base),
+        Expr::Index(i) => root(&i.expr),
+        Expr::MethodCall(m) => root(&m.receiver),
+        Expr::Reference(r) => root(&r.expr),
+        Expr::Paren(p) => root(&p.expr),
+        Expr::Unary(u) if matches!(u.op, syn::UnOp::Deref(_)) => root(&u.expr),
+        _ => None,
+    }
+}
+
+// Whether `'a` appears anywhere in a type: `&'a str`, `Holder<'a>`.
+fn mentions(ty: &Type, lifetime: &syn::Lifetime) -> bool {
+    struct Find<'l>(&'l syn::Lifetime, bool);
+    impl<'ast> Visit<'ast> for Find<'_> {
+        fn visit_lifetime(&mut self, l: &'ast syn::Lifetime) {
+            self.1 |= l == self.0;
+        }
+    }
+    let mut find = Find(lifetime, false);
+    find.visit_type(ty);
+    find.1
+}
+
+struct FnSig {
+    name: String,
+    line: usize,                 // The `fn` keyword.
+    end: usize,                  // The closing brace.
+    method: bool,                // Takes `self` in some form.
+    params: Vec<(String, Type)>, // (name, type), without `self`.
+    returns: Option<Type>,       // None for `()`
+    returns_text: String,
+    body: syn::Block,
+}
+
+impl FnSig {
+    fn returns_ref(&self) -> bool {
+        matches!(self.returns, Some(Type::Reference(_)))
+    }
+}
+
+// Every `fn` in the file, nested ones too (level 8 nests its helpers), and the structs with
+// a lifetime parameter (`struct Holder<'a>`): their values hold borrows.
+struct Items<'a> {
+    lines: &'a [&'a str],
+    functions: Vec<FnSig>,
+    structs: Vec<String>,
+}
+
+impl Items<'_> {
+    fn function(&mut self, sig: &syn::Signature, body: &syn::Block) {
+        let params = sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                FnArg::Typed(p) => {
+                    let name = pattern_names(&p.pat).into_iter().next().map_or_else(|| "_".to_string(), |(n, _)| n);
+                    Some((name, (*p.ty).clone()))
+                }
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+        let (returns, returns_text) = match &sig.output {
+            ReturnType::Type(_, ty) => (Some((**ty).clone()), text_of(self.lines, ty.span())),
+            ReturnType::Default => (None, String::new()),
+        };
+        self.functions.push(FnSig {
+            name: sig.ident.to_string(),
+            line: line_of(sig.fn_token.span),
+            end: line_of(body.brace_token.span.close()),
+            method: sig.receiver().is_some(),
+            params,
+            returns,
+            returns_text,
+            body: body.clone(),
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for Items<'_> {
+    fn visit_item_fn(&mut self, f: &'ast syn::ItemFn) {
+        self.function(&f.sig, &f.block);
+        visit::visit_item_fn(self, f);
+    }
+
+    fn visit_impl_item_fn(&mut self, f: &'ast syn::ImplItemFn) {
+        self.function(&f.sig, &f.block);
+        visit::visit_impl_item_fn(self, f);
+    }
+
+    fn visit_trait_item_fn(&mut self, f: &'ast syn::TraitItemFn) {
+        if let Some(body) = &f.default {
+            self.function(&f.sig, body);
+        }
+        visit::visit_trait_item_fn(self, f);
+    }
+
+    fn visit_item_struct(&mut self, s: &'ast syn::ItemStruct) {
+        if s.generics.lifetimes().next().is_some() {
+            self.structs.push(s.ident.to_string());
+        }
+        visit::visit_item_struct(self, s);
+    }
+}
+
+// The line numbers of commented-out statements marked as errors, with the code put back.
+fn what_ifs(lines: &[&str], from: usize, to: usize) -> Vec<(usize, String)> {
+    let mut found = Vec::new();
+    for no in from..=to {
+        let Some(content) = lines[no - 1].trim_start().strip_prefix("//") else { continue };
+        let content = content.strip_prefix(' ').unwrap_or(content);
+        let (code, comment) = split_comment(content);
+        let code = code.trim();
+        let statement = code.ends_with(';') && (code.starts_with("let ") || code.contains('(') || code.contains(" = "));
+        if statement && comment.to_lowercase().contains("error") {
+            let indent = lines[no - 1].len() - lines[no - 1].trim_start().len();
+            found.push((no, format!("{}{} // {}", " ".repeat(indent), code, comment)));
+        }
+    }
+    found
+}
+
+// --- The timeline ---
+
+#[derive(Debug, Clone)]
+struct Binding {
+    name: String,
+    decl: usize,
+    end: usize,               // The line whose `}` ends its scope.
+    param: bool,
+    assigned: Option<usize>,  // `let result;` has no value until `result = ...;`.
+    reference: bool,
+    sources: Vec<usize>,      // The bindings it borrows from (owners, after following references).
+    via: Option<String>,      // Why, when a signature decides: "longest_slice<'a>(x, y)".
+    uses: Vec<usize>,
+    column: usize,
+}
+
+impl Binding {
+    fn last_use(&self) -> usize {
+        self.uses.iter().copied().max().unwrap_or(0).max(self.assigned.unwrap_or(self.decl))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Problem {
+    line: usize,
+    code: &'static str, // "E0597", "E0425"
+    message: String,
+    detail: Vec<String>,
+    bindings: Vec<usize>,
+}
+
+struct Timeline {
+    function: String,
+    lines: Vec<(usize, String)>, // (line number, source as analysed)
+    bindings: Vec<Binding>,
+    problems: Vec<Problem>,
+}
+
+// What an initializer borrows from.
+struct Borrow {
+    reference: bool,
+    sources: Vec<usize>,
+    via: Option<String>,
+}
+
+const BORROWING_METHODS: [&str; 12] =
+    ["as_str", "as_slice", "as_bytes", "as_ref", "trim", "iter", "chars", "lines", "split_whitespace", "first", "last", "get"];
+
+const OWNED: Borrow = Borrow { reference: false, sources: Vec::new(), via: None };
+
+struct Analysis<'a> {
+    functions: &'a [FnSig],
+    structs: &'a [String],
+    bindings: Vec<Binding>,
+    blocks: Vec<Vec<usize>>, // Open blocks, innermost last, with the bindings declared in them.
+    problems: Vec<Problem>,
+}
+
+impl Analysis<'_> {
+    fn visible(&self, name: &str) -> Option<usize> {
+        self.blocks.iter().rev().flat_map(|b| b.iter().rev()).copied().find(|&b| self.bindings[b].name == name)
+    }
+
+    // What a reference to `name` (or a copy of it, if it is a reference itself) borrows from.
+    fn through(&self, name: &str) -> Option<Vec<usize>> {
+        let b = self.visible(name)?;
+        let binding = &self.bindings[b];
+        Some(if binding.reference { binding.sources.clone() } else { vec![b] })
+    }
+
+    fn borrow_of(&self, expr: &Expr, annotated_ref: bool) -> Borrow {
+        match expr {
+            Expr::Lit(lit) if matches!(lit.lit, Lit::Str(_)) => {
+                return Borrow { reference: true, sources: Vec::new(), via: None }; // 'static
+            }
+            Expr::Reference(r) => {
+                return Borrow { reference: true, sources: root(&r.expr).and_then(|n| self.through(&n)).unwrap_or_default(), via: None };
+            }
+            Expr::Paren(p) => return self.borrow_of(&p.expr, annotated_ref),
+            Expr::Path(p) => {
+                return match p.path.get_ident().and_then(|i| self.visible(&i.to_string())) {
+                    Some(b) if self.bindings[b].reference => Borrow { reference: true, sources: self.bindings[b].sources.clone(), via: None },
+                    _ => OWNED,
+                };
+            }
+            Expr::MethodCall(call) => {
+                let method = call.method.to_string();
+                let defined = self.functions.iter().find(|f| f.method && f.name == method && f.returns_ref());
+                if BORROWING_METHODS.contains(&method.as_str()) || defined.is_some() {
+                    let via = defined.map(|f| format!("{}(&self) -> {}", f.name, f.returns_text));
+                    return Borrow { reference: true, sources: root(&call.receiver).and_then(|n| self.through(&n)).unwrap_or_default(), via };
+                }
+            }
+            Expr::Call(call) => {
+                let name = match &*call.func {
+                    Expr::Path(p) => p.path.get_ident().map(|i| i.to_string()),
+                    _ => None,
+                };
+                if let Some(f) = name.and_then(|n| self.functions.iter().find(|f| !f.method && f.name == n && f.returns_ref())) {
+                    return self.call(f, &call.args);
+                }
+            }
+            Expr::Struct(s) => {
+                let name = s.path.segments.last().map(|s| s.ident.to_string()).unwrap_or_default();
+                if self.structs.contains(&name) {
+                    let sources = s.fields.iter().flat_map(|field| self.borrow_of(&field.expr, false).sources).collect();
+                    return Borrow { reference: true, sources, via: Some(format!("{}<'a>", name)) };
+                }
+            }
+            _ => {}
+        }
+        if annotated_ref {
+            struct Refs(Vec<String>);
+            impl<'ast> Visit<'ast> for Refs {
+                fn visit_expr_reference(&mut self, r: &'ast syn::ExprReference) {
+                    self.0.extend(root(&r.expr));
+                    visit::visit_expr_reference(self, r);
+                }
+            }
+            let mut refs = Refs(Vec::new());
+            refs.visit_expr(expr);
+            let sources = refs.0.iter().flat_map(|n| self.through(n).unwrap_or_default()).collect();
+            return Borrow { reference: true, sources, via: None };
+        }
+        OWNED
+    }
+
+    // A call to a function that returns a reference: the signature says which arguments it may point into.
+    fn call(&self, f: &FnSig, args: &Punctuated<Expr, Token![,]>) -> Borrow {
+        let lifetime = match &f.returns {
+            Some(Type::Reference(r)) => r.lifetime.clone(),
+            _ => None,
+        };
+        if lifetime.as_ref().is_some_and(|l| l.ident == "static") {
+            return Borrow { reference: true, sources: Vec::new(), via: None };
+        }
+        let tied: Vec<usize> = match &lifetime {
+            None => {
+                let refs: Vec<usize> = (0..f.params.len()).filter(|&i| matches!(f.params[i].1, Type::Reference(_))).collect();
+                if refs.len() == 1 { refs } else { Vec::new() }
+            }
+            Some(l) => (0..f.params.len()).filter(|&i| mentions(&f.params[i].1, l)).collect(),
+        };
+        let mut sources = Vec::new();
+        for &i in &tied {
+            if let Some(arg) = args.iter().nth(i) {
+                sources.extend(self.borrow_of(arg, false).sources);
+            }
+        }
+        let names: Vec<&str> = tied.iter().map(|&i| f.params[i].0.as_str()).collect();
+        let via = match lifetime {
+            None => format!("{}({}) -> {}: elided, the result borrows from `{}`", f.name, names.join(", "), f.returns_text, names.join("")),
+            Some(l) => format!("{}<{}>: {} and the result share {}", f.name, l, names.iter().map(|n| format!("`{}`", n)).collect::<Vec<_>>().join(", "), l),
+        };
+        Borrow { reference: true, sources, via: Some(via) }
+    }
+
+    fn declare(&mut self, name: &str, line: usize, borrow: Option<Borrow>, param: bool) {
+        let (assigned, reference, sources, via) = match borrow {
+            Some(b) => (Some(line), b.reference, b.sources, b.via),
+            None => (None, false, Vec::new(), None),
+        };
+        self.bindings.push(Binding { name: name.to_string(), decl: line, end: 0, param, assigned, reference, sources, via, uses: Vec::new(), column: 0 });
+        let b = self.bindings.len() - 1;
+        if let Some(block) = self.blocks.last_mut() {
+            block.push(b);
+        }
+    }
+
+    // Runs `inside` in a new block whose bindings are dropped at line `end`.
+    fn scope(&mut self, end: usize, inside: impl FnOnce(&mut Self)) {
+        self.blocks.push(Vec::new());
+        inside(self);
+        for b in self.blocks.pop().unwrap_or_default() {
+            self.bindings[b].end = end;
+        }
+    }
+
+    fn use_name(&mut self, name: &str, line: usize) {
+        if let Some(b) = self.visible(name) {
+            self.bindings[b].uses.push(line);
+        } else if let Some(gone) = self.bindings.iter().rposition(|b| b.name == name && b.end != 0 && b.end < line) {
+            if !self.problems.iter().any(|p| p.line == line && p.bindings == [gone]) {
+                self.problems.push(Problem {
+                    line,
+                    code: "E0425",
+                    message: format!("cannot find value `{}` in this scope", name),
+                    detail: vec![format!("`{}` was declared at line {}, but its block ended at line {}.", name, self.bindings[gone].decl, self.bindings[gone].end)],
+                    bindings: vec![gone],
+                });
+            }
+        }
+    }
+
+    // A macro body that is not a list of expressions (`vec![0; n]`): every name in it.
+    fn tokens(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                TokenTree::Ident(ident) => self.use_name(&ident.to_string(), line_of(ident.span())),
+                TokenTree::Group(group) => self.tokens(group.stream()),
+                TokenTree::Literal(_) | TokenTree::Punct(_) => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Analysis<'_> {
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        self.scope(line_of(block.brace_token.span.close()), |a| block.stmts.iter().for_each(|s| a.visit_stmt(s)));
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let Some(init) = &local.init {
+            self.visit_expr(&init.expr);
+            if let Some((_, diverge)) = &init.diverge {
+                self.visit_expr(diverge);
+            }
+        }
+        let (pattern, annotated_ref) = match &local.pat {
+            Pat::Type(t) => (&*t.pat, matches!(*t.ty, Type::Reference(_))),
+            pattern => (pattern, false),
+        };
+        let names = pattern_names(pattern);
+        let line = line_of(local.let_token.span);
+        for (name, _) in &names {
+            let borrow = local.init.as_ref().map(|i| if names.len() == 1 { self.borrow_of(&i.expr, annotated_ref) } else { OWNED });
+            self.declare(name, line, borrow, false);
+        }
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {} // A nested helper function or type.
+
+    fn visit_expr_path(&mut self, path: &'ast syn::ExprPath) {
+        if let Some(ident) = path.path.get_ident() {
+            self.use_name(&ident.to_string(), line_of(ident.span()));
+        }
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'ast syn::ExprAssign) {
+        let target = match &*assign.left {
+            Expr::Path(p) => p.path.get_ident().and_then(|i| self.visible(&i.to_string())),
+            _ => None,
+        };
+        let Some(b) = target else { return visit::visit_expr_assign(self, assign) };
+        self.visit_expr(&assign.right);
+        let borrow = self.borrow_of(&assign.right, false);
+        let line = line_of(assign.left.span());
+        let binding = &mut self.bindings[b];
+        if binding.assigned.is_none() {
+            binding.assigned = Some(line);
+        }
+        binding.reference |= borrow.reference;
+        binding.sources.extend(borrow.sources);
+        binding.via = borrow.via.or(binding.via.take());
+    }
+
+    fn visit_expr_for_loop(&mut self, for_loop: &'ast syn::ExprForLoop) {
+        self.visit_expr(&for_loop.expr);
+        let line = line_of(for_loop.for_token.span);
+        self.scope(line_of(for_loop.body.brace_token.span.close()), |a| {
+            for (name, _) in pattern_names(&for_loop.pat) {
+                a.declare(&name, line, Some(OWNED), false);
+            }
+            a.visit_block(&for_loop.body);
+        });
+    }
+
+    fn visit_expr_closure(&mut self, closure: &'ast syn::ExprClosure) {
+        self.scope(closure.span().end().line, |a| {
+            for (name, line) in closure.inputs.iter().flat_map(pattern_names) {
+                a.declare(&name, line, Some(OWNED), false);
+            }
+            a.visit_expr(&closure.body);
+        });
+    }
+
+    fn visit_expr_if(&mut self, expr_if: &'ast syn::ExprIf) {
+        // `if let` bindings live in the `then` block only.
+        self.scope(line_of(expr_if.then_branch.brace_token.span.close()), |a| {
+            a.visit_expr(&expr_if.cond);
+            a.visit_block(&expr_if.then_branch);
+        });
+        if let Some((_, other)) = &expr_if.else_branch {
+            self.visit_expr(other);
+        }
+    }
+
+    fn visit_expr_while(&mut self, expr_while: &'ast syn::ExprWhile) {
+        self.scope(line_of(expr_while.body.brace_token.span.close()), |a| {
+            a.visit_expr(&expr_while.cond);
+            a.visit_block(&expr_while.body);
+        });
+    }
+
+    fn visit_expr_let(&mut self, expr_let: &'ast syn::ExprLet) {
+        self.visit_expr(&expr_let.expr);
+        for (name, line) in pattern_names(&expr_let.pat) {
+            self.declare(&name, line, Some(OWNED), false);
+        }
+    }
+
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        self.scope(arm.body.span().end().line, |a| {
+            for (name, line) in pattern_names(&arm.pat) {
+                a.declare(&name, line, Some(OWNED), false);
+            }
+            if let Some((_, guard)) = &arm.guard {
+                a.visit_expr(guard);
+            }
+            a.visit_expr(&arm.body);
+        });
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) else {
+            return self.tokens(mac.tokens.clone());
+        };
+        for arg in &args {
+            if let Expr::Lit(syn::ExprLit { lit: Lit::Str(format), .. }) = arg {
+                for name in format_names(&format.value()) {
+                    self.use_name(&name, line_of(format.span()));
+                }
+            }
+            self.visit_expr(arg);
+        }
+    }
+}
+
+fn analyse(source: &str, function: &str, what_if: Option<&(usize, String)>) -> Result<Timeline, String> {
+    let mut lines: Vec<&str> = source.lines().collect();
+    if let Some((no, code)) = what_if {
+        lines[no - 1] = code;
+    }
+    let file = syn::parse_file(&lines.join("\n")).map_err(|e| format!("line {}: {}", line_of(e.span()), e))?;
+    let mut items = Items { lines: &lines, functions: Vec::new(), structs: Vec::new() };
+    items.visit_file(&file);
+    let f = items.functions.iter().find(|f| f.name == function).ok_or_else(|| format!("no function `{}` in the file", function))?;
+    let shown: Vec<(usize, String)> = (f.line..=f.end).map(|no| (no, lines[no - 1].to_string())).collect();
+    let mut a = Analysis { functions: &items.functions, structs: &items.structs, bindings: Vec::new(), blocks: Vec::new(), problems: Vec::new() };
+    a.scope(f.end, |a| {
+        for (name, ty) in f.params.iter().filter(|(name, _)| name != "_") {
+            let borrow = Borrow { reference: matches!(ty, Type::Reference(_)), sources: Vec::new(), via: None };
+            a.declare(name, f.line, Some(borrow), true);
+        }
+        a.visit_block(&f.body);
+    });
+
+
+    // E0597: a reference used after something it borrows from was dropped.
+    for (r, binding) in a.bindings.iter().enumerate() {
+        let mut sources = binding.sources.clone();
+        sources.dedup();
+        for &o in &sources {
+            let owner = &a.bindings[o];
+            let Some(&late) = binding.uses.iter().find(|&&u| u > owner.end) else { continue };
+            let mut detail = vec![format!(
+                "`{}` borrows `{}` (line {}) and is still used at line {},",
+                binding.name,
+                owner.name,
+                binding.assigned.unwrap_or(binding.decl),
+                late
+            )];
+            detail.push(format!("but `{}` is dropped at line {}, at the end of its block.", owner.name, owner.end));
+            if let Some(via) = &binding.via {
+                detail.push(format!("It borrows `{}` through {}.", owner.name, via));
+                if via.contains("share '") {
+                    detail.push(format!("That lifetime has to cover every use of `{}`, but it can last no longer than", binding.name));
+                    detail.push(format!("the shorter-lived argument, `{}`.", owner.name));
+                }
+            }
+            a.problems.push(Problem { line: late, code: "E0597", message: format!("`{}` does not live long enough", owner.name), detail, bindings: vec![o, r] });
+        }
+    }
+    a.problems.sort_by_key(|p| p.line);
+
+    // Columns: the first one whose previous binding is gone.
+    let mut columns: Vec<usize> = Vec::new(); // The last line each column is busy.
+    for binding in a.bindings.iter_mut() {
+        let free = columns.iter().position(|&busy| busy < binding.decl);
+        binding.column = free.unwrap_or(columns.len());
+        match free {
+            Some(c) => columns[c] = binding.end,
+            None => columns.push(binding.end),
+        }
+    }
+    Ok(Timeline { function: function.to_string(), lines: shown, bindings: a.bindings, problems: a.problems })
+}
+
+
+// --- Drawing ---
+
+fn glyph(binding: &Binding, line: usize, problems: &[Problem], index: usize) -> char {
+    if line < binding.decl || line > binding.end {
+        return ' ';
+    }
+    let borrowing = binding.reference && binding.assigned.is_some_and(|a| a <= line) && line <= binding.last_use();
+    if borrowing && problems.iter().any(|p| p.line == line && p.code == "E0597" && p.bindings.last() == Some(&index)) {
+        return '!';
+    }
+    if line == binding.decl && !binding.param {
+        return if binding.reference && binding.assigned == Some(line) && !binding.sources.is_empty() { '&' } else { 'o' };
+    }
+    if line == binding.end {
+        return 'x';
+    }
+    match binding.assigned {
+        None => ':',
+        Some(a) if line < a => ':',
+        Some(a) if line == a && binding.reference && !binding.sources.is_empty() => '&',
+        _ if borrowing && !binding.sources.is_empty() => '#',
+        _ => '|',
+    }
+}
+
+fn column_letter(column: usize) -> char {
+    (b'A' + (column % 26) as u8) as char
+}
+
+fn describe(binding: &Binding, bindings: &[Binding]) -> String {
+    let kind = match (binding.param, binding.reference) {
+        (true, true) => "reference parameter, borrowed from the caller".to_string(),
+        (true, false) => "parameter, owns its value".to_string(),
+        (false, false) => "owns its value".to_string(),
+        (false, true) if binding.sources.is_empty() => "reference to 'static data (a string literal)".to_string(),
+        (false, true) => {
+            let mut names: Vec<String> = binding.sources.iter().map(|&s| format!("`{}`", bindings[s].name)).collect();
+            names.dedup();
+            let live = match binding.assigned {
+                Some(a) if a == binding.last_use() => format!("borrow live at line {}", a),
+                Some(a) => format!("borrow live {}-{}", a, binding.last_use()),
+                None => "never assigned".to_string(),
+            };
+            let via = binding.via.as_ref().map(|v| format!(" (through {})", v)).unwrap_or_default();
+            format!("borrows {}{}, {}", names.join(" and "), via, live)
+        }
+    };
+    format!("{}  {:<w$} lines {}-{}  {}", column_letter(binding.column), binding.name, binding.decl, binding.end, kind, w = bindings.iter().map(|b| b.name.len()).max().unwrap_or(0))
+}
+
+fn render(timeline: &Timeline, from: usize, to: usize) -> String {
+    let width = timeline.bindings.iter().map(|b| b.column + 1).max().unwrap_or(0);
+    let mut out = String::new();
+    let header: String = (0..width).map(|c| format!("{} ", column_letter(c))).collect();
+    out.push_str(&format!("  {}\n", header.trim_end()));
+    for (no, raw) in timeline.lines.iter().filter(|(no, _)| (from..=to).contains(no)) {
+        let mut gutter = vec![' '; width];
+        for (i, b) in timeline.bindings.iter().enumerate() {
+            let g = glyph(b, *no, &timeline.problems, i);
+            if g != ' ' {
+                gutter[b.column] = g;
+            }
+        }
+        let marker = if timeline.problems.iter().any(|p| p.line == *no) { '!' } else { ' ' };
+        let gutter: String = gutter.iter().map(|g| format!("{} ", g)).collect();
+        out.push_str(&format!("{} {}{:>5} | {}\n", marker, gutter, no, raw.trim_end()));
+    }
+    out
+}
+
+fn render_problems(timeline: &Timeline) -> String {
+    let mut out = String::new();
+    for p in &timeline.problems {
+        out.push_str(&format!("error[{}]: {}\n  --> line {}\n", p.code, p.message, p.line));
+        for d in &p.detail {
+            out.push_str(&format!("  {}\n", d));
+        }
+    }
+    out
+}
+
+fn report(source: &str, file: &str, function: &str) -> Result<String, String> {
+    let timeline = analyse(source, function, None)?;
+    let (first, last) = (timeline.lines[0].0, timeline.lines[timeline.lines.len() - 1].0);
+    let mut out = format!("{} ({}:{})\n\n", function, file, first);
+    out.push_str(&render(&timeline, first, last));
+    out.push('\n');
+    for b in &timeline.bindings {
+        out.push_str(&format!("  {}\n", describe(b, &timeline.bindings)));
+    }
+    out.push('\n');
+    if timeline.problems.is_empty() {
+        out.push_str("No scope or lifetime errors in the code as written.\n");
+    } else {
+        out.push_str(&render_problems(&timeline));
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+    for example in what_ifs(&lines, first, last) {
+        out.push_str(&format!("\n=== What if line {} were not commented out? ===\n\n", example.0));
+        let what = match analyse(source, function, Some(&example)) {
+            Ok(what) => what,
+            Err(e) => {
+                out.push_str(&format!("(the file no longer parses: {})\n", e));
+                continue;
+            }
+        };
+        match what.problems.iter().find(|p| p.line == example.0) {
+            Some(p) => {
+                let start = p.bindings.iter().map(|&b| what.bindings[b].decl).min().unwrap_or(first);
+                out.push_str(&render(&what, start, example.0));
+                out.push('\n');
+                let only = Timeline { function: what.function.clone(), lines: Vec::new(), bindings: Vec::new(), problems: vec![p.clone()] };
+                out.push_str(&render_problems(&only));
+            }
+            None => out.push_str("(found no error on that line)\n"),
+        }
+    }
+    Ok(out)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [file, function] = args.as_slice() else {
+        eprintln!("usage: lifetime_timeline FILE FUNCTION");
+        process::exit(2);
+    };
+    match fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e)).and_then(|s| report(&s, file, function)) {
+        Ok(text) => print!("{}", text),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    const FIXTURE: &str = r#"fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() { x } else { y }
+}
+
+fn first(s: &str) -> &str {
+    &s[..1]
+}
+
+struct Wrapper<'a> {
+    part: &'a str,
+}
+
+fn too_short() {
+    let outer = String::from("outer");
+    let result;
+    {
+        let inner = String::from("in");
+        result = longest(outer.as_str(), inner.as_str());
+        println!("{}", result);
+    }
+    println!("{}", result);
+}
+
+fn fine() {
+    let outer = String::from("outer");
+    let kept;
+    {
+        let slice: &str = &outer[0..2];
+        kept = Wrapper { part: slice };
+    }
+    let one = first(&outer);
+    println!("{} {}", kept.part, one);
+}
+
+fn scopes() {
+    let x = 5;
+    {
+        let x = x * 2;
+        let y = 1;
+        println!("{} {}", x, y);
+    }
+    // println!("{}", y); // ERROR: not in this scope
+    println!("{}", x);
+}
+"#;
+
+    fn find(t: &Timeline, name: &str, nth: usize) -> Binding {
+        t.bindings.iter().filter(|b| b.name == name).nth(nth).cloned().unwrap_or_else(|| panic!("no binding `{}` #{}", name, nth))
+    }
+
+    #[test]
+    fn a_shared_lifetime_ties_the_result_to_the_shorter_argument() {
+        let t = analyse(FIXTURE, "too_short", None).unwrap();
+        let inner = find(&t, "inner", 0);
+        assert_eq!((inner.decl, inner.end), (17, 20), "a block's bindings end at its closing brace");
+        let result = find(&t, "result", 0);
+        assert_eq!(result.assigned, Some(18), "`let result;` has no value until it is assigned");
+        assert_eq!(result.sources.len(), 2, "a shared 'a makes the result borrow both arguments");
+        assert_eq!(t.problems.len(), 1);
+        assert_eq!((t.problems[0].code, t.problems[0].line), ("E0597", 21));
+        assert!(t.problems[0].message.contains("`inner`"));
+        let drawn = render(&t, 13, 22);
+        assert!(drawn.lines().any(|l| l.starts_with('!') && l.contains("   21 |")), "the gutter marks the bad use with !");
+        assert!(drawn.lines().any(|l| l.contains('x') && l.contains("   20 |")), "...and the drop with x");
+    }
+
+    #[test]
+    fn structs_and_elided_signatures_borrow_the_owner() {
+        let t = analyse(FIXTURE, "fine", None).unwrap();
+        let kept = find(&t, "kept", 0);
+        let owners: Vec<&str> = kept.sources.iter().map(|&s| t.bindings[s].name.as_str()).collect();
+        assert_eq!(owners, ["outer"], "a struct holding a copy of a reference borrows the owner, not the reference");
+        let one = find(&t, "one", 0);
+        assert!(one.reference && one.sources.len() == 1, "an elided signature borrows from its one reference parameter");
+        assert_eq!(t.problems, []);
+    }
+
+    #[test]
+    fn shadowing_makes_a_second_binding() {
+        let t = analyse(FIXTURE, "scopes", None).unwrap();
+        let (outer, inner) = (find(&t, "x", 0), find(&t, "x", 1));
+        assert_eq!((outer.end, inner.end), (44, 41));
+        assert!(outer.uses.contains(&38), "`let x = x * 2` uses the outer x");
+        assert_eq!((inner.column, find(&t, "y", 0).column), (1, 2), "a shadowed binding takes a new column only while both live");
+    }
+
+    #[test]
+    fn a_commented_out_name_from_a_closed_block_is_e0425() {
+        let lines: Vec<&str> = FIXTURE.lines().collect();
+        let examples = what_ifs(&lines, 35, 44);
+        assert_eq!(examples.len(), 1);
+        let t = analyse(FIXTURE, "scopes", Some(&examples[0])).unwrap();
+        let codes: Vec<(&str, usize)> = t.problems.iter().map(|p| (p.code, p.line)).collect();
+        assert_eq!(codes, [("E0425", 42)]);
+    }
+
+    #[test]
+    fn format_string_captures_are_uses() {
+        assert_eq!(format_names("{x} {{y}} {z:>5} {0} {}"), ["x", "z"]);
+        let t = analyse("fn f() {\n    let x = 1;\n    println!(\"{x}\");\n}", "f", None).unwrap();
+        assert_eq!(find(&t, "x", 0).uses, [3]);
+    }
+
+    // The lessons: nothing wrong in the code that runs, the expected error for each example.
+    #[test]
+    fn lesson_examples() {
+        let folder = Path::new(file!()).parent().expect("file!() names this file in its folder");
+        let lessons = [
+            ("Day001.002.rs", "variable_scopes", vec!["E0425"]),
+            ("Day001.002.rs", "lifetimes_explained", vec!["E0597"]),
+            ("Day001.003.rs", "level_8_advanced_lifetimes", vec!["E0597"]),
+        ];
+        for (file, function, expected) in lessons {
+            let source = fs::read_to_string(folder.join(file)).unwrap();
+            let real = analyse(&source, function, None).unwrap();
+            assert_eq!(real.problems, [], "{} {}: the code as written", file, function);
+            let lines: Vec<&str> = source.lines().collect();
+            let (first, last) = (real.lines[0].0, real.lines[real.lines.len() - 1].0);
+            let found: Vec<&str> = what_ifs(&lines, first, last)
+                .iter()
+                .filter_map(|e| analyse(&source, function, Some(e)).unwrap().problems.iter().find(|p| p.line == e.0).map(|p| p.code))
+                .collect();
+            assert_eq!(found, expected, "{} {}: the commented-out examples", file, function);
+        }
+    }
+
+    #[test]
+    fn holder_borrows_the_owner_so_it_may_outlive_the_inner_block() {
+        let source = fs::read_to_string(Path::new(file!()).with_file_name("Day001.003.rs")).unwrap();
+        let t = analyse(&source, "level_8_advanced_lifetimes", None).unwrap();
+        let holder = find(&t, "holder_instance", 0);
+        let owners: Vec<&str> = holder.sources.iter().map(|&s| t.bindings[s].name.as_str()).collect();
+        assert_eq!(owners, ["s_outer"], "Holder borrows s_outer, not part_of_s_outer");
+        assert!(holder.last_use() > holder.decl + 3);
+    }
+}
@@ -0,0 +1,79 @@
+# limits_matrix.py - numeric limits and overflow behaviour across Rust, C++ and Python
+# Runs the three probes (limits_probe.rs in a debug and a release build, limits_probe.cpp,
+# limits_probe.py) and merges what they report into one Markdown table.
+# Nothing in the table is typed in by hand: every cell is something a probe printed.
+#
+#   python3 limits_matrix.py RUST_DEBUG RUST_RELEASE CPP > limits_matrix.md
+# (./build_limits_matrix.sh builds the probes and does this for you.)
+
+import os
+import subprocess
+import sys
+
+COLUMNS = ["min", "max", "overflow", "from_1000", "from_minus_1", "from_1e10", "from_nan"]
+HEADINGS = ["Language", "Type", "Min", "Max", "MAX + 1", "1000 → T", "-1 → T", "1e10 → T", "NaN → T"]
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+
+def run(command):
+    output = subprocess.run(command, check=True, capture_output=True, text=True).stdout
+    rows = []
+    for line in output.splitlines():
+        if not line or line.startswith("#"):
+            continue
+        language, type_name, *values = line.split("\t")
+        if len(values) != len(COLUMNS):
+            raise ValueError("%s: expected %d columns, got %r" % (command[0], len(COLUMNS) + 2, line))
+        rows.append(dict(language=language, type=type_name, **dict(zip(COLUMNS, values))))
+    return rows
+
+
+def merge_rust(debug, release):
+    """One row per Rust type; the overflow cell says what each build did when they differ."""
+    merged = []
+    for d, r in zip(debug, release):
+        assert d["type"] == r["type"]
+        row = dict(r)
+        if d["overflow"] != r["overflow"]:
+            row["overflow"] = "debug: %s; release: %s" % (d["overflow"], r["overflow"])
+        merged.append(row)
+    return merged
+
+
+def collect(rust_debug, rust_release, cpp):
+    return (merge_rust(run([rust_debug]), run([rust_release]))
+            + run([cpp])
+            + run([sys.executable, os.path.join(HERE, "limits_probe.py")]))
+
+
+def render(rows):
+    out = [
+        "# Numeric limits and overflow across languages",
+        "",
+        "Generated by `./build_limits_matrix.sh` from limits_probe.rs, limits_probe.cpp and",
+        "limits_probe.py; do not edit by hand. `MAX + 1` is what the addition does at run time;",
+        "C++ promotes types narrower than `int` first, so for those the cell shows the `int` sum",
+        "and what narrowing it back to the type gives.",
+        "The conversion columns use `as` in Rust, `static_cast` in C++ and the type's",
+        "constructor in Python. \"UB\" marks undefined behaviour the C++ probe refuses to run;",
+        "a Python exception name means the conversion raised it.",
+        "",
+        "| " + " | ".join(HEADINGS) + " |",
+        "|" + "---|" * len(HEADINGS),
+    ]
+    for row in rows:
+        cells = [row["language"], "`%s`" % row["type"]] + [row[c] for c in COLUMNS]
+        out.append("| " + " | ".join(cells) + " |")
+    return "\n".join(out) + "\n"
+
+
+def main():
+    if len(sys.argv) != 4:
+        print("usage: limits_matrix.py RUST_DEBUG RUST_RELEASE CPP", file=sys.stderr)
+        sys.exit(2)
+    sys.stdout.write(render(collect(*sys.argv[1:])))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,89 @@
+// limits_probe.cpp - the C++ probe for limits_matrix.py
+// Prints one tab-separated line per type, in the same columns as limits_probe.rs:
+//   cpp <type> <min> <max> <MAX + 1> <1000 -> T> <-1 -> T> <1e10 -> T> <NaN -> T>
+// Conversions use static_cast. Where the standard says the behaviour is undefined
+// (signed overflow, an out-of-range float to int) the probe says "UB" instead of doing it.
+// Types narrower than int are promoted before the addition, so their MAX + 1 is a defined
+// int; the cell shows that sum and what narrowing it back to T gives.
+// Build: c++ -std=c++17 -O2 limits_probe.cpp -o target/limits_probe_cpp
+
+#include <cmath>        // Required for NAN
+#include <iostream>     // Required for input/output operations
+#include <limits>       // Required for checking min/max values of data types
+#include <sstream>      // Required for std::ostringstream
+#include <string>       // Required for string operations
+
+// Print char-sized integers as numbers, not characters
+template <typename T>
+std::string show(T value) {
+    std::ostringstream out;
+    if constexpr (std::is_floating_point_v<T>) {
+        out.precision(std::numeric_limits<T>::max_digits10);
+        out << value;
+    } else {
+        out << +value;
+    }
+    return out.str();
+}
+
+void line(const char* type, const std::string (&columns)[7]) {
+    std::cout << "cpp\t" << type;
+    for (const auto& column : columns) std::cout << "\t" << column;
+    std::cout << "\n";
+}
+
+template <typename T>
+void int_row(const char* type) {
+    using L = std::numeric_limits<T>;
+    std::string overflow;
+    if constexpr (sizeof(T) < sizeof(int)) {
+        // The sum is an int; narrowing it is modular since C++20, implementation-defined before
+        overflow = show(L::max() + 1) + " in int, narrows to " + show(static_cast<T>(L::max() + 1));
+    } else {
+        // Unsigned types are defined to wrap (is_modulo); signed overflow is undefined behaviour
+        overflow = L::is_modulo ? "wraps to " + show(static_cast<T>(L::max() + 1)) : "UB";
+    }
+    bool fits = 1e10 >= static_cast<double>(L::min()) && 1e10 <= static_cast<double>(L::max());
+    line(type, {
+        show(L::min()),
+        show(L::max()),
+        overflow,
+        show(static_cast<T>(1000LL)),  // Modular since C++20, implementation-defined before
+        show(static_cast<T>(-1LL)),
+        fits ? show(static_cast<T>(1e10)) : "UB",
+        "UB",  // NaN is never in range
+    });
+}
+
+template <typename T>
+void float_row(const char* type) {
+    using L = std::numeric_limits<T>;
+    volatile T max = L::max();  // volatile: do the addition at run time
+    line(type, {
+        show(L::lowest()),
+        show(L::max()),
+        show(static_cast<T>(max + 1)),  // 1 is far below half a step at MAX: rounds back to MAX
+        show(static_cast<T>(1000LL)),
+        show(static_cast<T>(-1LL)),
+        show(static_cast<T>(1e10)),
+        show(static_cast<T>(NAN)),
+    });
+}
+
+int main() {
+    int_row<signed char>("signed char");
+    int_row<short>("short");
+    int_row<int>("int");
+    int_row<long>("long");
+    int_row<long long>("long long");
+    int_row<char>("char");
+    int_row<unsigned char>("unsigned char");
+    int_row<unsigned short>("unsigned short");
+    int_row<unsigned int>("unsigned int");
+    int_row<unsigned long>("unsigned long");
+    int_row<unsigned long long>("unsigned long long");
+    float_row<float>("float");
+    float_row<double>("double");
+    float_row<long double>("long double");
+    return 0;
+}
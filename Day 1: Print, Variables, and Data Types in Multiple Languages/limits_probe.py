@@ -0,0 +1,57 @@
+# limits_probe.py - the Python probe for limits_matrix.py
+# Prints one tab-separated line per type, in the same columns as limits_probe.rs:
+#   python <type> <min> <max> <MAX + 1> <1000 -> T> <-1 -> T> <1e10 -> T> <NaN -> T>
+# Python's own int never overflows, so the fixed-width types come from ctypes
+# (what you meet when talking to C, see Day1001_ffi.py). Conversions that raise
+# print the exception name.
+
+import ctypes
+import sys
+
+
+def attempt(convert):
+    try:
+        return str(convert())
+    except Exception as e:
+        return type(e).__name__
+
+
+def line(type_name, *columns):
+    print("\t".join(["python", type_name] + [str(c) for c in columns]))
+
+
+def int_row():
+    huge = 2**64  # Bigger than any C integer
+    line("int", "unbounded", "unbounded",
+         "grows (bigint)" if huge + 1 == 18446744073709551617 else "?",
+         int(1000), int(-1), attempt(lambda: int(1e10)), attempt(lambda: int(float("nan"))))
+
+
+def float_row():
+    biggest = sys.float_info.max
+    line("float", repr(-biggest), repr(biggest),
+         "%r, ** raises %s" % (biggest + 1, attempt(lambda: biggest ** 2)),
+         float(1000), float(-1), float(1e10), float("nan"))
+
+
+def ctypes_row(name, ctype):
+    bits = ctypes.sizeof(ctype) * 8
+    signed = ctype(-1).value == -1
+    lowest = ctype(1 << (bits - 1)).value if signed else 0  # 1 << (bits-1) wraps to MIN
+    highest = ctype(-1).value if not signed else (1 << (bits - 1)) - 1
+    line("ctypes." + name, lowest, highest,
+         "wraps to %d" % ctype(highest + 1).value,
+         ctype(1000).value, ctype(-1).value,
+         attempt(lambda: ctype(1e10).value), attempt(lambda: ctype(float("nan")).value))
+
+
+def main():
+    int_row()
+    # By name: ctypes.c_int8.__name__ is the C alias, "c_byte"
+    for name in ("c_int8", "c_int16", "c_int32", "c_int64", "c_uint8", "c_uint16", "c_uint32", "c_uint64"):
+        ctypes_row(name, getattr(ctypes, name))
+    float_row()
+
+
+if __name__ == "__main__":
+    main()
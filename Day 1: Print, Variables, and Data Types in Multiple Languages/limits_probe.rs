@@ -0,0 +1,68 @@
+// limits_probe.rs
+// The Rust probe for limits_matrix.py. For every integer and float type it prints
+// one tab-separated line with what this build actually does:
+//   rust <type> <min> <max> <MAX + 1> <1000 as T> <-1 as T> <1e10 as T> <NaN as T>
+// The first line names the build ("# build debug" or "# build release"), because
+// `MAX + 1` panics with debug assertions on and wraps with them off.
+//
+// Build both: rustc --edition 2021 limits_probe.rs -o target/limits_probe_debug
+//             rustc --edition 2021 -O limits_probe.rs -o target/limits_probe_release
+
+use std::hint::black_box;
+use std::panic;
+
+fn line(ty: &str, columns: [String; 7]) {
+    println!("rust\t{}\t{}", ty, columns.join("\t"));
+}
+
+// black_box keeps the compiler from folding `MAX + 1` at compile time (which is a hard error).
+macro_rules! int_row {
+    ($t:ty) => {{
+        let overflow = match panic::catch_unwind(|| black_box(<$t>::MAX) + black_box(1)) {
+            Ok(v) => format!("wraps to {}", v),
+            Err(_) => "panics".to_string(),
+        };
+        line(stringify!($t), [
+            <$t>::MIN.to_string(),
+            <$t>::MAX.to_string(),
+            overflow,
+            (black_box(1000i64) as $t).to_string(),
+            (black_box(-1i64) as $t).to_string(),
+            (black_box(1e10f64) as $t).to_string(), // Float to int saturates.
+            (black_box(f64::NAN) as $t).to_string(), // NaN becomes 0.
+        ]);
+    }};
+}
+
+macro_rules! float_row {
+    ($t:ty) => {{
+        line(stringify!($t), [
+            format!("{:e}", <$t>::MIN),
+            format!("{:e}", <$t>::MAX),
+            format!("{:e}", black_box(<$t>::MAX) + 1.0), // 1 is far below half a step at MAX: rounds back to MAX.
+            (black_box(1000i64) as $t).to_string(),
+            (black_box(-1i64) as $t).to_string(),
+            (black_box(1e10f64) as $t).to_string(),
+            (black_box(f64::NAN) as $t).to_string(),
+        ]);
+    }};
+}
+
+fn main() {
+    panic::set_hook(Box::new(|_| {})); // The overflow panics are expected; keep stderr quiet.
+    println!("# build {}", if cfg!(debug_assertions) { "debug" } else { "release" });
+    int_row!(i8);
+    int_row!(i16);
+    int_row!(i32);
+    int_row!(i64);
+    int_row!(i128);
+    int_row!(isize);
+    int_row!(u8);
+    int_row!(u16);
+    int_row!(u32);
+    int_row!(u64);
+    int_row!(u128);
+    int_row!(usize);
+    float_row!(f32);
+    float_row!(f64);
+}
@@ -0,0 +1,1113 @@
+// ownership_viz.rs
+// Draws what a lesson function does to the stack and the heap, one statement at a time:
+// stack frames and their bindings, heap blocks, owner arrows (`owns ---->`), borrow arrows
+// (`& ---->`, `&mut ---->`) and bindings that a move made unusable.
+//
+// Usage:
+//   rustc --edition 2021 ownership_viz.rs -o target/ownership_viz
+//   target/ownership_viz Day001.002.rs ownership_and_moves            # ASCII, step by step
+//   target/ownership_viz Day001.002.rs rules_of_references --svg target/rules.svg
+//   target/ownership_viz Day001.003.rs level_2_ownership_borrowing
+//
+// Tests (fixtures and the level 2 lessons):
+//   rustc --edition 2021 --test ownership_viz.rs -o target/ownership_viz_test && target/ownership_viz_test
+//
+// Nothing is compiled or run: the tool reads the function and models the statements the
+// ownership lessons use: `String::from`, moves, copies, `.clone()`, `&x` and `&mut x`
+// (including slices), calls to functions in the same file (their bodies get their own
+// stack frame when they are straight-line code), `push_str`-style mutations, and the end
+// of a block. Anything else is left out of the picture. The lesson's own comment on a
+// line is shown with its step, so the comments are the captions.
+//
+// Borrows last until the reference's last use (non-lexical lifetimes), so `let r3 = &mut
+// data;` after the last use of `r1` is fine, as it is for rustc. Commented-out examples
+// marked as compile errors, like
+//   // println!("{}", s1_owner); // COMPILE ERROR! `s1_owner` value was moved.
+// are played as "what if" steps on a copy of the state, and the model reports the error
+// it finds (E0382, E0499, E0502, ...). The tests confirm it finds one for every such
+// example in the level 2 lessons, and none in the code that really runs.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::process;
+
+// --- Reading the lesson ---
+
+// `code // comment` -> (code, comment), ignoring `//` inside string literals.
+fn split_comment(line: &str) -> (&str, &str) {
+    let bytes = line.as_bytes();
+    let mut in_string = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'/' if !in_string && bytes.get(i + 1) == Some(&b'/') => {
+                return (line[..i].trim_end(), line[i + 2..].trim());
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    (line.trim_end(), "")
+}
+
+// The code with every string literal emptied, so braces and names inside them don't count.
+fn strip_strings(code: &str) -> String {
+    let mut out = String::new();
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if c == '"' {
+            while let Some(d) = chars.next() {
+                if d == '\\' {
+                    chars.next();
+                } else if d == '"' {
+                    out.push('"');
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+// Names used as values: not method or field names (`.len`), paths (`String::`), macros or calls.
+fn value_names(code: &str) -> Vec<String> {
+    let code = strip_strings(code);
+    let chars: Vec<char> = code.chars().collect();
+    let mut names = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_alphabetic() || chars[i] == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let before = if start > 0 { chars[start - 1] } else { ' ' };
+            let after = chars.get(i).copied().unwrap_or(' ');
+            let path = chars.get(i + 1) == Some(&':') && after == ':';
+            if before != '.' && before != ':' && before != '\'' && after != '!' && after != '(' && !path {
+                names.push(chars[start..i].iter().collect());
+            }
+        } else if chars[i].is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1; // `5u8`, `0x1F`
+            }
+        } else {
+            i += 1;
+        }
+    }
+    names
+}
+
+fn is_name(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn uses_name(code: &str, name: &str) -> bool {
+    value_names(code).iter().any(|n| n == name)
+}
+
+// Splits at top-level commas: "a, f(b, c), &d" -> ["a", "f(b, c)", "&d"].
+fn split_args(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let (mut depth, mut in_string, mut current) = (0, false, String::new());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                current.push(c);
+                if let Some(d) = chars.next() {
+                    current.push(d);
+                }
+                continue;
+            }
+            '"' => in_string = !in_string,
+            '(' | '[' | '{' if !in_string => depth += 1,
+            ')' | ']' | '}' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    parts
+}
+
+// `alloc_line!(EXPR)` (Day001.002.rs) is just EXPR to this tool.
+fn unwrap_macro(expr: &str) -> &str {
+    match expr.strip_prefix("alloc_line!(").and_then(|e| e.strip_suffix(')')) {
+        Some(inner) => inner.trim(),
+        None => expr,
+    }
+}
+
+fn string_literal(expr: &str) -> Option<String> {
+    let inner = expr.strip_prefix('"')?.strip_suffix('"')?;
+    Some(inner.replace("\\\"", "\"").replace("\\n", "\n"))
+}
+
+#[derive(Clone)]
+struct Param {
+    name: String,
+    ty: String,
+}
+
+#[derive(Clone)]
+struct FnDef {
+    name: String,
+    line: usize,
+    params: Vec<Param>,
+    returns: String,           // "" for `()`
+    body: Vec<(usize, String)>, // (line number, raw line)
+    straight: bool,            // No branches or loops: its body can be stepped through.
+    tail: Option<String>,      // The final expression, e.g. `some_string`.
+}
+
+// Every `fn` in the file, at any indentation (the level 2 lessons nest their helpers).
+fn find_functions(lines: &[&str]) -> Vec<FnDef> {
+    let mut found = Vec::new();
+    for (i, raw) in lines.iter().enumerate() {
+        let (code, _) = split_comment(raw);
+        let trimmed = code.trim_start();
+        let Some(rest) = trimmed.strip_prefix("fn ").or_else(|| trimmed.strip_prefix("pub fn ")) else { continue };
+        let name: String = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+        let Some(open) = rest.find('(') else { continue };
+        let mut depth = 0;
+        let Some(close) = rest[open..].find(|c| {
+            depth += (c == '(') as i32 - (c == ')') as i32;
+            depth == 0
+        }).map(|i| open + i) else { continue };
+        let params = split_args(&rest[open + 1..close])
+            .iter()
+            .filter_map(|p| {
+                let (name, ty) = p.split_once(':')?;
+                Some(Param { name: name.trim().trim_start_matches("mut ").to_string(), ty: ty.trim().to_string() })
+            })
+            .collect();
+        let after = &rest[close + 1..];
+        let returns = after.split_once("->").map(|(_, r)| r.split('{').next().unwrap_or("").trim().to_string()).unwrap_or_default();
+
+        let mut depth = 0i32;
+        let mut end = i;
+        for (j, l) in lines.iter().enumerate().skip(i) {
+            let stripped = strip_strings(split_comment(l).0);
+            depth += stripped.matches('{').count() as i32 - stripped.matches('}').count() as i32;
+            if depth <= 0 && stripped.contains('}') {
+                end = j;
+                break;
+            }
+        }
+        // `alloc_tracker::` lines (Day001.002.rs) measure the lesson and are not part of it.
+        let body: Vec<(usize, String)> = (i + 1..end)
+            .filter(|&j| !split_comment(lines[j]).0.contains("alloc_tracker::"))
+            .map(|j| (j + 1, lines[j].to_string()))
+            .collect();
+        let codes: Vec<&str> = body.iter().map(|(_, l)| split_comment(l).0.trim()).filter(|c| !c.is_empty()).collect();
+        let straight = !codes.iter().any(|c| {
+            ["for ", "while ", "loop ", "if ", "match ", "return", "else"].iter().any(|k| c.starts_with(k)) || c.contains('?')
+        });
+        let tail = codes.last().filter(|c| !c.ends_with(';') && !c.ends_with('}') && !c.ends_with('{')).map(|c| c.to_string());
+        found.push(FnDef { name, line: i + 1, params, returns, body, straight, tail });
+    }
+    found
+}
+
+// The lines a function runs, in order. A commented-out example run ends up as `WhatIf`.
+enum Unit {
+    Real(usize, String),
+    WhatIf(Vec<(usize, String)>), // Lines with the leading `// ` removed.
+}
+
+fn looks_like_code(text: &str) -> bool {
+    let (code, _) = split_comment(text);
+    let code = code.trim();
+    let first: String = code.chars().take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '!').collect();
+    let next = code[first.len()..].chars().next();
+    let statement = (code.starts_with("let ") || (!first.is_empty() && matches!(next, Some('(') | Some('.'))))
+        && (code.ends_with(';') || code.ends_with('{') || code.ends_with('}'));
+    statement || (code.starts_with("fn ") && code.ends_with('{')) || code == "}"
+}
+
+fn units_of(body: &[(usize, String)]) -> Vec<Unit> {
+    let mut units = Vec::new();
+    let mut i = 0;
+    while i < body.len() {
+        let (no, raw) = &body[i];
+        let trimmed = raw.trim_start();
+        let code = split_comment(raw).0.trim();
+        if code.starts_with("fn ") {
+            // A nested helper: its body runs only when it is called.
+            let mut depth = 0i32;
+            while i < body.len() {
+                let stripped = strip_strings(split_comment(&body[i].1).0);
+                depth += stripped.matches('{').count() as i32 - stripped.matches('}').count() as i32;
+                i += 1;
+                if depth <= 0 && stripped.contains('}') {
+                    break;
+                }
+            }
+            continue;
+        }
+        if let Some(content) = trimmed.strip_prefix("//") {
+            let content = content.strip_prefix(' ').unwrap_or(content);
+            if looks_like_code(content) && content.trim() != "}" {
+                let mut block = Vec::new();
+                let mut marked = false;
+                while i < body.len() {
+                    let Some(c) = body[i].1.trim_start().strip_prefix("//") else { break };
+                    let c = c.strip_prefix(' ').unwrap_or(c);
+                    if looks_like_code(c) {
+                        block.push((body[i].0, c.to_string()));
+                    } else if !c.trim_start().starts_with("//") {
+                        break; // Prose, not a continuation comment.
+                    }
+                    marked |= c.contains("ERROR");
+                    i += 1;
+                }
+                if marked {
+                    units.push(Unit::WhatIf(block));
+                }
+                continue;
+            }
+        } else if !code.is_empty() {
+            units.push(Unit::Real(*no, raw.clone()));
+        }
+        i += 1;
+    }
+    units
+}
+
+// --- The model ---
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Owner(usize),                                                  // Owns heap block #n.
+    Copy(String),                                                  // Plain data on the stack.
+    Static(String),                                                // A `&'static str` literal.
+    Ref { frame: usize, target: String, mutable: bool, last_use: usize },
+    Other(String),                                                 // Something this tool doesn't model.
+}
+
+#[derive(Clone, Debug)]
+struct Slot {
+    name: String,
+    value: Value,
+    mutable: bool,
+    moved_to: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct Frame {
+    function: String,
+    called_from: Option<usize>,
+    slots: Vec<Slot>,
+    scopes: Vec<usize>,  // Number of slots when each open block started.
+    gone: Vec<String>,   // Names dropped at the end of a block.
+}
+
+#[derive(Clone, Debug)]
+struct Block {
+    text: String,
+    freed: bool,
+}
+
+#[derive(Clone, Debug)]
+struct State {
+    frames: Vec<Frame>,
+    heap: Vec<Block>, // Block #n is heap[n - 1].
+}
+
+struct Step {
+    line: usize,
+    source: String,
+    notes: Vec<String>,
+    error: Option<String>,
+    what_if: bool,
+    now: usize,
+    state: State,
+}
+
+const MUTATING_METHODS: [&str; 9] = ["push_str", "push", "clear", "truncate", "insert", "insert_str", "pop", "remove", "retain"];
+
+struct Viz<'a> {
+    functions: &'a [FnDef],
+    state: State,
+    steps: Vec<Step>,
+    current_line: String, // The statement being run, shown with the steps of a call it makes.
+}
+
+impl Viz<'_> {
+    fn frame(&self) -> &Frame {
+        self.state.frames.last().expect("a frame is always open")
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame {
+        self.state.frames.last_mut().expect("a frame is always open")
+    }
+
+    fn slot(&self, frame: usize, name: &str) -> Option<&Slot> {
+        self.state.frames.get(frame)?.slots.iter().rev().find(|s| s.name == name)
+    }
+
+    fn slot_mut(&mut self, frame: usize, name: &str) -> Option<&mut Slot> {
+        self.state.frames.get_mut(frame)?.slots.iter_mut().rev().find(|s| s.name == name)
+    }
+
+    fn allocate(&mut self, text: String) -> usize {
+        self.state.heap.push(Block { text, freed: false });
+        self.state.heap.len()
+    }
+
+    // The block a binding's data lives in, following references.
+    fn block_of(&self, frame: usize, name: &str) -> Option<usize> {
+        match &self.slot(frame, name)?.value {
+            Value::Owner(block) => Some(*block),
+            Value::Ref { frame, target, .. } => self.block_of(*frame, target),
+            _ => None,
+        }
+    }
+
+    // References in the current frame to `name` that are still used at or after `now`.
+    fn live_borrows(&self, name: &str, now: usize) -> Vec<(String, bool, usize)> {
+        let here = self.state.frames.len() - 1;
+        self.frame()
+            .slots
+            .iter()
+            .filter_map(|s| match &s.value {
+                Value::Ref { frame, target, mutable, last_use } if *frame == here && target == name && *last_use >= now && s.moved_to.is_none() => {
+                    Some((s.name.clone(), *mutable, *last_use))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn check_borrow(&self, name: &str, mutable: bool, now: usize) -> Result<(), String> {
+        let here = self.state.frames.len() - 1;
+        let Some(slot) = self.slot(here, name) else { return Ok(()) };
+        if let Some(to) = &slot.moved_to {
+            return Err(format!("error[E0382]: borrow of moved value: `{}` (it was moved to {})", name, to));
+        }
+        let live = self.live_borrows(name, now);
+        if mutable {
+            if let Some((r, _, last)) = live.iter().find(|(_, m, _)| *m) {
+                return Err(format!("error[E0499]: cannot borrow `{}` as mutable more than once at a time (`{}` is still used on line {})", name, r, last));
+            }
+            if let Some((r, _, last)) = live.first() {
+                return Err(format!("error[E0502]: cannot borrow `{}` as mutable because it is also borrowed as immutable (`{}` is still used on line {})", name, r, last));
+            }
+            if let Value::Ref { mutable: false, .. } = slot.value {
+                return Err(format!("error[E0596]: cannot borrow `*{}` as mutable, as it is behind a `&` reference", name));
+            }
+            if !slot.mutable && !matches!(slot.value, Value::Ref { mutable: true, .. }) {
+                return Err(format!("error[E0596]: cannot borrow `{}` as mutable, as it is not declared as mutable", name));
+            }
+        } else if let Some((r, _, last)) = live.iter().find(|(_, m, _)| *m) {
+            return Err(format!("error[E0502]: cannot borrow `{}` as immutable because it is also borrowed as mutable (`{}` is still used on line {})", name, r, last));
+        }
+        Ok(())
+    }
+
+    fn check_move(&self, name: &str, now: usize) -> Result<(), String> {
+        let here = self.state.frames.len() - 1;
+        let Some(slot) = self.slot(here, name) else { return Ok(()) };
+        if let Some(to) = &slot.moved_to {
+            return Err(format!("error[E0382]: use of moved value: `{}` (it was moved to {})", name, to));
+        }
+        if let Some((r, _, last)) = self.live_borrows(name, now).first() {
+            return Err(format!("error[E0505]: cannot move out of `{}` because it is borrowed (`{}` is still used on line {})", name, r, last));
+        }
+        Ok(())
+    }
+
+    // Every other mention of a binding in `code`: reading a moved-from or out-of-scope name.
+    fn check_uses(&self, code: &str, skip: &[String], now: usize) -> Result<(), String> {
+        let here = self.state.frames.len() - 1;
+        for name in value_names(code) {
+            if skip.contains(&name) {
+                continue;
+            }
+            match self.slot(here, &name) {
+                Some(slot) => {
+                    if let Some(to) = &slot.moved_to {
+                        return Err(format!("error[E0382]: borrow of moved value: `{}` (it was moved to {})", name, to));
+                    }
+                    if let Some((r, _, last)) = self.live_borrows(&name, now).iter().find(|(r, m, _)| *m && *r != name) {
+                        return Err(format!("error[E0502]: cannot borrow `{}` as immutable because it is also borrowed as mutable (`{}` is still used on line {})", name, r, last));
+                    }
+                }
+                None if self.frame().gone.contains(&name) => {
+                    return Err(format!("error[E0425]: cannot find value `{}` in this scope (it was dropped at the end of its block)", name));
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Last line in `context` after `now` that mentions `name`; `now` if there is none.
+    fn last_use(name: &str, now: usize, context: &[(usize, String)]) -> usize {
+        context.iter().filter(|(no, code)| *no > now && uses_name(code, name)).map(|(no, _)| *no).max().unwrap_or(now)
+    }
+
+    // Evaluates the right-hand side of a `let` (or a call argument) into a value, checking
+    // and applying moves and borrows. `to` names the destination, for "moved to" notes.
+    fn eval(&mut self, expr: &str, to: &str, now: usize, context: &[(usize, String)], notes: &mut Vec<String>) -> Result<Value, String> {
+        let expr = unwrap_macro(expr.trim());
+        let here = self.state.frames.len() - 1;
+        if let Some(text) = expr
+            .strip_prefix("String::from(")
+            .and_then(|e| e.strip_suffix(')'))
+            .and_then(string_literal)
+            .or_else(|| expr.strip_suffix(".to_string()").and_then(string_literal))
+            .or_else(|| expr.strip_suffix(".to_owned()").and_then(string_literal))
+            .or_else(|| (expr == "String::new()").then(String::new))
+        {
+            let block = self.allocate(text);
+            notes.push(format!("allocates block #{} on the heap; {} owns it", block, to));
+            return Ok(Value::Owner(block));
+        }
+        if let Some(text) = string_literal(expr) {
+            return Ok(Value::Static(text));
+        }
+        if let Some(source) = expr.strip_suffix(".clone()").filter(|s| is_name(s)) {
+            self.check_borrow(source, false, now)?;
+            if let Some(original) = self.block_of(here, source) {
+                let text = self.state.heap[original - 1].text.clone();
+                let block = self.allocate(text);
+                notes.push(format!("clone: allocates block #{}, a copy of #{}; {} is untouched", block, original, source));
+                return Ok(Value::Owner(block));
+            }
+        }
+        let (mutable, rest) = match (expr.strip_prefix("&mut "), expr.strip_prefix('&')) {
+            (Some(rest), _) => (true, rest),
+            (None, Some(rest)) => (false, rest),
+            _ => (false, ""),
+        };
+        if !rest.is_empty() {
+            let target = rest.split('[').next().unwrap_or("").trim();
+            if is_name(target) && self.slot(here, target).is_some() {
+                self.check_borrow(target, mutable, now)?;
+                let slice = if rest.contains('[') { " (a slice of it)" } else { "" };
+                let last_use = Self::last_use(to, now, context);
+                notes.push(format!("{} borrows {}{}{}", to, if mutable { "&mut " } else { "&" }, target, slice));
+                return Ok(Value::Ref { frame: here, target: target.to_string(), mutable, last_use });
+            }
+        }
+        if is_name(expr) {
+            if let Some(slot) = self.slot(here, expr).cloned() {
+                return match slot.value {
+                    Value::Owner(_) | Value::Ref { mutable: true, .. } => {
+                        self.check_move(expr, now)?;
+                        let value = slot.value.clone();
+                        self.slot_mut(here, expr).expect("just found").moved_to = Some(to.to_string());
+                        let what = if let Value::Owner(b) = value { format!("block #{}", b) } else { "the &mut borrow".to_string() };
+                        notes.push(format!("move: {} -> {} (pointer, length and capacity are copied; {} stays where it is); {} can't be used any more", expr, to, what, expr));
+                        Ok(match value {
+                            Value::Ref { frame, target, mutable, .. } => Value::Ref { frame, target, mutable, last_use: Self::last_use(to, now, context) },
+                            v => v,
+                        })
+                    }
+                    Value::Ref { frame, target, mutable: false, .. } => {
+                        self.check_uses(expr, &[], now)?;
+                        notes.push(format!("copies the shared reference: {} also borrows {}", to, target));
+                        Ok(Value::Ref { frame, target, mutable: false, last_use: Self::last_use(to, now, context) })
+                    }
+                    Value::Copy(v) => {
+                        self.check_uses(expr, &[], now)?;
+                        notes.push(format!("copy: {} gets its own copy of {} ({} is Copy, so {} stays valid)", to, v, expr, expr));
+                        Ok(Value::Copy(v))
+                    }
+                    other => {
+                        self.check_uses(expr, &[], now)?;
+                        Ok(other)
+                    }
+                };
+            }
+        }
+        let literal = expr.chars().all(|c| c.is_alphanumeric() || " _.,-()[]'".contains(c)) && !expr.chars().any(|c| c.is_alphabetic() && !"eEuifxb".contains(c)) || expr == "true" || expr == "false";
+        if literal {
+            return Ok(Value::Copy(expr.to_string()));
+        }
+        if let Some((name, args)) = expr.strip_suffix(')').and_then(|e| e.split_once('(')).filter(|(n, _)| is_name(n)) {
+            if let Some(def) = self.find_function(name, now) {
+                return self.call(&def, args, now, context);
+            }
+        }
+        self.check_uses(expr, &[], now)?;
+        Ok(Value::Other(expr.to_string()))
+    }
+
+    // The definition nearest to the call, so nested helpers with common names resolve right.
+    fn find_function(&self, name: &str, now: usize) -> Option<FnDef> {
+        self.functions.iter().filter(|f| f.name == name).min_by_key(|f| f.line.abs_diff(now)).cloned()
+    }
+
+    fn call(&mut self, def: &FnDef, args: &str, now: usize, context: &[(usize, String)]) -> Result<Value, String> {
+        let args = split_args(args);
+        let mut notes = Vec::new();
+        let mut bound = Vec::new();
+        for (param, arg) in def.params.iter().zip(&args) {
+            let label = format!("`{}` of {}()", param.name, def.name);
+            let value = match self.eval(arg, &label, now, context, &mut notes)? {
+                Value::Ref { frame, target, mutable, .. } => Value::Ref { frame, target, mutable, last_use: usize::MAX }, // Borrowed for the whole call.
+                v => v,
+            };
+            bound.push(Slot { name: param.name.clone(), value, mutable: param.ty.starts_with("&mut"), moved_to: None });
+        }
+        self.state.frames.push(Frame { function: def.name.clone(), called_from: Some(now), slots: bound, scopes: Vec::new(), gone: Vec::new() });
+        notes.insert(0, format!("call {}(): a new stack frame on top", def.name));
+        if !def.straight {
+            notes.push(format!("(the body of {}() has branches or loops and is not stepped through)", def.name));
+        }
+        let source = self.current_line.clone();
+        self.push_step(now, &source, notes, None, false);
+        if def.straight && self.state.frames.len() < 6 {
+            let body = def.body.clone();
+            self.run(&body);
+        }
+        // The return value: a local that is handed back, a borrow of a reference argument, or opaque.
+        let here = self.state.frames.len() - 1;
+        let mut result = Value::Other(format!("{}(..)", def.name));
+        let mut returned = None;
+        if let Some(tail) = def.tail.as_deref().map(|t| split_comment(t).0.trim()) {
+            if let Some(slot) = self.slot(here, tail).cloned() {
+                if matches!(slot.value, Value::Owner(_)) {
+                    self.slot_mut(here, tail).expect("just found").moved_to = Some("the caller".to_string());
+                    returned = Some(tail.to_string());
+                    result = slot.value;
+                }
+            }
+        }
+        if def.returns.starts_with('&') {
+            let refs: Vec<&Slot> = self.frame().slots.iter().filter(|s| matches!(s.value, Value::Ref { .. } | Value::Static(_))).collect();
+            if let [only] = refs.as_slice() {
+                result = only.value.clone(); // Lifetime elision: the result borrows from the only reference argument.
+            }
+        } else if def.returns == "String" && returned.is_none() && !def.straight {
+            let block = self.allocate("?".to_string());
+            result = Value::Owner(block);
+        }
+        let mut notes = vec![format!("return from {}(): its frame is popped", def.name)];
+        if let Some(name) = returned {
+            notes.push(format!("ownership of {}'s data moves out to the caller", name));
+        }
+        let frame = self.state.frames.pop().expect("pushed above");
+        notes.extend(self.drop_slots(frame.slots));
+        let result = match result {
+            Value::Ref { frame, target, mutable, .. } => Value::Ref { frame, target, mutable, last_use: usize::MAX },
+            v => v,
+        };
+        self.push_step(now, &format!("{}() returns", def.name), notes, None, false);
+        Ok(result)
+    }
+
+    // Drops bindings in reverse order of declaration, freeing the blocks they still own.
+    fn drop_slots(&mut self, slots: Vec<Slot>) -> Vec<String> {
+        let mut notes = Vec::new();
+        for slot in slots.into_iter().rev() {
+            match (&slot.value, &slot.moved_to) {
+                (Value::Owner(block), None) => {
+                    self.state.heap[block - 1].freed = true;
+                    notes.push(format!("drop {}: frees block #{}", slot.name, block));
+                }
+                (Value::Owner(_), Some(to)) => notes.push(format!("{} was moved to {}: nothing to free", slot.name, to)),
+                (Value::Ref { .. }, _) => notes.push(format!("{} goes away; the data it borrowed is not freed", slot.name)),
+                _ => {}
+            }
+        }
+        notes
+    }
+
+    fn exec(&mut self, now: usize, raw: &str, context: &[(usize, String)], what_if: bool) -> Result<(), String> {
+        let (code, _) = split_comment(raw);
+        let code = code.trim();
+        let mut notes = Vec::new();
+        self.current_line = raw.trim().to_string();
+        let statement = unwrap_macro(code.trim_end_matches(';')).trim();
+        let outcome = if code.starts_with('}') {
+            let start = self.frame_mut().scopes.pop().unwrap_or(0);
+            let dropped: Vec<Slot> = self.frame_mut().slots.drain(start..).collect();
+            let names: Vec<String> = dropped.iter().map(|s| s.name.clone()).collect();
+            self.frame_mut().gone.extend(names);
+            notes.push("end of block".to_string());
+            notes.extend(self.drop_slots(dropped));
+            Ok(())
+        } else if code.ends_with('{') {
+            let count = self.frame().slots.len();
+            self.frame_mut().scopes.push(count);
+            Ok(())
+        } else if let Some(rest) = statement.strip_prefix("let ") {
+            self.exec_let(rest, now, context, &mut notes)
+        } else if let Some((receiver, call)) = statement.split_once('.').filter(|(r, _)| is_name(r)) {
+            let method: String = call.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+            let args = call.strip_prefix(method.as_str()).and_then(|a| a.strip_prefix('(')).and_then(|a| a.strip_suffix(')')).unwrap_or("");
+            self.exec_method(receiver, &method, args, now, &mut notes)
+        } else if let Some((name, args)) = statement.strip_suffix(')').and_then(|s| s.split_once('(')).filter(|(n, _)| is_name(n)) {
+            match self.find_function(name, now) {
+                Some(def) => self.call(&def, args, now, context).map(|_| ()),
+                None => self.check_uses(statement, &[], now),
+            }
+        } else {
+            self.check_uses(statement, &[], now)
+        };
+        let error = outcome.as_ref().err().cloned();
+        if !notes.is_empty() || error.is_some() || what_if {
+            self.push_step(now, raw.trim(), notes, error, what_if);
+        }
+        outcome
+    }
+
+    fn exec_let(&mut self, rest: &str, now: usize, context: &[(usize, String)], notes: &mut Vec<String>) -> Result<(), String> {
+        let Some((pattern, expr)) = rest.split_once('=') else { return Ok(()) };
+        let pattern = pattern.split(':').next().unwrap_or("").trim();
+        let (mutable, name) = match pattern.strip_prefix("mut ") {
+            Some(name) => (true, name.trim()),
+            None => (false, pattern),
+        };
+        if !is_name(name) {
+            return self.check_uses(expr, &[], now); // Destructuring: not modelled.
+        }
+        let value = self.eval(expr, name, now, context, notes)?;
+        if let Value::Copy(v) | Value::Static(v) = &value {
+            if notes.is_empty() {
+                notes.push(format!("{} = {} (on the stack{})", name, v, if matches!(value, Value::Static(_)) { "; the text is in the program binary" } else { "" }));
+            }
+        }
+        self.frame_mut().slots.push(Slot { name: name.to_string(), value, mutable, moved_to: None });
+        Ok(())
+    }
+
+    fn exec_method(&mut self, receiver: &str, method: &str, args: &str, now: usize, notes: &mut Vec<String>) -> Result<(), String> {
+        let here = self.state.frames.len() - 1;
+        if self.slot(here, receiver).is_none() {
+            return self.check_uses(args, &[], now);
+        }
+        if !MUTATING_METHODS.contains(&method) {
+            return self.check_uses(&format!("{} {}", receiver, args), &[], now);
+        }
+        self.check_borrow(receiver, true, now)?;
+        self.check_uses(args, &[], now)?;
+        let Some(block) = self.block_of(here, receiver) else { return Ok(()) };
+        let arg = split_args(args).first().cloned().unwrap_or_default();
+        let text = &mut self.state.heap[block - 1].text;
+        match (method, string_literal(&arg)) {
+            ("push_str", Some(s)) => text.push_str(&s),
+            ("push", _) if arg.len() >= 3 && arg.starts_with('\'') => text.push_str(&arg[1..arg.len() - 1]),
+            ("clear", _) => text.clear(),
+            _ => {}
+        }
+        let through = if matches!(self.slot(here, receiver).map(|s| &s.value), Some(Value::Ref { .. })) { " through the &mut borrow" } else { "" };
+        notes.push(format!("{}.{}() changes block #{}{}", receiver, method, block, through));
+        Ok(())
+    }
+
+    fn push_step(&mut self, now: usize, source: &str, notes: Vec<String>, error: Option<String>, what_if: bool) {
+        self.steps.push(Step { line: now, source: source.to_string(), notes, error, what_if, now, state: self.state.clone() });
+    }
+
+    // A commented-out helper, like `// fn try_change_immutable(s_ref: &String) {`, gets a frame
+    // whose reference parameters borrow "a value in the caller". Returns true if `line` was
+    // its signature or its closing brace.
+    fn enter_or_leave_commented_fn(&mut self, now: usize, line: &str, base: usize) -> bool {
+        let code = split_comment(line).0.trim();
+        if code == "}" && self.state.frames.len() > base {
+            self.state.frames.pop();
+            return true;
+        }
+        let Some(def) = find_functions(&[code]).pop() else { return false };
+        let slots = def
+            .params
+            .iter()
+            .map(|p| {
+                let value = match p.ty.strip_prefix('&') {
+                    Some(rest) => Value::Ref { frame: usize::MAX, target: "a value in the caller".to_string(), mutable: rest.starts_with("mut "), last_use: usize::MAX },
+                    None => Value::Other("an argument".to_string()),
+                };
+                Slot { name: p.name.clone(), value, mutable: false, moved_to: None }
+            })
+            .collect();
+        self.state.frames.push(Frame { function: def.name.clone(), called_from: None, slots, scopes: Vec::new(), gone: Vec::new() });
+        self.push_step(now, line.trim(), vec![format!("if {}() existed and were called: a new stack frame with its parameters", def.name)], None, true);
+        true
+    }
+
+    fn run(&mut self, body: &[(usize, String)]) {
+        let units = units_of(body);
+        let context: Vec<(usize, String)> = units
+            .iter()
+            .filter_map(|u| match u {
+                Unit::Real(no, raw) => Some((*no, split_comment(raw).0.to_string())),
+                Unit::WhatIf(_) => None,
+            })
+            .collect();
+        for unit in units {
+            match unit {
+                Unit::Real(no, raw) => {
+                    let _ = self.exec(no, &raw, &context, false); // An error is recorded in its step.
+                }
+                Unit::WhatIf(lines) => {
+                    let saved = self.state.clone();
+                    let what_if_context: Vec<(usize, String)> = lines.iter().map(|(no, l)| (*no, split_comment(l).0.to_string())).collect();
+                    let mut failed = false;
+                    for (no, line) in &lines {
+                        if self.enter_or_leave_commented_fn(*no, line, saved.frames.len()) {
+                            continue;
+                        }
+                        if self.exec(*no, line, &what_if_context, true).is_err() {
+                            failed = true;
+                            break; // rustc would stop here: nothing after it compiles.
+                        }
+                    }
+                    if !failed {
+                        let last = lines.last().map(|(no, _)| *no).unwrap_or(0);
+                        self.push_step(last, "(end of the commented-out example)", vec!["the lesson says this does not compile, but the model found no error".to_string()], None, true);
+                    }
+                    self.state = saved;
+                }
+            }
+        }
+    }
+}
+
+fn visualize(source: &str, function: &str) -> Result<Vec<Step>, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let functions = find_functions(&lines);
+    let def = functions
+        .iter()
+        .filter(|f| f.name == function)
+        .min_by_key(|f| f.line)
+        .cloned()
+        .ok_or_else(|| format!("no function named {}", function))?;
+    let mut viz = Viz {
+        functions: &functions,
+        state: State { frames: vec![Frame { function: def.name.clone(), called_from: None, slots: Vec::new(), scopes: Vec::new(), gone: Vec::new() }], heap: Vec::new() },
+        steps: Vec::new(),
+        current_line: String::new(),
+    };
+    viz.run(&def.body);
+    let end = def.body.last().map(|(no, _)| no + 1).unwrap_or(def.line);
+    let frame = viz.state.frames.pop().expect("the function's frame");
+    let mut notes = vec![format!("end of {}(): bindings are dropped in reverse order of declaration", def.name)];
+    notes.extend(viz.drop_slots(frame.slots));
+    viz.state.frames.push(Frame { function: def.name.clone(), called_from: None, slots: Vec::new(), scopes: Vec::new(), gone: Vec::new() });
+    viz.push_step(end, "}", notes, None, false);
+    Ok(viz.steps)
+}
+
+// --- Drawing ---
+
+struct Row {
+    name: String,
+    detail: String,        // "owns", "& ----> s1", "= 5", ...
+    block: Option<usize>,  // Owned block, drawn on the same row.
+    target: Option<(usize, String)>,
+    mutable: bool,
+    dead: bool,            // Moved out of, or a borrow that is over.
+}
+
+fn rows_of(step: &Step) -> Vec<(Option<String>, Row)> {
+    let mut rows = Vec::new();
+    for (f, frame) in step.state.frames.iter().enumerate() {
+        let header = match frame.called_from {
+            Some(line) => format!("fn {} (called from line {})", frame.function, line),
+            None => format!("fn {}", frame.function),
+        };
+        rows.push((Some(header), Row { name: String::new(), detail: String::new(), block: None, target: None, mutable: false, dead: false }));
+        for slot in &frame.slots {
+            let mut row = Row { name: slot.name.clone(), detail: String::new(), block: None, target: None, mutable: false, dead: false };
+            match (&slot.value, &slot.moved_to) {
+                (_, Some(to)) => {
+                    row.detail = format!("xx moved to {}", to);
+                    row.dead = true;
+                }
+                (Value::Owner(block), None) => {
+                    row.detail = "owns".to_string();
+                    row.block = Some(*block);
+                }
+                (Value::Ref { frame: tf, target, mutable, last_use }, None) => {
+                    let arrow = if *mutable { "&mut" } else { "&" };
+                    let owner = match step.state.frames.get(*tf) {
+                        _ if *tf == f => target.clone(),
+                        Some(frame) => format!("{} in {}", target, frame.function),
+                        None => target.clone(),
+                    };
+                    row.detail = format!("{} ----> {}", arrow, owner);
+                    if *last_use < step.now {
+                        row.detail.push_str(&format!("  (borrow over: last used on line {})", last_use));
+                        row.dead = true;
+                    }
+                    row.target = Some((*tf, target.clone()));
+                    row.mutable = *mutable;
+                }
+                (Value::Copy(v), None) => row.detail = format!("= {}", v),
+                (Value::Static(v), None) => row.detail = format!("= {:?} (&'static str)", v),
+                (Value::Other(v), None) => row.detail = format!("= {}", v),
+            }
+            rows.push((None, row));
+        }
+    }
+    rows
+}
+
+fn render_ascii(step: &Step, number: usize) -> String {
+    let mut out = String::new();
+    let kind = if step.what_if { " | what if (commented out in the lesson)" } else { "" };
+    out.push_str(&format!("-- step {} | line {}{} --\n", number, step.line, kind));
+    out.push_str(&format!("  {:>4} | {}\n", step.line, step.source));
+    for note in &step.notes {
+        out.push_str(&format!("  * {}\n", note));
+    }
+    if let Some(error) = &step.error {
+        out.push_str(&format!("  !! {}\n", error));
+    }
+    let rows = rows_of(step);
+    let name_width = rows.iter().map(|(_, r)| r.name.chars().count()).max().unwrap_or(0).max(12);
+    let left_of = |r: &Row| format!("  {:<w$} {}", r.name, r.detail, w = name_width);
+    let width = rows.iter().filter(|(_, r)| r.block.is_some()).map(|(_, r)| left_of(r).chars().count()).max().unwrap_or(0).max(24);
+    out.push_str(&format!("\n    {:<w$}     HEAP\n", "STACK", w = width));
+    for (header, row) in rows {
+        if let Some(h) = header {
+            out.push_str(&format!("    {}\n", h));
+            continue;
+        }
+        let left = left_of(&row);
+        match row.block {
+            Some(block) => {
+                let b = &step.state.heap[block - 1];
+                let dashes = "-".repeat(width.saturating_sub(left.chars().count()) + 2);
+                out.push_str(&format!("    {} {}> #{} {:?} (len {})\n", left, dashes, block, b.text, b.text.len()));
+            }
+            None => out.push_str(&format!("    {}\n", left.trim_end())),
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// All steps, one panel under the other. Stack on the left, heap on the right; owner arrows
+// are black, shared borrows blue, mutable borrows red, finished or moved ones grey and dashed.
+fn render_svg(steps: &[Step], title: &str) -> String {
+    const ROW: i32 = 26;
+    const STACK_X: i32 = 70;
+    const SLOT_W: i32 = 330;
+    const HEAP_X: i32 = 520;
+    const HEAP_W: i32 = 300;
+    let mut body = String::new();
+    let mut y = 40;
+    for (i, step) in steps.iter().enumerate() {
+        let color = if step.error.is_some() { "#b00020" } else { "#222" };
+        let kind = if step.what_if { " (what if: commented out in the lesson)" } else { "" };
+        body.push_str(&format!("<text x=\"10\" y=\"{}\" font-weight=\"bold\">step {} · line {}{}</text>\n", y, i + 1, step.line, kind));
+        y += 18;
+        body.push_str(&format!("<text x=\"10\" y=\"{}\" fill=\"#555\">{}</text>\n", y, escape(&step.source)));
+        for note in &step.notes {
+            y += 16;
+            body.push_str(&format!("<text x=\"20\" y=\"{}\">• {}</text>\n", y, escape(note)));
+        }
+        if let Some(error) = &step.error {
+            y += 16;
+            body.push_str(&format!("<text x=\"20\" y=\"{}\" fill=\"{}\">{}</text>\n", y, color, escape(error)));
+        }
+        y += 14;
+        let mut positions: BTreeMap<(usize, String), i32> = BTreeMap::new();
+        let rows = rows_of(step);
+        let mut frame = 0usize;
+        let mut placed = Vec::new();
+        for (header, row) in rows {
+            y += ROW;
+            if let Some(h) = header {
+                body.push_str(&format!("<text x=\"{}\" y=\"{}\" font-style=\"italic\">{}</text>\n", STACK_X, y - 6, escape(&h)));
+                frame = step.state.frames.iter().position(|f| h.starts_with(&format!("fn {}", f.function))).unwrap_or(frame);
+                continue;
+            }
+            positions.insert((frame, row.name.clone()), y - ROW / 2 - 2);
+            let fill = if row.dead { "#eee" } else { "#fff" };
+            body.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#333\"/>\n", STACK_X, y - ROW + 4, SLOT_W, ROW - 4, fill));
+            let decoration = if row.dead { " text-decoration=\"line-through\" fill=\"#888\"" } else { "" };
+            body.push_str(&format!("<text x=\"{}\" y=\"{}\"{}>{} {}</text>\n", STACK_X + 6, y - 8, decoration, escape(&row.name), escape(&row.detail)));
+            if let Some(block) = row.block {
+                let b = &step.state.heap[block - 1];
+                body.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#fffbe6\" stroke=\"#a80\"/>\n", HEAP_X, y - ROW + 4, HEAP_W, ROW - 4));
+                body.push_str(&format!("<text x=\"{}\" y=\"{}\">#{} {}</text>\n", HEAP_X + 6, y - 8, block, escape(&format!("{:?}", b.text))));
+                body.push_str(&format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#222\" marker-end=\"url(#arrow)\"/>\n", STACK_X + SLOT_W, y - ROW / 2 + 2, HEAP_X - 2, y - ROW / 2 + 2));
+            }
+            placed.push((frame, y - ROW / 2 + 2, row));
+        }
+        for (_, from_y, row) in &placed {
+            let Some(target) = &row.target else { continue };
+            let Some(to_y) = positions.get(target).map(|t| t + 4) else { continue };
+            let stroke = if row.dead { "#999\" stroke-dasharray=\"4 3" } else if row.mutable { "#c00" } else { "#06c" };
+            let bend = STACK_X - 40 - (from_y - to_y).abs() / 8;
+            body.push_str(&format!(
+                "<path d=\"M {x} {a} C {b} {a}, {b} {c}, {x} {c}\" fill=\"none\" stroke=\"{s}\" marker-end=\"url(#arrow)\"/>\n",
+                x = STACK_X - 2, a = from_y, b = bend.max(4), c = to_y, s = stroke
+            ));
+        }
+        y += 30;
+        body.push_str(&format!("<line x1=\"0\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#ccc\"/>\n", y - 12, HEAP_X + HEAP_W + 20, y - 12));
+        y += 16;
+    }
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" font-family=\"monospace\" font-size=\"12\">\n\
+         <defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"9\" refY=\"5\" markerWidth=\"7\" markerHeight=\"7\" orient=\"auto\">\
+         <path d=\"M 0 0 L 10 5 L 0 10 z\"/></marker></defs>\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n<text x=\"10\" y=\"20\" font-size=\"14\" font-weight=\"bold\">{t}</text>\n{b}</svg>\n",
+        w = HEAP_X + HEAP_W + 20,
+        h = y,
+        t = escape(title),
+        b = body
+    )
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let svg = args.iter().position(|a| a == "--svg").and_then(|i| args.get(i + 1)).cloned();
+    let positional: Vec<&String> = args.iter().enumerate().filter(|(i, a)| !a.starts_with("--") && (*i == 0 || args[i - 1] != "--svg")).map(|(_, a)| a).collect();
+    let [file, function] = positional.as_slice() else {
+        eprintln!("usage: ownership_viz FILE FUNCTION [--svg OUT.svg]");
+        process::exit(2);
+    };
+    let steps = match fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e)).and_then(|s| visualize(&s, function)) {
+        Ok(steps) => steps,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    match svg {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, render_svg(&steps, &format!("{} :: {}()", file, function))) {
+                eprintln!("{}: {}", path, e);
+                process::exit(2);
+            }
+            println!("wrote {} steps to {}", steps.len(), path);
+        }
+        None => {
+            for (i, step) in steps.iter().enumerate() {
+                println!("{}", render_ascii(step, i + 1));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    const FIXTURE: &str = r#"fn moves() {
+    let s1 = String::from("hello");
+    let s2 = s1;
+    // println!("{}", s1); // COMPILE ERROR: s1 was moved
+    println!("{}", s2);
+}
+
+fn borrows() {
+    let mut data = String::from("data");
+    let r1 = &data;
+    let r2 = &data;
+    println!("{} {}", r1, r2);
+    let r3 = &mut data;
+    r3.push_str("!");
+    // let a = &mut data;
+    // let b = &mut data; // ERROR: two mutable borrows
+    // println!("{} {}", a, b);
+}
+
+fn append(target: &mut String) {
+    target.push_str(" world");
+}
+
+fn calls() {
+    let mut s = String::from("hello");
+    append(&mut s);
+    {
+        let inner = String::from("inner");
+    }
+    // println!("{}", inner); // COMPILE ERROR: not in scope
+    let t = s.clone();
+}
+"#;
+
+    fn final_state_of(steps: &[Step], line: usize) -> Option<&State> {
+        steps.iter().rfind(|s| s.line == line && !s.what_if).map(|s| &s.state)
+    }
+
+    fn errors(steps: &[Step]) -> Vec<String> {
+        steps.iter().filter_map(|s| s.error.clone()).collect()
+    }
+
+    // A lesson file next to this one.
+    fn lesson(file: &str, function: &str) -> Vec<Step> {
+        let source = fs::read_to_string(Path::new(file!()).with_file_name(file)).unwrap();
+        visualize(&source, function).unwrap()
+    }
+
+    #[test]
+    fn a_move_marks_the_source_as_moved() {
+        let steps = visualize(FIXTURE, "moves").unwrap();
+        let after_move = final_state_of(&steps, 3).unwrap();
+        let slot = |name: &str| after_move.frames[0].slots.iter().find(|x| x.name == name).unwrap();
+        assert_eq!(slot("s1").moved_to.as_deref(), Some("s2"));
+        assert_eq!(slot("s2").value, Value::Owner(1)); // The same block.
+        assert!(errors(&steps).iter().any(|e| e.starts_with("error[E0382]")), "using s1 afterwards");
+        let frees = steps.last().unwrap().notes.iter().filter(|n| n.contains("frees block #1")).count();
+        assert_eq!(frees, 1, "the end of the function frees block #1 once");
+    }
+
+    #[test]
+    fn borrows_end_at_their_last_use() {
+        let steps = visualize(FIXTURE, "borrows").unwrap();
+        assert!(!steps.iter().any(|s| !s.what_if && s.error.is_some()), "&mut after the last use of the & borrows is fine (NLL)");
+        let errs = errors(&steps);
+        assert!(errs.len() == 1 && errs[0].starts_with("error[E0499]"), "two live &mut borrows: {:?}", errs);
+        assert!(steps.iter().any(|s| s.state.heap.first().is_some_and(|b| b.text == "data!")), "push_str through &mut");
+    }
+
+    #[test]
+    fn calls_blocks_and_clones() {
+        let steps = visualize(FIXTURE, "calls").unwrap();
+        assert!(steps.iter().any(|s| s.state.frames.len() == 2 && s.state.frames[1].function == "append"), "a call gets its own frame");
+        assert!(steps.iter().any(|s| s.state.heap.first().is_some_and(|b| b.text == "hello world")), "the callee's push_str reaches the caller");
+        assert!(steps.iter().any(|s| s.notes.iter().any(|n| n == "drop inner: frees block #2")), "the end of a block frees what it owned");
+        let errs = errors(&steps);
+        assert!(errs.len() == 1 && errs[0].starts_with("error[E0425]"), "a name used after its block: {:?}", errs);
+        assert!(steps.iter().any(|s| s.notes.iter().any(|n| n.starts_with("clone: allocates block #3"))), "clone allocates");
+    }
+
+    // The real lessons: no error in the code that runs, one in every commented-out example.
+    #[test]
+    fn level_2_lessons() {
+        let lessons = [
+            ("Day001.002.rs", "ownership_and_moves", 1),
+            ("Day001.002.rs", "references_and_borrowing", 1),
+            ("Day001.002.rs", "mutable_references", 0),
+            ("Day001.002.rs", "rules_of_references", 2),
+            ("Day001.003.rs", "level_2_ownership_borrowing", 7),
+        ];
+        for (file, function, expected) in lessons {
+            let steps = lesson(file, function);
+            let real: Vec<&String> = steps.iter().filter(|s| !s.what_if).filter_map(|s| s.error.as_ref()).collect();
+            let found = steps.iter().filter(|s| s.what_if && s.error.is_some()).count();
+            let missed = steps.iter().filter(|s| s.what_if && s.notes.iter().any(|n| n.contains("found no error"))).count();
+            assert!(real.is_empty(), "{} {}: errors in the real code {:?}", file, function, real);
+            assert_eq!((found, missed), (expected, 0), "{} {}: commented-out errors found, missed", file, function);
+        }
+        let steps = lesson("Day001.002.rs", "mutable_references");
+        assert!(steps.iter().any(|s| s.state.heap.first().is_some_and(|b| b.text == "initial text... and now it's changed!")));
+    }
+}
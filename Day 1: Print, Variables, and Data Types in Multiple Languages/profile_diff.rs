@@ -0,0 +1,453 @@
+// profile_diff.rs
+// Builds one lesson section under the debug and the release profile, runs both, and shows
+// their output side by side with the build and run times. Some lessons only make sense
+// next to each other this way: `255_u8 + 1` panics in debug and wraps to 0 in release,
+// `debug_assert!` disappears in release, and a timing loop can be orders of magnitude faster.
+//
+// Usage:
+//   rustc --edition 2021 profile_diff.rs -o target/profile_diff
+//   target/profile_diff Day001.003.rs                            # list the sections
+//   target/profile_diff Day001.003.rs level_13_build_profiles    # a section (or a unique prefix, like level_13_b)
+//   target/profile_diff --changes-only Day001.003.rs level_13_b  # ...only the lines that differ
+//
+// Tests (on a small fixture):
+//   rustc --edition 2021 --test profile_diff.rs -o target/profile_diff_test && target/profile_diff_test
+//
+// The profiles use the same flags as Cargo's `dev` and `release`:
+//   debug:   -C opt-level=0 -C debug-assertions=on  -C overflow-checks=on
+//   release: -C opt-level=3 -C debug-assertions=off -C overflow-checks=off
+//
+// A section is a top-level function with no arguments. The file is cut into its top-level
+// items, the lesson's own `main` is dropped (it may call sections that don't build, as in
+// ` Day001.001.rs`), and a new `main` calls just the chosen section. Like `diff`, it exits
+// 0 if both builds printed the same and exited the same way, 1 if not, 2 on errors.
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::time::{Duration, Instant};
+
+// --- Profiles ---
+
+struct Profile {
+    name: &'static str,
+    flags: &'static [&'static str],
+}
+
+const PROFILES: [Profile; 2] = [
+    Profile { name: "debug", flags: &["-C", "opt-level=0", "-C", "debug-assertions=on", "-C", "overflow-checks=on"] },
+    Profile { name: "release", flags: &["-C", "opt-level=3", "-C", "debug-assertions=off", "-C", "overflow-checks=off"] },
+];
+
+// --- Cutting the lesson into items ---
+
+struct Item {
+    start: usize,
+    end: usize,
+    function: Option<String>,
+    takes_arguments: bool,
+    unclosed: bool, // Its `{` is never closed, so it runs to the end of the file.
+}
+
+// Cuts the source into top-level items by tracking `{}` depth, skipping comments, strings
+// and char literals. Comments and attributes before an item belong to it.
+fn split_items(source: &str) -> Vec<Item> {
+    let chars: Vec<char> = source.chars().collect();
+    let at = |i: usize| chars.get(i).copied().unwrap_or('\0');
+    let (mut i, mut line, mut depth) = (0, 1, 0i32);
+    let mut ends = Vec::new(); // Lines where an item ends.
+    while i < chars.len() {
+        match chars[i] {
+            '\n' => line += 1,
+            '/' if at(i + 1) == '/' => {
+                while i + 1 < chars.len() && chars[i + 1] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if at(i + 1) == '*' => {
+                let mut nesting = 0;
+                while i < chars.len() {
+                    if chars[i] == '/' && at(i + 1) == '*' {
+                        nesting += 1;
+                        i += 1;
+                    } else if chars[i] == '*' && at(i + 1) == '/' {
+                        nesting -= 1;
+                        i += 1;
+                        if nesting == 0 {
+                            break;
+                        }
+                    } else if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+            }
+            'r' if (at(i + 1) == '#' || at(i + 1) == '"') && !(i > 0 && (at(i - 1).is_alphanumeric() || at(i - 1) == '_')) => {
+                let mut hashes = 0;
+                let mut j = i + 1;
+                while at(j) == '#' {
+                    hashes += 1;
+                    j += 1;
+                }
+                if at(j) == '"' {
+                    i = j + 1;
+                    while i < chars.len() && !(chars[i] == '"' && (0..hashes).all(|h| at(i + 1 + h) == '#')) {
+                        line += (chars[i] == '\n') as usize;
+                        i += 1;
+                    }
+                    i += hashes;
+                }
+            }
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    line += (at(i) == '\n') as usize;
+                    i += 1;
+                }
+            }
+            '\'' if at(i + 1) == '\\' => {
+                i += 2;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+            }
+            '\'' if at(i + 2) == '\'' => i += 2, // 'x' (a lifetime like 'a has no closing quote)
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    ends.push(line);
+                }
+            }
+            ';' if depth == 0 => ends.push(line),
+            _ => {}
+        }
+        i += 1;
+    }
+    let lines: Vec<&str> = source.lines().collect();
+    let unclosed = depth != 0;
+    if unclosed || ends.last() != Some(&lines.len()) {
+        ends.push(lines.len());
+    }
+    let mut items = Vec::new();
+    let mut start = 1;
+    for end in ends {
+        if end < start {
+            continue; // Two items ended on one line.
+        }
+        let signature = lines[start - 1..end]
+            .iter()
+            .find(|l| l.starts_with("fn ") || l.starts_with("pub fn ")); // At column 0: not a method.
+        let function = signature.map(|l| {
+            l.trim_start_matches("pub ").trim_start_matches("fn ").chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect()
+        });
+        let takes_arguments = signature.is_some_and(|l| !l.contains("()"));
+        items.push(Item { start, end, function, takes_arguments, unclosed: unclosed && end == lines.len() });
+        start = end + 1;
+    }
+    items
+}
+
+fn sections(items: &[Item]) -> Vec<&str> {
+    items
+        .iter()
+        .filter(|item| !item.takes_arguments && !item.unclosed)
+        .filter_map(|item| item.function.as_deref())
+        .filter(|name| *name != "main")
+        .collect()
+}
+
+// An exact name, or a prefix that matches just one section.
+fn resolve<'a>(wanted: &str, sections: &[&'a str]) -> Result<&'a str, String> {
+    if let Some(exact) = sections.iter().find(|s| **s == wanted) {
+        return Ok(exact);
+    }
+    let matching: Vec<&str> = sections.iter().copied().filter(|s| s.starts_with(wanted)).collect();
+    match matching.as_slice() {
+        [one] => Ok(one),
+        [] => Err(format!("no section named {:?}; run without a section name to list them", wanted)),
+        many => Err(format!("{:?} matches {} sections: {}", wanted, many.len(), many.join(", "))),
+    }
+}
+
+// The lesson without its `main` (and without an item that never closes), plus a `main`
+// that calls `section`. Line numbers of the kept lines are unchanged: dropped lines become blank.
+fn program_for(lesson: &str, items: &[Item], section: &str) -> String {
+    let lines: Vec<&str> = lesson.lines().collect();
+    let mut source = String::new();
+    for item in items {
+        let keep = !item.unclosed && item.function.as_deref() != Some("main");
+        for line in &lines[item.start - 1..item.end] {
+            source.push_str(if keep { line } else { "" });
+            source.push('\n');
+        }
+    }
+    source.push_str(&format!("fn main() {{\n    {}();\n}}\n", section));
+    source
+}
+
+// --- Building and running ---
+
+struct Run {
+    build_time: Duration,
+    run_time: Duration,
+    stdout: String,
+    stderr: String,
+    exit: Option<i32>, // None if killed by a signal.
+}
+
+fn build_and_run(source: &str, dir: &Path, exe: &Path, profile: &Profile) -> Result<Run, String> {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let started = Instant::now();
+    let mut child = Command::new(&rustc)
+        .args(["--edition", "2021", "--error-format=short", "-A", "warnings", "--crate-name", "profile_diff_section"])
+        .args(profile.flags)
+        .arg("-o")
+        .arg(exe)
+        .arg("-") // The source comes on stdin; `mod` files are found from `dir`.
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run {}: {}", rustc, e))?;
+    child.stdin.take().expect("stdin is piped").write_all(source.as_bytes()).map_err(|e| e.to_string())?;
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let build_time = started.elapsed();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let errors: Vec<&str> = stderr.lines().filter(|l| l.contains(": error")).collect();
+        return Err(format!("the {} build failed:\n  {}", profile.name, errors.join("\n  ").replace("<anon>:", "line ")));
+    }
+    let started = Instant::now();
+    let output = Command::new(exe).current_dir(dir).output().map_err(|e| format!("could not run {}: {}", exe.display(), e))?;
+    Ok(Run {
+        build_time,
+        run_time: started.elapsed(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        exit: output.status.code(),
+    })
+}
+
+// Everything a run printed: stdout, then stderr lines marked with `!` (the two streams are
+// read separately, so their relative order is lost).
+fn output_lines(run: &Run) -> Vec<String> {
+    let mut lines: Vec<String> = run.stdout.lines().map(str::to_string).collect();
+    lines.extend(run.stderr.lines().map(|l| format!("! {}", l)));
+    lines
+}
+
+// --- Side-by-side diff ---
+
+#[derive(Debug, PartialEq)]
+enum Row {
+    Same(usize, usize),
+    Changed(usize, usize),
+    OnlyLeft(usize),
+    OnlyRight(usize),
+}
+
+// A longest-common-subsequence alignment. Runs of removed and added lines between two common
+// lines are paired up as `Changed`, so a line whose timing differs sits next to its twin.
+fn align(left: &[String], right: &[String]) -> Vec<Row> {
+    let (n, m) = (left.len(), right.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if left[i] == right[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let mut rows = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let flush = |rows: &mut Vec<Row>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        let paired = removed.len().min(added.len());
+        for k in 0..paired {
+            rows.push(Row::Changed(removed[k], added[k]));
+        }
+        rows.extend(removed[paired..].iter().map(|&l| Row::OnlyLeft(l)));
+        rows.extend(added[paired..].iter().map(|&r| Row::OnlyRight(r)));
+        removed.clear();
+        added.clear();
+    };
+    while i < n || j < m {
+        if i < n && j < m && left[i] == right[j] {
+            flush(&mut rows, &mut removed, &mut added);
+            rows.push(Row::Same(i, j));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            removed.push(i);
+            i += 1;
+        } else {
+            added.push(j);
+            j += 1;
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+fn fit(text: &str, width: usize) -> String {
+    let count = text.chars().count();
+    if count <= width {
+        format!("{}{}", text, " ".repeat(width - count))
+    } else {
+        format!("{}~", text.chars().take(width - 1).collect::<String>())
+    }
+}
+
+fn print_side_by_side(left: &[String], right: &[String], changes_only: bool) {
+    let width = left.iter().chain(right).map(|l| l.chars().count()).max().unwrap_or(0).clamp(20, 58);
+    println!("{} | {}", fit(PROFILES[0].name, width), PROFILES[1].name);
+    println!("{}-+-{}", "-".repeat(width), "-".repeat(width));
+    for row in align(left, right) {
+        let (l, marker, r) = match row {
+            Row::Same(a, b) if !changes_only => (left[a].as_str(), '|', right[b].as_str()),
+            Row::Same(..) => continue,
+            Row::Changed(a, b) => (left[a].as_str(), '*', right[b].as_str()),
+            Row::OnlyLeft(a) => (left[a].as_str(), '<', ""),
+            Row::OnlyRight(b) => ("", '>', right[b].as_str()),
+        };
+        println!("{} {} {}", fit(l, width), marker, r);
+    }
+}
+
+fn describe_exit(exit: Option<i32>) -> String {
+    match exit {
+        Some(0) => "exit 0".to_string(),
+        Some(101) => "exit 101 (panicked)".to_string(),
+        Some(code) => format!("exit {}", code),
+        None => "killed by a signal".to_string(),
+    }
+}
+
+fn millis(d: Duration) -> String {
+    format!("{:.1} ms", d.as_secs_f64() * 1000.0)
+}
+
+fn compare(path: &Path, wanted: &str, changes_only: bool, work: &Path) -> Result<bool, String> {
+    let lesson = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let items = split_items(&lesson);
+    let section = resolve(wanted, &sections(&items))?;
+    let source = program_for(&lesson, &items, section);
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut runs = Vec::new();
+    for profile in &PROFILES {
+        runs.push(build_and_run(&source, dir, &work.join(profile.name), profile)?);
+    }
+    let (debug, release) = (&runs[0], &runs[1]);
+    println!("{} :: {}()\n", path.display(), section);
+    let (left, right) = (output_lines(debug), output_lines(release));
+    print_side_by_side(&left, &right, changes_only);
+    let differing = align(&left, &right).iter().filter(|r| !matches!(r, Row::Same(..))).count();
+    println!();
+    println!("{:<8} {:>12} {:>12}", "", PROFILES[0].name, PROFILES[1].name);
+    println!("{:<8} {:>12} {:>12}", "build", millis(debug.build_time), millis(release.build_time));
+    println!("{:<8} {:>12} {:>12}", "run", millis(debug.run_time), millis(release.run_time));
+    println!("{:<8} {:>12} {:>12}", "status", describe_exit(debug.exit), describe_exit(release.exit));
+    println!("\n{} of {} output line(s) differ", differing, left.len().max(right.len()));
+    Ok(differing == 0 && debug.exit == release.exit)
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let changes_only = args.iter().any(|a| a == "--changes-only");
+    args.retain(|a| a != "--changes-only");
+    let work = env::temp_dir().join(format!("profile_diff_{}", process::id()));
+    if let Err(e) = fs::create_dir_all(&work) {
+        eprintln!("{}: {}", work.display(), e);
+        process::exit(2);
+    }
+    let status = match args.as_slice() {
+        [file] => match fs::read_to_string(file) {
+            Ok(lesson) => {
+                println!("Sections in {} (pass one to compare its debug and release runs):", file);
+                for name in sections(&split_items(&lesson)) {
+                    println!("  {}", name);
+                }
+                0
+            }
+            Err(e) => {
+                eprintln!("{}: {}", file, e);
+                2
+            }
+        },
+        [file, section] => match compare(&PathBuf::from(file), section, changes_only, &work) {
+            Ok(same) => if same { 0 } else { 1 },
+            Err(e) => {
+                eprintln!("{}", e);
+                2
+            }
+        },
+        _ => {
+            eprintln!("usage: profile_diff [--changes-only] FILE [SECTION]");
+            2
+        }
+    };
+    fs::remove_dir_all(&work).ok();
+    process::exit(status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"fn needs_arguments(x: u8) -> u8 { x }
+
+fn section() {
+    let x: u8 = std::hint::black_box(255);
+    println!("debug assertions: {}", cfg!(debug_assertions));
+    println!("wrapping_add: {}", x.wrapping_add(1));
+    let y = x + 1;
+    println!("plain +: {}", y);
+}
+
+fn main() {
+    does_not_exist();
+}
+"#;
+
+    fn lines(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn align_pairs_changed_lines_and_keeps_common_ones() {
+        let rows = align(&lines(&["a", "b", "c", "d"]), &lines(&["a", "x", "c", "e", "f"]));
+        assert_eq!(rows, [Row::Same(0, 0), Row::Changed(1, 1), Row::Same(2, 2), Row::Changed(3, 3), Row::OnlyRight(4)]);
+    }
+
+    #[test]
+    fn sections_skip_main_and_functions_with_arguments() {
+        let items = split_items(FIXTURE);
+        let found = sections(&items);
+        assert_eq!(found, ["section"]);
+        assert_eq!(resolve("sec", &found), Ok("section")); // A unique prefix.
+        assert!(resolve("nope", &found).is_err());
+    }
+
+    #[test]
+    fn debug_and_release_differ_on_overflow_and_debug_assertions() {
+        let dir = env::temp_dir().join(format!("profile_diff_test_{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = program_for(FIXTURE, &split_items(FIXTURE), "section");
+        let runs: Vec<Result<Run, String>> = PROFILES.iter().map(|p| build_and_run(&source, &dir, &dir.join(p.name), p)).collect();
+        fs::remove_dir_all(&dir).ok();
+        let (debug, release) = (runs[0].as_ref().unwrap(), runs[1].as_ref().unwrap());
+
+        assert!(debug.stdout.contains("debug assertions: true"));
+        assert!(release.stdout.contains("debug assertions: false"));
+        assert!(debug.stdout.contains("wrapping_add: 0") && release.stdout.contains("wrapping_add: 0"));
+        assert_eq!(debug.exit, Some(101));
+        assert!(debug.stderr.contains("attempt to add with overflow"));
+        assert_eq!(release.exit, Some(0));
+        assert!(release.stdout.contains("plain +: 0"));
+    }
+}
@@ -0,0 +1,448 @@
+// str_inspect.rs
+// Looks inside a string: its UTF-8 bytes, its `char`s (Unicode scalar values) with the
+// byte offset each starts at, its grapheme clusters (what a reader calls a "character"),
+// and which byte indices are valid places to slice a `&str`.
+//
+//   report(s)               - all of the above as a table
+//   explain_slice(s, a..b)  - whether `&s[a..b]` works, and if not, the exact panic and why
+//   print_reports(..)       - both, for the lesson's strings and the samples (`--inspect-strings`)
+//
+// The tests at the end check the grapheme clusters and compare every predicted panic
+// message with the one Rust really prints:
+//
+//   rustc --edition 2021 --test str_inspect.rs -o target/str_inspect_test && target/str_inspect_test
+//
+// Grapheme clusters follow the rules of Unicode's UAX #29 (including the 15.1 rule that
+// keeps Devanagari conjuncts such as "स्ते" together), but with small built-in tables
+// covering the scripts these lessons use, not the whole Unicode database. A real
+// program should use the `unicode-segmentation` crate.
+//
+// Shared by Day001.002.rs and Day001.003.rs through `mod str_inspect;`.
+
+use std::cell::Cell;
+use std::ops::Range;
+
+thread_local! {
+    static PRINT_REPORTS: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn enable_reports() {
+    PRINT_REPORTS.with(|p| p.set(true));
+}
+
+// --- Character properties (a teaching subset of the Unicode tables) ---
+
+fn in_ranges(c: char, ranges: &[(u32, u32)]) -> bool {
+    let c = c as u32;
+    ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi)
+}
+
+// Combining marks, variation selectors, emoji skin-tone modifiers and tags.
+const EXTEND: &[(u32, u32)] = &[
+    (0x0300, 0x036F), (0x0483, 0x0489), (0x0591, 0x05BD), (0x05BF, 0x05BF), (0x05C1, 0x05C2),
+    (0x05C4, 0x05C5), (0x05C7, 0x05C7), (0x0610, 0x061A), (0x064B, 0x065F), (0x0670, 0x0670),
+    (0x06D6, 0x06DC), (0x06DF, 0x06E4), (0x06E7, 0x06E8), (0x06EA, 0x06ED), (0x0900, 0x0902),
+    (0x093A, 0x093A), (0x093C, 0x093C), (0x0941, 0x0948), (0x094D, 0x094D), (0x0951, 0x0957),
+    (0x0962, 0x0963), (0x1AB0, 0x1AFF), (0x1DC0, 0x1DFF), (0x200C, 0x200C), (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F), (0xFE20, 0xFE2F), (0x1F3FB, 0x1F3FF), (0xE0020, 0xE007F), (0xE0100, 0xE01EF),
+];
+
+// Vowel signs that take up their own space but still belong to the previous letter.
+const SPACING_MARK: &[(u32, u32)] = &[(0x0903, 0x0903), (0x093B, 0x093B), (0x093E, 0x0940), (0x0949, 0x094C), (0x094E, 0x094F)];
+
+const EXTENDED_PICTOGRAPHIC: &[(u32, u32)] = &[
+    (0x00A9, 0x00A9), (0x00AE, 0x00AE), (0x203C, 0x203C), (0x2049, 0x2049), (0x2122, 0x2122),
+    (0x2139, 0x2139), (0x2194, 0x2199), (0x21A9, 0x21AA), (0x231A, 0x231B), (0x2328, 0x2328),
+    (0x23CF, 0x23CF), (0x23E9, 0x23F3), (0x23F8, 0x23FA), (0x24C2, 0x24C2), (0x25AA, 0x25AB),
+    (0x25B6, 0x25B6), (0x25C0, 0x25C0), (0x25FB, 0x25FE), (0x2600, 0x27BF), (0x2934, 0x2935),
+    (0x2B05, 0x2B07), (0x2B1B, 0x2B1C), (0x2B50, 0x2B50), (0x2B55, 0x2B55), (0x3030, 0x3030),
+    (0x303D, 0x303D), (0x3297, 0x3297), (0x3299, 0x3299), (0x1F000, 0x1F1E5), (0x1F200, 0x1F3FA),
+    (0x1F400, 0x1FAFF), (0x1FC00, 0x1FFFD),
+];
+
+// Format characters that are their own cluster (bidi controls, zero-width space, BOM).
+const FORMAT_CONTROL: &[(u32, u32)] = &[
+    (0x00AD, 0x00AD), (0x200B, 0x200B), (0x200E, 0x200F), (0x2028, 0x202E), (0x2060, 0x2064),
+    (0x2066, 0x206F), (0xFEFF, 0xFEFF),
+];
+
+const DEVANAGARI_CONSONANT: &[(u32, u32)] = &[(0x0915, 0x0939), (0x0958, 0x095F), (0x0978, 0x097F)];
+const VIRAMA: char = '\u{094D}';
+const ZWJ: char = '\u{200D}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Break {
+    Cr,
+    Lf,
+    Control,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    SpacingMark,
+    HangulL,
+    HangulV,
+    HangulT,
+    HangulLv,
+    HangulLvt,
+    Pictographic,
+    Other,
+}
+
+fn break_class(c: char) -> Break {
+    let cp = c as u32;
+    match c {
+        '\r' => Break::Cr,
+        '\n' => Break::Lf,
+        ZWJ => Break::Zwj,
+        _ if c.is_control() || in_ranges(c, FORMAT_CONTROL) => Break::Control,
+        _ if in_ranges(c, EXTEND) => Break::Extend,
+        _ if in_ranges(c, SPACING_MARK) => Break::SpacingMark,
+        '\u{1F1E6}'..='\u{1F1FF}' => Break::RegionalIndicator,
+        '\u{1100}'..='\u{115F}' | '\u{A960}'..='\u{A97C}' => Break::HangulL,
+        '\u{1160}'..='\u{11A7}' | '\u{D7B0}'..='\u{D7C6}' => Break::HangulV,
+        '\u{11A8}'..='\u{11FF}' | '\u{D7CB}'..='\u{D7FB}' => Break::HangulT,
+        '\u{AC00}'..='\u{D7A3}' if (cp - 0xAC00).is_multiple_of(28) => Break::HangulLv,
+        '\u{AC00}'..='\u{D7A3}' => Break::HangulLvt,
+        _ if in_ranges(c, EXTENDED_PICTOGRAPHIC) => Break::Pictographic,
+        _ => Break::Other,
+    }
+}
+
+// The bidirectional class, which decides which way a run of text is displayed.
+fn direction(c: char) -> &'static str {
+    match c {
+        '\u{200E}' => "LRM (invisible left-to-right mark)",
+        '\u{200F}' => "RLM (invisible right-to-left mark)",
+        '\u{202A}' => "LRE (embedding)",
+        '\u{202B}' => "RLE (embedding)",
+        '\u{202C}' => "PDF (ends an embedding)",
+        '\u{202D}' => "LRO (override)",
+        '\u{202E}' => "RLO (override: shows what follows backwards)",
+        '\u{2066}' => "LRI (isolate)",
+        '\u{2067}' => "RLI (isolate)",
+        '\u{2068}' => "FSI (isolate)",
+        '\u{2069}' => "PDI (ends an isolate)",
+        _ if in_ranges(c, EXTEND) || c == ZWJ => "NSM (takes the direction of its base)",
+        _ if in_ranges(c, &[(0x0590, 0x05FF), (0x07C0, 0x085F), (0xFB1D, 0xFB4F)]) => "R (right-to-left)",
+        _ if in_ranges(c, &[(0x0600, 0x07BF), (0x0860, 0x08FF), (0xFB50, 0xFDFF), (0xFE70, 0xFEFF)]) => "AL (right-to-left, Arabic)",
+        _ if c.is_ascii_digit() => "EN (number)",
+        _ if c.is_alphabetic() => "L (left-to-right)",
+        _ => "neutral (follows its neighbours)",
+    }
+}
+
+fn is_rtl(c: char) -> bool {
+    direction(c).starts_with("R ") || direction(c).starts_with("AL")
+}
+
+// --- Segmentation ---
+
+// The byte range of each grapheme cluster, by UAX #29's rules GB3 to GB13.
+pub fn graphemes(s: &str) -> Vec<Range<usize>> {
+    let chars: Vec<(usize, char)> = s.char_indices().collect();
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut ri_run = 0; // Regional indicators in a row: flags pair them up.
+    let mut emoji_state = false; // Seen Pictographic Extend*; a ZWJ then joins the next pictograph.
+    let mut conjunct_state = (false, false); // Seen a consonant; seen a virama after it.
+    for i in 1..chars.len() {
+        let (prev, next) = (chars[i - 1].1, chars[i].1);
+        let (a, b) = (break_class(prev), break_class(next));
+
+        ri_run = if a == Break::RegionalIndicator { ri_run + 1 } else { 0 };
+        emoji_state = match a {
+            Break::Pictographic => true,
+            Break::Extend | Break::Zwj => emoji_state,
+            _ => false,
+        };
+        conjunct_state = if in_ranges(prev, DEVANAGARI_CONSONANT) {
+            (true, false)
+        } else if conjunct_state.0 && (prev == VIRAMA || a == Break::Extend || a == Break::Zwj) {
+            (true, conjunct_state.1 || prev == VIRAMA)
+        } else {
+            (false, false)
+        };
+
+        use Break::*;
+        let join = match (a, b) {
+            (Cr, Lf) => true,                                                         // GB3
+            (Cr | Lf | Control, _) | (_, Cr | Lf | Control) => false,                 // GB4, GB5
+            (HangulL, HangulL | HangulV | HangulLv | HangulLvt) => true,              // GB6
+            (HangulLv | HangulV, HangulV | HangulT) => true,                          // GB7
+            (HangulLvt | HangulT, HangulT) => true,                                   // GB8
+            (_, Extend | Zwj) => true,                                                // GB9
+            (_, SpacingMark) => true,                                                 // GB9a
+            _ if conjunct_state.1 && in_ranges(next, DEVANAGARI_CONSONANT) => true,   // GB9c
+            (Zwj, Pictographic) => emoji_state,                                       // GB11
+            (RegionalIndicator, RegionalIndicator) => ri_run % 2 == 1,                // GB12, GB13
+            _ => false,                                                               // GB999
+        };
+        if !join {
+            clusters.push(start..chars[i].0);
+            start = chars[i].0;
+        }
+    }
+    if !s.is_empty() {
+        clusters.push(start..s.len());
+    }
+    clusters
+}
+
+// Every byte index where `&s[..i]` and `&s[i..]` are allowed: the start of each char, and the end.
+pub fn char_boundaries(s: &str) -> Vec<usize> {
+    (0..=s.len()).filter(|&i| s.is_char_boundary(i)).collect()
+}
+
+// --- Reports ---
+
+fn bytes_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")
+}
+
+fn what(c: char) -> &'static str {
+    if c == VIRAMA {
+        return "virama: joins the consonants either side";
+    }
+    match break_class(c) {
+        Break::Cr | Break::Lf | Break::Control => "control/format",
+        Break::Extend => "combining mark",
+        Break::Zwj => "zero-width joiner",
+        Break::SpacingMark => "spacing vowel sign",
+        Break::RegionalIndicator => "regional indicator (half a flag)",
+        Break::HangulL | Break::HangulV | Break::HangulT => "Hangul jamo",
+        Break::HangulLv | Break::HangulLvt => "Hangul syllable",
+        Break::Pictographic => "pictograph",
+        Break::Other => "",
+    }
+}
+
+// Terminal columns `shown` takes up: emoji and Hangul are drawn two columns wide.
+fn display_width(shown: &str) -> usize {
+    shown
+        .chars()
+        .map(|c| match break_class(c) {
+            Break::HangulLv | Break::HangulLvt | Break::RegionalIndicator => 2,
+            Break::Pictographic if c as u32 >= 0x1F000 => 2,
+            Break::Extend | Break::Zwj | Break::SpacingMark => 0,
+            _ => 1,
+        })
+        .sum()
+}
+
+pub fn report(s: &str) -> String {
+    let clusters = graphemes(s);
+    let mut out = format!(
+        "  {:?}: bytes: {}, chars: {}, grapheme clusters: {}\n",
+        s,
+        s.len(),
+        s.chars().count(),
+        clusters.len()
+    );
+    out.push_str("    bytes   char      scalar   UTF-8        direction / kind\n");
+    for (offset, c) in s.char_indices() {
+        let len = c.len_utf8();
+        let shown = if c.is_control() || in_ranges(c, FORMAT_CONTROL) || matches!(break_class(c), Break::Extend | Break::Zwj | Break::SpacingMark) {
+            format!("{:?}", c).trim_matches('\'').to_string() // Invisible or combining: show the escape.
+        } else {
+            c.to_string()
+        };
+        let padding = 9usize.saturating_sub(display_width(&shown));
+        let kind = what(c);
+        out.push_str(&format!(
+            "    {:>2}..{:<3} {}{:padding$} U+{:<6} {:<12} {}{}{}\n",
+            offset,
+            offset + len,
+            shown,
+            "",
+            format!("{:04X}", c as u32),
+            bytes_hex(&s.as_bytes()[offset..offset + len]),
+            direction(c),
+            if kind.is_empty() { "" } else { ", " },
+            kind,
+            padding = padding
+        ));
+    }
+    let shown: Vec<String> = clusters.iter().map(|r| format!("{}..{} {:?}", r.start, r.end, &s[r.clone()])).collect();
+    out.push_str(&format!("    grapheme clusters: {}\n", shown.join(" | ")));
+    let boundaries: Vec<String> = char_boundaries(s).iter().map(usize::to_string).collect();
+    out.push_str(&format!("    valid slice indices: {}  (any other index panics)\n", boundaries.join(", ")));
+    if s.chars().any(is_rtl) {
+        out.push_str(
+            "    note: the bytes are stored in logical order, the order the text is read. The terminal\n          \
+             draws right-to-left runs backwards, so byte 0 of a Hebrew or Arabic word is on the right.\n",
+        );
+    }
+    out
+}
+
+// What `&s[range]` panics with, word for word as core::str does, or None if it is a valid slice.
+pub fn slice_panic_message(s: &str, range: Range<usize>) -> Option<String> {
+    const MAX_DISPLAY_LENGTH: usize = 256; // The panic shows at most this many bytes of the string.
+    let mut cut = s.len().min(MAX_DISPLAY_LENGTH);
+    while !s.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    let shown = format!("`{}`{}", &s[..cut], if cut < s.len() { "[...]" } else { "" });
+    let (begin, end) = (range.start, range.end);
+    if begin > s.len() {
+        return Some(format!("start byte index {} is out of bounds of {}", begin, shown));
+    }
+    if end > s.len() {
+        return Some(format!("end byte index {} is out of bounds of {}", end, shown));
+    }
+    if begin > end {
+        return Some(format!("begin > end ({} > {}) when slicing {}", begin, end, shown));
+    }
+    for (which, index) in [("start", begin), ("end", end)] {
+        if !s.is_char_boundary(index) {
+            let (char_start, c) = s.char_indices().rev().find(|&(i, _)| i < index).expect("index 0 is always a boundary");
+            return Some(format!(
+                "{} byte index {} is not a char boundary; it is inside {:?} (bytes {:?}) of {}",
+                which,
+                index,
+                c,
+                char_start..char_start + c.len_utf8(),
+                shown
+            ));
+        }
+    }
+    None
+}
+
+// "Why does `&s[0..1]` panic on 'ö'?", answered for any string and range.
+pub fn explain_slice(s: &str, range: Range<usize>) -> String {
+    let mut out = format!("  &{:?}[{}..{}]\n", s, range.start, range.end);
+    match slice_panic_message(s, range.clone()) {
+        None => out.push_str(&format!("    fine: both ends are char boundaries, giving {:?}\n", &s[range])),
+        Some(message) => {
+            out.push_str(&format!("    panics: {}\n", message));
+            if let Some((start, c)) = s.char_indices().find(|&(i, c)| (i + 1..i + c.len_utf8()).any(|b| b == range.start || b == range.end)) {
+                let bytes = &s.as_bytes()[start..start + c.len_utf8()];
+                out.push_str(&format!(
+                    "    why: {:?} is {} bytes in UTF-8 ({}), at bytes {}..{}. A `&str` must always hold whole\n         \
+                     characters, so a slice may not start or end between those bytes; Rust checks\n         \
+                     at run time and panics instead of handing you broken UTF-8.\n",
+                    c,
+                    c.len_utf8(),
+                    bytes_hex(bytes),
+                    start,
+                    start + c.len_utf8()
+                ));
+                let prev = start;
+                let next = start + c.len_utf8();
+                out.push_str(&format!(
+                    "    instead: use {} or {} as the index, `s.get({}..{})` to get None rather than a panic,\n             \
+                     or `s.chars()` / `s.char_indices()` to walk whole characters.\n",
+                    prev,
+                    next,
+                    range.start,
+                    range.end
+                ));
+            } else {
+                out.push_str(&format!("    why: the string is only {} bytes long; `s.get(..)` returns None instead of panicking.\n", s.len()));
+            }
+        }
+    }
+    out
+}
+
+// The strings the reports are most useful on: accents two ways, emoji sequences, flags,
+// Hangul, a Devanagari conjunct and right-to-left text.
+pub fn samples() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("precomposed é", "caf\u{E9}"),
+        ("e + combining acute", "cafe\u{301}"),
+        ("emoji ZWJ family", "👨\u{200D}👩\u{200D}👧"),
+        ("skin tone + flag", "👍🏽🇯🇵"),
+        ("Hindi", "नमस्ते"),
+        ("Korean", "한국어"),
+        ("Hebrew + English", "שלום abc"),
+        ("Arabic with a mark", "سَلام"),
+    ]
+}
+
+// With `--inspect-strings`, prints a report for each lesson string, explains each slice,
+// then reports on the samples. Without the flag it prints nothing.
+pub fn print_reports(lesson_strings: &[&str], slices: &[(&str, Range<usize>)]) {
+    if !PRINT_REPORTS.with(Cell::get) {
+        return;
+    }
+    println!("[str_inspect] The lesson's strings:");
+    for s in lesson_strings {
+        print!("{}", report(s));
+    }
+    println!("[str_inspect] Slicing by byte index:");
+    for (s, range) in slices {
+        print!("{}", explain_slice(s, range.clone()));
+    }
+    println!("[str_inspect] More strings: combining marks, emoji sequences, Hangul, right-to-left:");
+    for (label, s) in samples() {
+        println!("  ({})", label);
+        print!("{}", report(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+    use std::sync::{Arc, Mutex};
+
+    // Runs `&s[range]` for real and returns the panic message, if it panicked.
+    fn real_panic(s: &str, range: Range<usize>) -> Option<String> {
+        let message = Arc::new(Mutex::new(None));
+        let captured = Arc::clone(&message);
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let payload = info.payload();
+            let text = payload.downcast_ref::<String>().cloned().or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()));
+            *captured.lock().unwrap() = text;
+        }));
+        let result = panic::catch_unwind(|| s[range].len());
+        panic::set_hook(previous);
+        let message = message.lock().unwrap().take();
+        result.err().map(|_| message.unwrap_or_default())
+    }
+
+    #[test]
+    fn grapheme_clusters() {
+        let expected_clusters: [(&str, &[&str]); 10] = [
+            ("Hello Wörld! 🦀", &["H", "e", "l", "l", "o", " ", "W", "ö", "r", "l", "d", "!", " ", "🦀"]),
+            ("cafe\u{301}", &["c", "a", "f", "e\u{301}"]),
+            ("👨\u{200D}👩\u{200D}👧", &["👨\u{200D}👩\u{200D}👧"]),
+            ("👍🏽🇯🇵", &["👍🏽", "🇯🇵"]),
+            ("🇯🇵🇫🇷🇩", &["🇯🇵", "🇫🇷", "🇩"]),
+            ("नमस्ते", &["न", "म", "स्ते"]),
+            ("한국어", &["한", "국", "어"]),
+            ("\u{1100}\u{1161}\u{11A8}", &["\u{1100}\u{1161}\u{11A8}"]),
+            ("a\r\nb", &["a", "\r\n", "b"]),
+            ("سَلام", &["سَ", "ل", "ا", "م"]),
+        ];
+        for (s, expected) in expected_clusters {
+            let actual: Vec<&str> = graphemes(s).into_iter().map(|r| &s[r]).collect();
+            assert_eq!(actual, expected, "grapheme clusters of {:?}", s);
+        }
+    }
+
+    #[test]
+    fn predicted_panic_messages_match_rust() {
+        let long = "x".repeat(300) + "ö";
+        #[allow(clippy::reversed_empty_ranges)] // Backwards on purpose: that is one of the panics.
+        let slices: [(&str, Range<usize>); 9] = [
+            ("ö", 0..1),
+            ("ö", 1..2),
+            ("ö", 0..5),
+            ("ö", 3..1),
+            ("Hello Wörld! 🦀", 7..8),
+            ("Hello Wörld! 🦀", 13..15),
+            ("Hello Wörld! 🦀", 15..14),
+            ("Hello Wörld! 🦀", 7..9),
+            (&long, 301..302),
+        ];
+        for (s, range) in slices {
+            let predicted = slice_panic_message(s, range.clone());
+            let actual = real_panic(s, range.clone());
+            assert_eq!(predicted, actual, "&s[{}..{}] on {:?}", range.start, range.end, s.chars().take(20).collect::<String>());
+        }
+    }
+}
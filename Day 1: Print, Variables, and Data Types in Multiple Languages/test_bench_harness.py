@@ -0,0 +1,150 @@
+# Tests for the benchmark harness, run by ./build_bench.sh --test
+# (or directly: python3 test_bench_harness.py RUST_BIN CPP_BIN)
+# Everything except the last test class runs on a fake clock, so the numbers are exact.
+
+import os
+import sys
+import unittest
+
+import bench_harness
+import bench_snippets
+
+BINARIES = ("target/bench_snippets_rust", "target/bench_snippets_cpp")
+
+
+class FakeClock:
+    """Each snippet run reads the clock twice; the second read is `durations[k]` after the first."""
+
+    def __init__(self, durations):
+        self.durations = list(durations)
+        self.now = 1_000_000
+        self.reads = 0
+
+    def __call__(self):
+        if self.reads % 2 == 1:
+            self.now += self.durations[self.reads // 2]
+        self.reads += 1
+        return self.now
+
+
+class FakeClockRunner:
+    """Runs the real Python snippets in-process, timed by a FakeClock."""
+
+    def __init__(self, language, durations):
+        self.language = language
+        self.clock = FakeClock(durations)
+
+    def time(self, name):
+        return bench_snippets.run_one(name, self.clock)
+
+
+class StubRunner:
+    """Replies from a script of (nanoseconds, checksum) pairs without running anything."""
+
+    def __init__(self, language, replies):
+        self.language = language
+        self.replies = iter(replies)
+
+    def time(self, name):
+        return next(self.replies)
+
+
+class StatisticsTest(unittest.TestCase):
+    def test_fake_clock_durations_come_back_as_samples(self):
+        runner = FakeClockRunner("python", [500, 700])
+        self.assertEqual(runner.time("concat"), (500, 300_000))
+        self.assertEqual(runner.time("sum_vec")[0], 700)
+
+    def test_warmup_runs_are_discarded(self):
+        runner = FakeClockRunner("python", [9000, 8000, 100, 110, 120])
+        result = bench_harness.measure(runner, "concat", warmup=2, runs=3)
+        self.assertEqual(result.samples, [100, 110, 120])
+
+    def test_median_and_p95(self):
+        durations = list(range(100, 120))  # 100..119, no outliers
+        result = bench_harness.measure(FakeClockRunner("python", durations), "concat", warmup=0, runs=20)
+        self.assertEqual(result.rejected, [])
+        self.assertEqual(result.median, 109.5)
+        self.assertEqual(result.p95, 118)  # 19 of 20 samples are at or below it
+
+    def test_outliers_are_rejected_before_the_statistics(self):
+        durations = [100, 101, 102, 103, 104, 105, 106, 107, 5000, 10]
+        result = bench_harness.measure(FakeClockRunner("python", durations), "concat", warmup=0, runs=10)
+        self.assertEqual(sorted(result.rejected), [10, 5000])
+        self.assertEqual(len(result.kept), 8)
+        self.assertEqual(result.median, 103.5)
+        self.assertEqual(result.p95, 107)
+
+    def test_identical_samples_keep_everything(self):
+        result = bench_harness.measure(FakeClockRunner("python", [42] * 5), "concat", warmup=0, runs=5)
+        self.assertEqual((len(result.kept), result.median, result.p95), (5, 42, 42))
+
+    def test_a_checksum_that_changes_between_runs_is_an_error(self):
+        runner = StubRunner("rust", [(10, 1), (10, 2)])
+        with self.assertRaises(RuntimeError):
+            bench_harness.measure(runner, "sum_vec", warmup=0, runs=2)
+
+    def test_format_ns(self):
+        self.assertEqual([bench_harness.format_ns(n) for n in (999, 1500, 2_345_678, 3_000_000_000)],
+                         ["999 ns", "1.50 µs", "2.35 ms", "3.00 s"])
+
+
+class TableTest(unittest.TestCase):
+    def results(self, checksums=(7, 7, 7)):
+        runners = [StubRunner("rust", [(1000, checksums[0])] * 3),
+                   StubRunner("cpp", [(1500, checksums[1])] * 3),
+                   StubRunner("python", [(40_000, checksums[2])] * 3)]
+        return bench_harness.run_all(runners, ["sum_vec"], warmup=1, runs=2)
+
+    def test_table_is_sorted_fastest_first_with_ratios(self):
+        self.assertEqual(bench_harness.render(self.results()),
+                         "Benchmark  Language  Median    p95       Kept  vs fastest\n"
+                         "---------  --------  --------  --------  ----  ----------\n"
+                         "sum_vec    rust      1.00 µs   1.00 µs   2/2   1.0x\n"
+                         "           cpp       1.50 µs   1.50 µs   2/2   1.5x\n"
+                         "           python    40.00 µs  40.00 µs  2/2   40.0x\n")
+
+    def test_checksum_mismatch_is_reported(self):
+        results = self.results(checksums=(7, 7, 8))
+        self.assertFalse(bench_harness.checksums_agree(results["sum_vec"]))
+        self.assertIn("CHECKSUM MISMATCH sum_vec: checksums differ: rust=7, cpp=7, python=8",
+                      bench_harness.render(results))
+
+
+class RealProgramsTest(unittest.TestCase):
+    """The three implementations really do the same work (one run each, timings ignored)."""
+
+    def setUp(self):
+        missing = [path for path in BINARIES if not os.path.exists(path)]
+        if missing:
+            self.skipTest("not built: %s (./build_bench.sh --test builds them)" % ", ".join(missing))
+
+    def test_all_languages_agree_on_every_checksum(self):
+        runners = [bench_harness.ProcessRunner("rust", [BINARIES[0]]),
+                   bench_harness.ProcessRunner("cpp", [BINARIES[1]]),
+                   bench_harness.ProcessRunner("python", [sys.executable, "bench_snippets.py"])]
+        try:
+            results = bench_harness.run_all(runners, bench_harness.BENCHMARKS, warmup=0, runs=1)
+        finally:
+            for runner in runners:
+                runner.close()
+        for name, by_language in results.items():
+            self.assertTrue(bench_harness.checksums_agree(by_language),
+                            [(r.language, r.checksum) for r in by_language])
+        self.assertEqual(results["sum_vec"][0].checksum, -500_000)
+        self.assertEqual(results["concat"][0].checksum, 300_000)
+
+    def test_unknown_benchmark_is_an_error(self):
+        runner = bench_harness.ProcessRunner("rust", [BINARIES[0]])
+        try:
+            with self.assertRaises(RuntimeError):
+                runner.time("no_such_benchmark")
+        finally:
+            runner.close()
+
+
+if __name__ == "__main__":
+    if len(sys.argv) >= 3:
+        BINARIES = tuple(sys.argv[1:3])
+        del sys.argv[1:3]
+    unittest.main()
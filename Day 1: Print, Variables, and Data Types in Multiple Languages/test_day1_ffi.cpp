@@ -0,0 +1,61 @@
+// Tests for libday1_ffi.so from C++, run by ./build_day1_ffi.sh --test
+// Exits non-zero and names the failing check if anything is wrong.
+
+#include <cstring>
+#include <iostream>
+#include <string>
+#include "day1_ffi.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                              \
+    do {                                                                         \
+        if (!(cond)) {                                                           \
+            std::cerr << "FAIL " << __FILE__ << ":" << __LINE__ << ": " #cond << std::endl; \
+            failures++;                                                          \
+        }                                                                        \
+    } while (0)
+
+int main() {
+    // longest_slice returns one of its inputs, not a copy
+    const char* apple = "apple";
+    const char* banana = "banana";
+    CHECK(day1_longest_slice(apple, banana) == banana);
+    CHECK(day1_longest_slice(banana, apple) == banana);
+    CHECK(day1_longest_slice("same", "size") != nullptr);
+    CHECK(std::strcmp(day1_longest_slice("same", "size"), "size") == 0);
+    CHECK(day1_longest_slice(nullptr, banana) == nullptr);
+
+    // largest returns a pointer into the array
+    int32_t numbers[] = {34, 50, 25, 100, 65};
+    CHECK(day1_largest_i32(numbers, 5) == &numbers[3]);
+    CHECK(day1_largest_i32(numbers, 0) == nullptr);
+    double floats[] = {1.5, -2.0, 9.25};
+    CHECK(*day1_largest_f64(floats, 3) == 9.25);
+
+    // Rectangle passed by value
+    Day1Rectangle big = {30, 50};
+    Day1Rectangle small = {10, 40};
+    CHECK(day1_rectangle_area(big) == 1500);
+    CHECK(day1_rectangle_area(Day1Rectangle{4294967295u, 2}) == 8589934590ull);
+    CHECK(day1_rectangle_can_hold(big, small));
+    CHECK(!day1_rectangle_can_hold(small, big));
+
+    // parse family: status codes, out-pointers and the error message
+    int32_t i = 0;
+    CHECK(day1_parse_i32("42", &i) == DAY1_OK && i == 42);
+    CHECK(day1_parse_i32("oops", &i) == DAY1_ERR_PARSE && i == 42);
+    std::string message(day1_last_error(nullptr, 0), '\0');
+    day1_last_error(&message[0], message.size() + 1);
+    CHECK(message == "Failed to parse 'oops': invalid digit found in string");
+    CHECK(day1_parse_i32(nullptr, &i) == DAY1_ERR_NULL);
+    int64_t wide = 0;
+    CHECK(day1_parse_i64("3000000000", &wide) == DAY1_OK && wide == 3000000000LL);
+    double d = 0;
+    CHECK(day1_parse_f64("2.5e3", &d) == DAY1_OK && d == 2500.0);
+
+    if (failures == 0) {
+        std::cout << "test_day1_ffi.cpp: all checks passed" << std::endl;
+    }
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,80 @@
+# Tests for libday1_ffi.so through ctypes, run by ./build_day1_ffi.sh --test
+# (or directly: python3 test_day1_ffi.py [path/to/libday1_ffi.so])
+
+import ctypes
+import sys
+import unittest
+
+import Day1001_ffi as ffi
+
+LIBRARY = None
+
+
+class Day1FfiTest(unittest.TestCase):
+    @classmethod
+    def setUpClass(cls):
+        cls.lib = ffi.load(LIBRARY)
+
+    def test_longest_slice_returns_one_of_the_inputs(self):
+        self.assertEqual(ffi.longest_slice(self.lib, "apple", "banana"), "banana")
+        self.assertEqual(ffi.longest_slice(self.lib, "cherry", "fig"), "cherry")
+        self.assertEqual(ffi.longest_slice(self.lib, "same", "size"), "size")  # Ties pick `y`
+        self.assertEqual(ffi.longest_slice(self.lib, "héllo", "hello"), "héllo")  # Bytes, not characters
+
+    def test_longest_slice_null(self):
+        self.assertIsNone(self.lib.day1_longest_slice(None, b"x"))
+
+    def test_largest(self):
+        self.assertEqual(ffi.largest(self.lib, [34, 50, 25, 100, 65]), 100)
+        self.assertEqual(ffi.largest(self.lib, [-7]), -7)
+        self.assertEqual(ffi.largest(self.lib, [1.5, -2.0, 9.25]), 9.25)
+        self.assertIsNone(ffi.largest(self.lib, []))
+
+    def test_largest_points_into_the_array(self):
+        array = (ctypes.c_int32 * 3)(1, 9, 4)
+        result = self.lib.day1_largest_i32(array, 3)
+        self.assertEqual(ctypes.addressof(result.contents), ctypes.addressof(array) + 4)
+
+    def test_rectangle(self):
+        self.assertEqual(self.lib.day1_rectangle_area(ffi.Day1Rectangle(30, 50)), 1500)
+        self.assertEqual(self.lib.day1_rectangle_area(ffi.Day1Rectangle(2**32 - 1, 2)), 2 * (2**32 - 1))
+        big, small = ffi.Day1Rectangle(30, 50), ffi.Day1Rectangle(10, 40)
+        self.assertTrue(self.lib.day1_rectangle_can_hold(big, small))
+        self.assertFalse(self.lib.day1_rectangle_can_hold(small, big))
+        self.assertFalse(self.lib.day1_rectangle_can_hold(big, big))
+
+    def test_parse_ok(self):
+        self.assertEqual(ffi.parse_number(self.lib, "42"), 42)
+        self.assertEqual(ffi.parse_number(self.lib, "-5"), -5)
+        self.assertEqual(ffi.parse_number(self.lib, "3000000000", "i64"), 3000000000)
+        self.assertEqual(ffi.parse_number(self.lib, "2.5e3", "f64"), 2500.0)
+
+    def test_parse_errors(self):
+        with self.assertRaises(ffi.Day1Error) as ctx:
+            ffi.parse_number(self.lib, "not_a_number")
+        self.assertEqual(ctx.exception.code, ffi.DAY1_ERR_PARSE)
+        self.assertEqual(str(ctx.exception), "Failed to parse 'not_a_number': invalid digit found in string")
+
+        with self.assertRaises(ffi.Day1Error) as ctx:
+            ffi.parse_number(self.lib, "3000000000")
+        self.assertIn("too large", str(ctx.exception))
+
+        out = ctypes.c_int32(7)
+        self.assertEqual(self.lib.day1_parse_i32(b"\xff", ctypes.byref(out)), ffi.DAY1_ERR_UTF8)
+        self.assertEqual(self.lib.day1_parse_i32(None, ctypes.byref(out)), ffi.DAY1_ERR_NULL)
+        self.assertEqual(self.lib.day1_parse_i32(b"1", None), ffi.DAY1_ERR_NULL)
+        self.assertEqual(out.value, 7)  # Untouched on failure
+
+    def test_last_error_truncates_like_snprintf(self):
+        with self.assertRaises(ffi.Day1Error):
+            ffi.parse_number(self.lib, "abc")
+        buf = ctypes.create_string_buffer(8)
+        needed = self.lib.day1_last_error(buf, len(buf))
+        self.assertEqual(needed, len("Failed to parse 'abc': invalid digit found in string"))
+        self.assertEqual(buf.value, b"Failed ")
+
+
+if __name__ == "__main__":
+    if len(sys.argv) > 1:
+        LIBRARY = sys.argv.pop(1)
+    unittest.main()
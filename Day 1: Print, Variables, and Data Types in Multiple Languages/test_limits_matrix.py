@@ -0,0 +1,81 @@
+# Tests for the limits probes, run by ./build_limits_matrix.sh --test
+# (or directly: python3 test_limits_matrix.py RUST_DEBUG RUST_RELEASE CPP)
+
+import sys
+import unittest
+
+import limits_matrix
+
+PROBES = ("target/limits_probe_debug", "target/limits_probe_release", "target/limits_probe_cpp")
+
+
+class LimitsMatrixTest(unittest.TestCase):
+    @classmethod
+    def setUpClass(cls):
+        rows = limits_matrix.collect(*PROBES)
+        cls.rows = {(r["language"], r["type"]): r for r in rows}
+
+    def row(self, language, type_name):
+        return self.rows[(language, type_name)]
+
+    def test_every_language_and_type_is_present(self):
+        for t in ("i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32", "f64"):
+            self.assertIn(("rust", t), self.rows)
+        for t in ("signed char", "short", "int", "long long", "unsigned char", "unsigned int", "float", "double", "long double"):
+            self.assertIn(("cpp", t), self.rows)
+        for t in ("int", "float", "ctypes.c_int8", "ctypes.c_uint64"):
+            self.assertIn(("python", t), self.rows)
+
+    def test_same_width_same_limits(self):
+        pairs = [("i8", "signed char", "ctypes.c_int8"), ("i32", "int", "ctypes.c_int32"),
+                 ("i64", "long long", "ctypes.c_int64"), ("u8", "unsigned char", "ctypes.c_uint8"),
+                 ("u64", "unsigned long long", "ctypes.c_uint64")]
+        for rust, cpp, python in pairs:
+            for column in ("min", "max"):
+                self.assertEqual(self.row("rust", rust)[column], self.row("cpp", cpp)[column], (rust, column))
+                self.assertEqual(self.row("rust", rust)[column], self.row("python", python)[column], (rust, column))
+        self.assertEqual(self.row("rust", "i32")["max"], "2147483647")
+
+    def test_overflow_behaviour(self):
+        self.assertEqual(self.row("rust", "i8")["overflow"], "debug: panics; release: wraps to -128")
+        self.assertEqual(self.row("rust", "u8")["overflow"], "debug: panics; release: wraps to 0")
+        self.assertEqual(self.row("cpp", "int")["overflow"], "UB")
+        self.assertEqual(self.row("cpp", "unsigned int")["overflow"], "wraps to 0")
+        self.assertEqual(self.row("cpp", "signed char")["overflow"], "128 in int, narrows to -128")
+        self.assertEqual(self.row("cpp", "unsigned char")["overflow"], "256 in int, narrows to 0")
+        self.assertEqual(self.row("python", "int")["overflow"], "grows (bigint)")
+        self.assertEqual(self.row("python", "ctypes.c_int8")["overflow"], "wraps to -128")
+        for language, t in (("rust", "f32"), ("rust", "f64"), ("cpp", "float"), ("cpp", "double")):
+            row = self.row(language, t)
+            self.assertEqual(row["overflow"], row["max"], (language, t))  # MAX + 1 rounds back to MAX
+        self.assertTrue(self.row("python", "float")["overflow"].startswith(self.row("python", "float")["max"]))
+
+    def test_1000_as_u8_truncates_to_232_everywhere(self):
+        self.assertEqual(self.row("rust", "u8")["from_1000"], "232")  # As in Day001.001.rs
+        self.assertEqual(self.row("cpp", "unsigned char")["from_1000"], "232")
+        self.assertEqual(self.row("python", "ctypes.c_uint8")["from_1000"], "232")
+        self.assertEqual(self.row("rust", "i8")["from_1000"], "-24")
+
+    def test_minus_one_to_unsigned_is_max(self):
+        for language, t in (("rust", "u32"), ("cpp", "unsigned int"), ("python", "ctypes.c_uint32")):
+            row = self.row(language, t)
+            self.assertEqual(row["from_minus_1"], row["max"], (language, t))
+
+    def test_float_to_int(self):
+        self.assertEqual(self.row("rust", "i32")["from_1e10"], "2147483647")  # Saturates
+        self.assertEqual(self.row("rust", "i32")["from_nan"], "0")
+        self.assertEqual(self.row("cpp", "int")["from_1e10"], "UB")
+        self.assertEqual(self.row("cpp", "long long")["from_1e10"], "10000000000")
+        self.assertEqual(self.row("python", "int")["from_1e10"], "10000000000")
+        self.assertEqual(self.row("python", "int")["from_nan"], "ValueError")
+
+    def test_render_has_one_line_per_row(self):
+        table = limits_matrix.render(list(self.rows.values()))
+        self.assertEqual(table.count("\n| "), len(self.rows) + 1)  # Plus the heading row
+
+
+if __name__ == "__main__":
+    if len(sys.argv) >= 4:
+        PROBES = tuple(sys.argv[1:4])
+        del sys.argv[1:4]
+    unittest.main()
@@ -0,0 +1,73 @@
+// type_layout.cpp - the C++ half of type_layout.rs
+// Prints sizeof/alignof for the Day1001.cpp types that type_layout.rs compares against,
+// one per line: "type<TAB>name<TAB>size<TAB>align", plus struct fields as
+// "field<TAB>Type.member<TAB>offset<TAB>size".
+// Build: c++ -std=c++17 -O2 type_layout.cpp -o target/type_layout_cpp
+
+#include <array>        // Required for std::array
+#include <complex>      // Required for complex numbers
+#include <cstddef>      // Required for offsetof and size_t
+#include <iostream>     // Required for input/output operations
+#include <memory>       // Required for smart pointers
+#include <optional>     // Required for std::optional
+#include <string>       // Required for string operations
+#include <string_view>  // Required for std::string_view
+#include <tuple>        // Required for std::tuple
+#include <vector>       // Required for vectors
+
+// The user-defined types from Day1001.cpp (declared inside main there)
+struct Person {
+    std::string name;
+    int age;
+    double height;
+};
+
+union Value {
+    int intValue;
+    double doubleValue;
+    char charValue;
+};
+
+enum Color { RED, GREEN, BLUE };
+enum class Direction { NORTH, EAST, SOUTH, WEST };
+
+#define TYPE(name, ...) \
+    std::cout << "type\t" << name << "\t" << sizeof(__VA_ARGS__) << "\t" << alignof(__VA_ARGS__) << "\n"
+#define FIELD(type, member) \
+    std::cout << "field\t" #type "." #member "\t" << offsetof(type, member) << "\t" << sizeof(type::member) << "\n"
+
+int main() {
+    TYPE("signed char", signed char);
+    TYPE("short", short);
+    TYPE("int", int);
+    TYPE("long", long);
+    TYPE("long long", long long);
+    TYPE("unsigned char", unsigned char);
+    TYPE("unsigned short", unsigned short);
+    TYPE("unsigned int", unsigned int);
+    TYPE("unsigned long long", unsigned long long);
+    TYPE("size_t", size_t);
+    TYPE("float", float);
+    TYPE("double", double);
+    TYPE("long double", long double);
+    TYPE("bool", bool);
+    TYPE("char", char);
+    TYPE("char32_t", char32_t);
+    TYPE("std::complex<double>", std::complex<double>);
+    TYPE("std::tuple<int, double, unsigned char>", std::tuple<int, double, unsigned char>);
+    TYPE("std::array<int, 5>", std::array<int, 5>);
+    TYPE("int[2][3]", int[2][3]);
+    TYPE("std::string_view", std::string_view);
+    TYPE("std::string", std::string);
+    TYPE("std::vector<int>", std::vector<int>);
+    TYPE("std::unique_ptr<int>", std::unique_ptr<int>);
+    TYPE("std::optional<int>", std::optional<int>);
+    TYPE("Person", Person);
+    FIELD(Person, name);
+    FIELD(Person, age);
+    FIELD(Person, height);
+    TYPE("Value", Value);
+    TYPE("enum Color", Color);
+    TYPE("enum class Direction", Direction);
+    return 0;
+}
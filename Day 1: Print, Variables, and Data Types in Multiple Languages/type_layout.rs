@@ -0,0 +1,432 @@
+// type_layout.rs
+// How big is each type from the data-types section of Day001.001.rs, how is it aligned,
+// where do its fields sit, and what does the C++ equivalent from Day1001.cpp look like?
+//
+// Usage:
+//   rustc --edition 2021 -O type_layout.rs -o target/type_layout
+//   c++ -std=c++17 -O2 type_layout.cpp -o target/type_layout_cpp
+//   target/type_layout                                  # table
+//   target/type_layout --cpp target/type_layout_cpp     # table with a C++ column
+//   target/type_layout --json [--cpp ...]               # the same data as JSON
+// Tests (the reported layouts against std::mem, and the JSON shape):
+//   rustc --edition 2021 --test type_layout.rs -o target/type_layout_test && target/type_layout_test
+//
+// The types below are copies of the ones declared inside `section_data_types`
+// (Rust does not let us name a type that is local to another function).
+// All numbers are for the machine this runs on; 64-bit Linux is assumed in the notes.
+
+use std::collections::HashMap;
+use std::mem::{align_of, offset_of, size_of};
+use std::process::{self, Command};
+
+// --- The Day001.001.rs types ---
+
+#[allow(dead_code)]
+enum SpreadsheetCell {
+    Int(i32),
+    Float(f64),
+    Text(String),
+}
+
+#[allow(dead_code)]
+struct Person {
+    name: String,
+    age: u32,
+    active: bool,
+}
+
+#[allow(dead_code)]
+struct Color(i32, i32, i32);
+
+#[allow(dead_code)]
+struct Point(i32, i32, i32);
+
+struct UnitStruct;
+
+#[allow(dead_code)]
+enum IpAddrKind {
+    V4,
+    V6,
+}
+
+#[allow(dead_code)]
+enum IpAddr {
+    V4(u8, u8, u8, u8),
+    V6(String),
+}
+
+#[allow(dead_code)]
+enum Message {
+    Quit,
+    Move { x: i32, y: i32 },
+    Write(String),
+    ChangeColor(Color),
+}
+
+// `Person` from Day1001.cpp, field for field, with C's layout rules.
+#[allow(dead_code)]
+#[repr(C)]
+struct CppPerson {
+    name: String,
+    age: i32,
+    height: f64,
+}
+
+// --- The report ---
+
+struct Field {
+    name: &'static str,
+    offset: usize,
+    size: usize,
+}
+
+struct Row {
+    group: &'static str,
+    rust: &'static str,
+    size: usize,
+    align: usize,
+    fields: Vec<Field>,
+    note: String,
+    cpp: Option<&'static str>, // The C++ type printed by type_layout.cpp, if there is one.
+}
+
+fn row<T>(group: &'static str, rust: &'static str, cpp: Option<&'static str>) -> Row {
+    Row { group, rust, size: size_of::<T>(), align: align_of::<T>(), fields: Vec::new(), note: String::new(), cpp }
+}
+
+macro_rules! field {
+    ($t:ty, $f:tt, $ft:ty) => {
+        Field { name: stringify!($f), offset: offset_of!($t, $f), size: size_of::<$ft>() }
+    };
+}
+
+// "niche" note for `Option<T>`: does `None` fit in a bit pattern `T` never uses?
+// `niche` names that pattern for this `T`; whether it is used is measured, not assumed.
+fn option_note<T>(niche: Option<&str>) -> String {
+    if size_of::<Option<T>>() == size_of::<T>() {
+        match niche {
+            Some(niche) => format!("niche: `None` is {}, so no extra tag", niche),
+            None => "niche: `None` uses a bit pattern the payload never holds, so no extra tag".to_string(),
+        }
+    } else {
+        format!(
+            "no niche: every bit pattern of the payload is valid, so a tag adds {} bytes (with padding)",
+            size_of::<Option<T>>() - size_of::<T>()
+        )
+    }
+}
+
+// For enums: is the tag stored separately, or in a niche of the largest variant?
+fn enum_note(size: usize, largest_payload: usize) -> String {
+    if size == largest_payload {
+        format!("tag lives in a niche of the largest variant ({} bytes), so it costs nothing", largest_payload)
+    } else {
+        format!("largest variant is {} bytes; tag and padding add {}", largest_payload, size - largest_payload)
+    }
+}
+
+fn padding_note(row: &Row) -> String {
+    let used: usize = row.fields.iter().map(|f| f.size).sum();
+    if row.size > used {
+        format!("{} bytes of padding", row.size - used)
+    } else {
+        "no padding".to_string()
+    }
+}
+
+fn rows() -> Vec<Row> {
+    let mut rows = vec![
+        row::<i8>("integers", "i8", Some("signed char")),
+        row::<i16>("integers", "i16", Some("short")),
+        row::<i32>("integers", "i32", Some("int")),
+        row::<i64>("integers", "i64", Some("long long")),
+        row::<i128>("integers", "i128", None),
+        row::<isize>("integers", "isize", Some("long")),
+        row::<u8>("integers", "u8", Some("unsigned char")),
+        row::<u16>("integers", "u16", Some("unsigned short")),
+        row::<u32>("integers", "u32", Some("unsigned int")),
+        row::<u64>("integers", "u64", Some("unsigned long long")),
+        row::<u128>("integers", "u128", None),
+        row::<usize>("integers", "usize", Some("size_t")),
+        row::<f32>("floats", "f32", Some("float")),
+        row::<f64>("floats", "f64", Some("double")),
+        row::<bool>("other scalars", "bool", Some("bool")),
+        row::<char>("other scalars", "char", Some("char32_t")),
+        row::<()>("other scalars", "()", None),
+    ];
+    rows.last_mut().unwrap().note = "zero-sized: takes no memory at all".to_string();
+    rows[15].note = "a Unicode scalar value, not a byte: compare C++ `char` (1 byte)".to_string();
+
+    let mut tuple = row::<(i32, f64, u8)>("tuples and arrays", "(i32, f64, u8)", Some("std::tuple<int, double, unsigned char>"));
+    tuple.fields = vec![field!((i32, f64, u8), 0, i32), field!((i32, f64, u8), 1, f64), field!((i32, f64, u8), 2, u8)];
+    tuple.note = format!("Rust may reorder fields to reduce padding; {}", padding_note(&tuple));
+    rows.push(tuple);
+    rows.push(row::<[i32; 5]>("tuples and arrays", "[i32; 5]", Some("std::array<int, 5>")));
+    rows.push(row::<[[i32; 3]; 2]>("tuples and arrays", "[[i32; 3]; 2]", Some("int[2][3]")));
+
+    let mut s = row::<&str>("pointers and strings", "&str", Some("std::string_view"));
+    s.note = "fat pointer: address + length".to_string();
+    rows.push(s);
+    let mut string = row::<String>("pointers and strings", "String", Some("std::string"));
+    string.note = "pointer + capacity + length; the text is on the heap (libstdc++ adds a 16-byte inline buffer)".to_string();
+    rows.push(string);
+    let mut vec = row::<Vec<i32>>("pointers and strings", "Vec<i32>", Some("std::vector<int>"));
+    vec.note = "pointer + capacity + length, like String".to_string();
+    rows.push(vec);
+    rows.push(row::<Box<i32>>("pointers and strings", "Box<i32>", Some("std::unique_ptr<int>")));
+
+    let mut opt_box = row::<Option<Box<i32>>>("Option and niches", "Option<Box<i32>>", Some("std::unique_ptr<int>"));
+    opt_box.note = option_note::<Box<i32>>(Some("a null pointer (a `Box` is never null)"));
+    rows.push(opt_box);
+    let mut opt_ref = row::<Option<&str>>("Option and niches", "Option<&str>", None);
+    opt_ref.note = option_note::<&str>(Some("a null data pointer (a reference is never null)"));
+    rows.push(opt_ref);
+    let mut opt_string = row::<Option<String>>("Option and niches", "Option<String>", None);
+    opt_string.note = option_note::<String>(Some("a capacity above `isize::MAX` (no allocation is that big)"));
+    rows.push(opt_string);
+    let mut opt_bool = row::<Option<bool>>("Option and niches", "Option<bool>", None);
+    opt_bool.note = option_note::<bool>(Some("the byte 2 (a `bool` is only ever 0 or 1)"));
+    rows.push(opt_bool);
+    let mut opt_char = row::<Option<char>>("Option and niches", "Option<char>", None);
+    opt_char.note = option_note::<char>(Some("0x110000 (the largest `char` is 0x10FFFF)"));
+    rows.push(opt_char);
+    let mut opt_i32 = row::<Option<i32>>("Option and niches", "Option<i32>", Some("std::optional<int>"));
+    opt_i32.note = option_note::<i32>(None);
+    rows.push(opt_i32);
+
+    let mut person = row::<Person>("custom types", "Person", None);
+    person.fields = vec![field!(Person, name, String), field!(Person, age, u32), field!(Person, active, bool)];
+    person.note = format!("default repr: the compiler picks the field order; {}", padding_note(&person));
+    rows.push(person);
+    let mut cpp_person = row::<CppPerson>("custom types", "#[repr(C)] CppPerson", Some("Person"));
+    cpp_person.fields = vec![field!(CppPerson, name, String), field!(CppPerson, age, i32), field!(CppPerson, height, f64)];
+    cpp_person.note = format!("repr(C): declaration order, C padding rules; {}", padding_note(&cpp_person));
+    rows.push(cpp_person);
+    let mut color = row::<Color>("custom types", "Color(i32, i32, i32)", None);
+    color.fields = vec![field!(Color, 0, i32), field!(Color, 1, i32), field!(Color, 2, i32)];
+    rows.push(color);
+    rows.push(row::<Point>("custom types", "Point(i32, i32, i32)", None));
+    let mut unit = row::<UnitStruct>("custom types", "UnitStruct", None);
+    unit.note = "zero-sized, like ()".to_string();
+    rows.push(unit);
+
+    let mut kind = row::<IpAddrKind>("enums", "IpAddrKind", Some("enum Color"));
+    kind.note = "fieldless enum: just a 1-byte tag (a C++ enum is an int)".to_string();
+    rows.push(kind);
+    let mut ip = row::<IpAddr>("enums", "IpAddr", None);
+    ip.note = enum_note(size_of::<IpAddr>(), size_of::<String>().max(4));
+    rows.push(ip);
+    let mut cell = row::<SpreadsheetCell>("enums", "SpreadsheetCell", Some("Value"));
+    cell.note = format!(
+        "{}; the C++ union `Value` has no tag, so it cannot tell which member is live",
+        enum_note(size_of::<SpreadsheetCell>(), size_of::<String>().max(size_of::<f64>()))
+    );
+    rows.push(cell);
+    let mut message = row::<Message>("enums", "Message", None);
+    message.note = enum_note(size_of::<Message>(), size_of::<String>().max(size_of::<Color>()));
+    rows.push(message);
+    rows
+}
+
+// --- The C++ side: lines of `type<TAB>name<TAB>size<TAB>align` from type_layout.cpp ---
+
+fn cpp_layouts(helper: &str) -> Result<HashMap<String, (usize, usize)>, String> {
+    let out = Command::new(helper).output().map_err(|e| format!("{}: {}", helper, e))?;
+    if !out.status.success() {
+        return Err(format!("{} exited with {}", helper, out.status));
+    }
+    let mut layouts = HashMap::new();
+    for line in String::from_utf8_lossy(&out.stdout).lines() {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if let ["type", name, size, align] = parts[..] {
+            let size = size.parse().map_err(|_| format!("bad size in `{}`", line))?;
+            let align = align.parse().map_err(|_| format!("bad align in `{}`", line))?;
+            layouts.insert(name.to_string(), (size, align));
+        }
+    }
+    Ok(layouts)
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn render_json(rows: &[Row], cpp: &HashMap<String, (usize, usize)>) -> String {
+    let items: Vec<String> = rows
+        .iter()
+        .map(|r| {
+            let fields: Vec<String> = r
+                .fields
+                .iter()
+                .map(|f| format!("{{\"name\":{},\"offset\":{},\"size\":{}}}", json_string(f.name), f.offset, f.size))
+                .collect();
+            let cpp_json = match r.cpp {
+                Some(name) => match cpp.get(name) {
+                    Some((size, align)) => format!("{{\"type\":{},\"size\":{},\"align\":{}}}", json_string(name), size, align),
+                    None => format!("{{\"type\":{}}}", json_string(name)),
+                },
+                None => "null".to_string(),
+            };
+            format!(
+                "{{\"group\":{},\"type\":{},\"size\":{},\"align\":{},\"fields\":[{}],\"note\":{},\"cpp\":{}}}",
+                json_string(r.group),
+                json_string(r.rust),
+                r.size,
+                r.align,
+                fields.join(","),
+                json_string(&r.note),
+                cpp_json
+            )
+        })
+        .collect();
+    format!("[\n  {}\n]\n", items.join(",\n  "))
+}
+
+fn render_table(rows: &[Row], cpp: &HashMap<String, (usize, usize)>) -> String {
+    let mut out = String::new();
+    let mut group = "";
+    for r in rows {
+        if r.group != group {
+            group = r.group;
+            out.push_str(&format!("\n== {} ==\n", group));
+            out.push_str(&format!("  {:<22} {:>5} {:>6}   {}\n", "type", "size", "align", "C++ (size/align)"));
+        }
+        let cpp_col = match r.cpp {
+            Some(name) => match cpp.get(name) {
+                Some((size, align)) => format!("{} ({}/{})", name, size, align),
+                None => name.to_string(),
+            },
+            None => String::new(),
+        };
+        out.push_str(&format!("  {:<22} {:>5} {:>6}   {}\n", r.rust, r.size, r.align, cpp_col));
+        for f in &r.fields {
+            out.push_str(&format!("      .{:<16} offset {:>3}, {} bytes\n", f.name, f.offset, f.size));
+        }
+        if !r.note.is_empty() {
+            out.push_str(&format!("      note: {}\n", r.note));
+        }
+    }
+    out
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let json = args.iter().any(|a| a == "--json");
+    let cpp = match args.iter().position(|a| a == "--cpp").map(|i| args.get(i + 1)) {
+        None => HashMap::new(),
+        Some(None) => {
+            eprintln!("type_layout: --cpp needs the path of the type_layout.cpp binary");
+            process::exit(2);
+        }
+        Some(Some(helper)) => cpp_layouts(helper).unwrap_or_else(|e| {
+            eprintln!("type_layout: {}", e);
+            process::exit(2);
+        }),
+    };
+    let rows = rows();
+    if json {
+        print!("{}", render_json(&rows, &cpp));
+    } else {
+        print!("{}", render_table(&rows, &cpp));
+        let mut cpp_only: Vec<&String> = cpp.keys().filter(|name| !rows.iter().any(|r| r.cpp == Some(name.as_str()))).collect();
+        if !cpp_only.is_empty() {
+            cpp_only.sort();
+            println!("\n== C++ types with no Rust row ==");
+            for name in cpp_only {
+                let (size, align) = cpp[name];
+                println!("  {:<22} {:>5} {:>6}", name, size, align);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(rows: &'a [Row], rust: &str) -> &'a Row {
+        rows.iter().find(|r| r.rust == rust).unwrap_or_else(|| panic!("no row for {}", rust))
+    }
+
+    macro_rules! assert_layout {
+        ($rows:expr, $rust:expr, $t:ty) => {
+            let r = find($rows, $rust);
+            assert_eq!((r.size, r.align), (size_of::<$t>(), align_of::<$t>()), "{}", $rust);
+        };
+    }
+
+    #[test]
+    fn reported_layouts_match_std_mem() {
+        let rows = rows();
+        assert_layout!(&rows, "i32", i32);
+        assert_layout!(&rows, "char", char);
+        assert_layout!(&rows, "()", ());
+        assert_layout!(&rows, "(i32, f64, u8)", (i32, f64, u8));
+        assert_layout!(&rows, "String", String);
+        assert_layout!(&rows, "Option<Box<i32>>", Option<Box<i32>>);
+        assert_layout!(&rows, "Person", Person);
+        assert_layout!(&rows, "#[repr(C)] CppPerson", CppPerson);
+        assert_layout!(&rows, "Message", Message);
+    }
+
+    #[test]
+    fn repr_c_fields_sit_in_declaration_order() {
+        let rows = rows();
+        let fields: Vec<(&str, usize, usize)> = find(&rows, "#[repr(C)] CppPerson").fields.iter().map(|f| (f.name, f.offset, f.size)).collect();
+        let (string, int) = (size_of::<String>(), size_of::<i32>());
+        let height = (string + int).next_multiple_of(align_of::<f64>());
+        assert_eq!(fields, [("name", 0, string), ("age", string, int), ("height", height, size_of::<f64>())]);
+    }
+
+    #[test]
+    fn json_has_one_object_per_row() {
+        let rows = vec![
+            row::<i8>("integers", "i8", Some("signed char")),
+            Row {
+                group: "custom types",
+                rust: "Pair",
+                size: 8,
+                align: 4,
+                fields: vec![Field { name: "0", offset: 0, size: 4 }, Field { name: "1", offset: 4, size: 4 }],
+                note: "a \"quoted\" note".to_string(),
+                cpp: Some("Pair"),
+            },
+            row::<u8>("integers", "u8", None),
+        ];
+        let cpp = HashMap::from([("signed char".to_string(), (1, 1))]);
+        assert_eq!(
+            render_json(&rows, &cpp),
+            concat!(
+                "[\n",
+                "  {\"group\":\"integers\",\"type\":\"i8\",\"size\":1,\"align\":1,\"fields\":[],\"note\":\"\",\"cpp\":{\"type\":\"signed char\",\"size\":1,\"align\":1}},\n",
+                "  {\"group\":\"custom types\",\"type\":\"Pair\",\"size\":8,\"align\":4,",
+                "\"fields\":[{\"name\":\"0\",\"offset\":0,\"size\":4},{\"name\":\"1\",\"offset\":4,\"size\":4}],",
+                "\"note\":\"a \\\"quoted\\\" note\",\"cpp\":{\"type\":\"Pair\"}},\n",
+                "  {\"group\":\"integers\",\"type\":\"u8\",\"size\":1,\"align\":1,\"fields\":[],\"note\":\"\",\"cpp\":null}\n",
+                "]\n",
+            )
+        );
+    }
+
+    #[test]
+    fn every_row_becomes_a_json_line() {
+        let rows = rows();
+        let json = render_json(&rows, &HashMap::new());
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!((lines[0], lines[lines.len() - 1]), ("[", "]"));
+        assert_eq!(lines.len(), rows.len() + 2);
+        for (line, r) in lines[1..lines.len() - 1].iter().zip(&rows) {
+            assert!(line.starts_with(&format!("  {{\"group\":{},\"type\":{},", json_string(r.group), json_string(r.rust))), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// config.rs
+// Reads /etc/ipcam.conf, the same KEY=value file that start-ipcam.sh `source`s.
+// Only IPCAM_URL is written by the setup scripts; the other keys are optional
+// tuning knobs for the Rust tooling and fall back to sensible defaults.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const CONFIG_FILE: &str = "/etc/ipcam.conf";
+pub const DEFAULT_IPCAM_URL: &str = "http://192.168.0.198:8080";
+pub const VIDEO_DEVICE: &str = "/dev/video10";
+
+#[derive(Debug, Clone)]
+pub struct IpcamConfig {
+    pub path: PathBuf,
+    pub ipcam_url: String,
+    pub probe_timeout: Duration,   // IPCAM_PROBE_TIMEOUT (seconds)
+    pub backoff_initial: Duration, // IPCAM_BACKOFF_INITIAL (seconds)
+    pub backoff_max: Duration,     // IPCAM_BACKOFF_MAX (seconds)
+    pub raw: BTreeMap<String, String>, // Every key found in the file, untouched.
+}
+
+impl IpcamConfig {
+    pub fn defaults() -> IpcamConfig {
+        IpcamConfig {
+            path: PathBuf::from(CONFIG_FILE),
+            ipcam_url: DEFAULT_IPCAM_URL.to_string(),
+            probe_timeout: Duration::from_secs(3),
+            backoff_initial: Duration::from_secs(1),
+            backoff_max: Duration::from_secs(60),
+            raw: BTreeMap::new(),
+        }
+    }
+
+    // Same precedence as the original start-ipcam.sh:
+    // environment IPCAM_URL > config file > built-in default.
+    // A missing file is not an error; the scripts treat it the same way.
+    pub fn load(path: &Path) -> io::Result<IpcamConfig> {
+        let mut config = IpcamConfig::defaults();
+        config.path = path.to_path_buf();
+        match fs::read_to_string(path) {
+            Ok(text) => config.raw = parse_shell_vars(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        if let Some(url) = config.raw.get("IPCAM_URL") {
+            config.ipcam_url = url.clone();
+        }
+        if let Ok(url) = std::env::var("IPCAM_URL") {
+            if !url.is_empty() {
+                config.ipcam_url = url;
+            }
+        }
+        config.ipcam_url = config.ipcam_url.trim_end_matches('/').to_string();
+
+        if let Some(d) = config.seconds("IPCAM_PROBE_TIMEOUT")? {
+            config.probe_timeout = d;
+        }
+        if let Some(d) = config.seconds("IPCAM_BACKOFF_INITIAL")? {
+            config.backoff_initial = d;
+        }
+        if let Some(d) = config.seconds("IPCAM_BACKOFF_MAX")? {
+            config.backoff_max = d;
+        }
+        Ok(config)
+    }
+
+    // The endpoint the scripts probe and feed to ffmpeg.
+    pub fn video_url(&self) -> String {
+        format!("{}/video", self.ipcam_url)
+    }
+
+    // IP Webcam's single-still endpoint.
+    pub fn shot_url(&self) -> String {
+        format!("{}/shot.jpg", self.ipcam_url)
+    }
+
+    pub fn seconds(&self, key: &str) -> io::Result<Option<Duration>> {
+        match self.raw.get(key) {
+            None => Ok(None),
+            // `try_from_secs_f64` refuses negative, NaN and too-large values (1e30 would panic
+            // in `from_secs_f64`).
+            Some(v) => v
+                .parse::<f64>()
+                .ok()
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                .map(Some)
+                .ok_or_else(|| invalid(format!("{}={} is not a number of seconds", key, v))),
+        }
+    }
+
+    pub fn number(&self, key: &str) -> io::Result<Option<u64>> {
+        match self.raw.get(key) {
+            None => Ok(None),
+            Some(v) => v
+                .parse::<u64>()
+                .map(Some)
+                .map_err(|_| invalid(format!("{}={} is not a whole number", key, v))),
+        }
+    }
+
+    // A size given in megabytes, in bytes.
+    pub fn megabytes(&self, key: &str) -> io::Result<Option<u64>> {
+        match self.number(key)? {
+            None => Ok(None),
+            Some(mb) => mb
+                .checked_mul(1024 * 1024)
+                .map(Some)
+                .ok_or_else(|| invalid(format!("{}={} is more bytes than fit in 64 bits", key, mb))),
+        }
+    }
+
+    pub fn text(&self, key: &str) -> Option<&str> {
+        self.raw.get(key).map(String::as_str).filter(|v| !v.is_empty())
+    }
+}
+
+// Understands the subset of shell syntax the scripts write:
+// `KEY=value`, optional `export`, optional quotes, `#` comments.
+pub fn parse_shell_vars(text: &str) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        let value = value.trim();
+        let value = if value.len() >= 2
+            && ((value.starts_with('"') && value.ends_with('"'))
+                || (value.starts_with('\'') && value.ends_with('\'')))
+        {
+            &value[1..value.len() - 1]
+        } else {
+            // Unquoted values end at the first inline comment.
+            value.split(" #").next().unwrap_or("").trim()
+        };
+        vars.insert(key.to_string(), value.to_string());
+    }
+    vars
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with(text: &str) -> IpcamConfig {
+        IpcamConfig { raw: parse_shell_vars(text), ..IpcamConfig::defaults() }
+    }
+
+    #[test]
+    fn seconds_accepts_fractions_and_refuses_what_no_duration_holds() {
+        assert_eq!(with("T=2.5").seconds("T").unwrap(), Some(Duration::from_millis(2500)));
+        assert_eq!(with("").seconds("T").unwrap(), None);
+        for bad in ["1e30", "-1", "NaN", "inf", "soon"] {
+            let err = with(&format!("T={}", bad)).seconds("T").unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+#!/bin/bash
+set -e
+
+# Read IPCAM_URL from environment or /etc/ipcam.conf or fallback default
+if [ -n "$IPCAM_URL" ]; then
+    BASE_URL="$IPCAM_URL"
+elif [ -f /etc/ipcam.conf ]; then
+    source /etc/ipcam.conf
+    if [ -z "$IPCAM_URL" ]; then
+        echo "Error: /etc/ipcam.conf exists but IPCAM_URL variable not set."
+        exit 1
+    fi
+    BASE_URL="$IPCAM_URL"
+else
+    BASE_URL="$DEFAULT_BASE_URL"
+    echo "No IPCAM_URL found in env or /etc/ipcam.conf. Using default: $BASE_URL"
+fi
+
+echo "Reloading v4l2loopback module..."
+sudo modprobe -r v4l2loopback || true
+sudo modprobe v4l2loopback devices=1 video_nr=10 card_label="IPCam" exclusive_caps=1
+
+echo "Starting ffmpeg stream from $BASE_URL/video to /dev/video10"
+ffmpeg -i "${BASE_URL}/video" -vcodec rawvideo -pix_fmt yuv420p -f v4l2 /dev/video10
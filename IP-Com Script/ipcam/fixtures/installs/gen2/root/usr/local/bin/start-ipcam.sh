@@ -0,0 +1,24 @@
+#!/bin/bash
+set -e
+
+CONFIG_FILE="/etc/ipcam.conf"
+
+# Load IPCAM_URL from config
+if [ -f "$CONFIG_FILE" ]; then
+    source "$CONFIG_FILE"
+fi
+
+: "${IPCAM_URL:=http://192.168.0.198:8080}"
+
+# Check if IP cam is online before starting
+if curl --silent --fail --head "$IPCAM_URL/video" > /dev/null; then
+    echo "✅ Camera found at $IPCAM_URL/video, starting ffmpeg..."
+
+    sudo modprobe -r v4l2loopback || true
+    sudo modprobe v4l2loopback devices=1 video_nr=10 card_label="IPCam" exclusive_caps=1
+
+    exec ffmpeg -re -i "$IPCAM_URL/video" -vcodec rawvideo -pix_fmt yuv420p -f v4l2 /dev/video10
+else
+    echo "❌ Camera not reachable at $IPCAM_URL/video. Exiting."
+    exit 42
+fi
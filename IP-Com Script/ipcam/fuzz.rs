@@ -0,0 +1,231 @@
+// fuzz.rs
+// Randomised checks for the MJPEG parser, driven by the synthetic streams in
+// fixtures/ (see fixtures/streams.txt for how they were made). Every iteration is derived from `--seed`, so a failure can be
+// replayed exactly with the seed it prints.
+//
+// For each fixture:
+//   1. Parse it in one go and compare the frame/error counts with fixtures/streams.txt.
+//   2. Re-chunking: feed the same bytes in random read sizes (1 byte up to 4 KiB,
+//      including zero-length `Interrupted` reads). The result must be identical.
+//   3. Mutation: flip, drop, duplicate or truncate random bytes. The parser must
+//      not panic and must finish within a bounded number of items.
+
+use std::fs;
+use std::io::{self, Read};
+use std::panic;
+use std::path::Path;
+
+use crate::mjpeg::{MjpegError, MjpegReader};
+
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    // xorshift64*: small, fast and good enough for picking chunk sizes.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+}
+
+// Hands out `data` in random-sized pieces, sometimes failing with `Interrupted`.
+struct ChunkedReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    rng: Rng,
+}
+
+impl Read for ChunkedReader<'_> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.rng.below(16) == 0 {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "fuzz"));
+        }
+        let n = (1 + self.rng.below(4096)).min(out.len()).min(self.data.len() - self.pos);
+        out[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+// A comparable summary of one parse: frame sizes and offsets, error kinds and offsets.
+#[derive(Debug, PartialEq, Eq)]
+enum Item {
+    Frame { offset: u64, len: usize },
+    Malformed { offset: u64 },
+    Truncated { offset: u64 },
+    Other(String),
+}
+
+fn summarise<R: Read>(reader: MjpegReader<R>, limit: usize) -> Vec<Item> {
+    reader
+        .take(limit)
+        .map(|item| match item {
+            Ok(frame) => Item::Frame { offset: frame.offset, len: frame.data.len() },
+            Err(MjpegError::Malformed { offset, .. }) => Item::Malformed { offset },
+            Err(MjpegError::Truncated { offset }) => Item::Truncated { offset },
+            Err(e) => Item::Other(e.to_string()),
+        })
+        .collect()
+}
+
+fn counts(items: &[Item]) -> (usize, usize) {
+    let frames = items.iter().filter(|i| matches!(i, Item::Frame { .. })).count();
+    (frames, items.len() - frames)
+}
+
+fn mutate(data: &[u8], rng: &mut Rng) -> Vec<u8> {
+    let mut out = data.to_vec();
+    for _ in 0..1 + rng.below(8) {
+        if out.is_empty() {
+            break;
+        }
+        let i = rng.below(out.len());
+        match rng.below(5) {
+            0 => out[i] ^= 1 << rng.below(8),
+            1 => {
+                out.remove(i);
+            }
+            2 => {
+                let end = (i + 1 + rng.below(64)).min(out.len());
+                let copy = out[i..end].to_vec();
+                out.splice(i..i, copy);
+            }
+            3 => out.truncate(i),
+            _ => out[i] = [b'\r', b'\n', b'-', b':'][rng.below(4)],
+        }
+    }
+    out
+}
+
+// Reads fixtures/streams.txt: `file frames errors` per line.
+pub fn load_manifest(dir: &Path) -> io::Result<Vec<(String, usize, usize)>> {
+    let text = fs::read_to_string(dir.join("streams.txt"))?;
+    let mut entries = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if let [file, frames, errors] = fields[..] {
+            if let (Ok(f), Ok(e)) = (frames.parse(), errors.parse()) {
+                entries.push((file.to_string(), f, e));
+                continue;
+            }
+        }
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("bad streams.txt line: {:?}", line)));
+    }
+    Ok(entries)
+}
+
+// Returns the number of failed checks; details are printed as they happen.
+pub fn run(dir: &Path, boundary: &str, iterations: u32, seed: u64) -> io::Result<usize> {
+    let mut failures = 0;
+    for (file, want_frames, want_errors) in load_manifest(dir)? {
+        let data = fs::read(dir.join(&file))?;
+        let limit = data.len() + 16; // Every item consumes at least one byte.
+
+        let baseline = summarise(MjpegReader::new(&data[..], boundary), limit);
+        let (frames, errors) = counts(&baseline);
+        let ok = (frames, errors) == (want_frames, want_errors);
+        println!(
+            "{:<28} {} frames, {} errors (expected {}, {}) {}",
+            file, frames, errors, want_frames, want_errors, if ok { "ok" } else { "MISMATCH" }
+        );
+        if !ok {
+            failures += 1;
+            for item in &baseline {
+                println!("    {:?}", item);
+            }
+        }
+
+        for i in 0..iterations {
+            let case_seed = seed ^ (u64::from(i) << 20) ^ file.len() as u64;
+            let chunked = ChunkedReader { data: &data, pos: 0, rng: Rng::new(case_seed) };
+            let rechunked = summarise(MjpegReader::new(chunked, boundary), limit);
+            if rechunked != baseline {
+                failures += 1;
+                println!("    re-chunking changed the result (seed {}): {:?}", case_seed, rechunked);
+            }
+
+            let mutated = mutate(&data, &mut Rng::new(case_seed));
+            let outcome = panic::catch_unwind(|| {
+                let items = summarise(MjpegReader::new(&mutated[..], boundary), mutated.len() + 17);
+                items.len() <= mutated.len() + 16
+            });
+            match outcome {
+                Ok(true) => {}
+                Ok(false) => {
+                    failures += 1;
+                    println!("    mutated input did not terminate (seed {})", case_seed);
+                }
+                Err(_) => {
+                    failures += 1;
+                    println!("    parser panicked on mutated input (seed {})", case_seed);
+                }
+            }
+        }
+    }
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::BOUNDARY;
+    use std::path::PathBuf;
+
+    const SEED: u64 = 0x1dea_5eed;
+
+    fn fixtures() -> PathBuf {
+        crate::fixtures_dir()
+    }
+
+    #[test]
+    fn corpus_parses_to_the_manifest_counts() {
+        let dir = fixtures();
+        let manifest = load_manifest(&dir).unwrap();
+        assert_eq!(manifest.len(), 3);
+        for (file, want_frames, want_errors) in manifest {
+            let data = fs::read(dir.join(&file)).unwrap();
+            let items = summarise(MjpegReader::new(&data[..], BOUNDARY), data.len() + 16);
+            assert_eq!(counts(&items), (want_frames, want_errors), "{}: {:?}", file, items);
+        }
+    }
+
+    #[test]
+    fn rechunking_with_a_fixed_seed_gives_the_same_items() {
+        let dir = fixtures();
+        for (file, _, _) in load_manifest(&dir).unwrap() {
+            let data = fs::read(dir.join(&file)).unwrap();
+            let limit = data.len() + 16;
+            let baseline = summarise(MjpegReader::new(&data[..], BOUNDARY), limit);
+            for i in 0..32 {
+                let chunked = ChunkedReader { data: &data, pos: 0, rng: Rng::new(SEED + i) };
+                let rechunked = summarise(MjpegReader::new(chunked, BOUNDARY), limit);
+                assert_eq!(rechunked, baseline, "{} re-chunked with seed {}", file, SEED + i);
+            }
+        }
+    }
+
+    #[test]
+    fn full_run_with_a_fixed_seed_has_no_failures() {
+        assert_eq!(run(&fixtures(), BOUNDARY, 50, SEED).unwrap(), 0);
+    }
+
+    #[test]
+    fn same_seed_same_mutation() {
+        let data = fs::read(fixtures().join("ipwebcam-6frames.mjpeg")).unwrap();
+        assert_eq!(mutate(&data, &mut Rng::new(SEED)), mutate(&data, &mut Rng::new(SEED)));
+        assert_ne!(mutate(&data, &mut Rng::new(SEED)), data);
+    }
+}
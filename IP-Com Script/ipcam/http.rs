@@ -0,0 +1,117 @@
+// http.rs
+// Just enough HTTP/1.1 to talk to an IP Webcam-style camera: plain `http://`,
+// one request per connection, no redirects. This replaces the scripts' use of
+// `curl --silent --fail --head "$IPCAM_URL/video"`.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+pub fn parse_url(url: &str) -> Result<Url, String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("unsupported URL (only http:// is supported): {}", url))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((h, p)) => (h, p.parse::<u16>().map_err(|_| format!("bad port in URL: {}", url))?),
+        None => (authority, 80),
+    };
+    if host.is_empty() {
+        return Err(format!("missing host in URL: {}", url));
+    }
+    Ok(Url { host: host.to_string(), port, path: path.to_string() })
+}
+
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: BufReader<TcpStream>, // Positioned right after the blank line ending the headers.
+}
+
+impl Response {
+    // Header names are case-insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    // `curl --fail` treats any status >= 400 as a failure.
+    pub fn is_success(&self) -> bool {
+        self.status < 400
+    }
+}
+
+// Sends a single request and parses the status line and headers.
+// `timeout` bounds the connect and every individual read/write.
+pub fn request(method: &str, url: &str, timeout: Duration) -> io::Result<Response> {
+    let url = parse_url(url).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, format!("could not resolve {}", url.host));
+    let mut stream = None;
+    for addr in (url.host.as_str(), url.port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(s) => {
+                stream = Some(s);
+                break;
+            }
+            Err(e) => last_err = e,
+        }
+    }
+    let mut stream = stream.ok_or(last_err)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: {}:{}\r\nUser-Agent: ipcam-rs\r\nConnection: close\r\n\r\n",
+        method, url.path, url.host, url.port
+    )?;
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed before status line"));
+    }
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("bad status line: {:?}", line.trim_end())))?;
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = trimmed.split_once(':') {
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+    }
+    Ok(Response { status, headers, body: reader })
+}
+
+// The reachability check from start-ipcam.sh, as a function.
+pub fn head_ok(url: &str, timeout: Duration) -> Result<u16, String> {
+    match request("HEAD", url, timeout) {
+        Ok(resp) if resp.is_success() => Ok(resp.status),
+        Ok(resp) => Err(format!("HTTP {}", resp.status)),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,445 @@
+// ipcam.rs
+// Rust companion to the ipcam shell scripts in the parent directory.
+// Reads the same /etc/ipcam.conf and talks to the same IP Webcam `/video` endpoint.
+//
+// Build:  rustc --edition 2021 -O ipcam.rs     (modules are picked up from this directory)
+// Run:    ./ipcam help
+// Test:   rustc --edition 2021 --test ipcam.rs -o ipcam-tests && ./ipcam-tests
+//         (the tests read fixtures/ next to ipcam-tests, so they can run from any directory)
+//
+// Try the supervisor without a camera, in two terminals:
+//   ./ipcam stub --port 8099 --script up:5,down:3
+//   IPCAM_URL=http://127.0.0.1:8099 ./ipcam supervise --config /dev/null -- sh -c 'sleep 30'
+// The stub's /shot.jpg serves a fixed JPEG, so snapshots work the same way:
+//   IPCAM_URL=http://127.0.0.1:8099 ./ipcam snapshot --config /dev/null --dir /tmp/snaps --every 2
+
+mod config;
+mod fuzz;
+mod http;
+mod logfmt;
+mod migrate;
+mod mjpeg;
+mod retention;
+mod snapshot;
+mod status;
+mod stub;
+mod supervisor;
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use config::{IpcamConfig, CONFIG_FILE};
+use supervisor::{Backoff, HttpProbe, Supervisor, SupervisorOptions};
+
+// The tests' fixtures/: next to the test binary when it is built as the Test line above
+// says, otherwise next to this file as rustc was given it.
+#[cfg(test)]
+fn fixtures_dir() -> PathBuf {
+    let beside_binary = std::env::current_exe().ok().and_then(|exe| Some(exe.parent()?.join("fixtures")));
+    beside_binary
+        .filter(|dir| dir.join("streams.txt").is_file())
+        .unwrap_or_else(|| Path::new(file!()).with_file_name("fixtures"))
+}
+
+const USAGE: &str = "\
+usage: ipcam <command> [options]
+
+commands:
+  supervise [--config PATH] [--max-cycles N] [--reload-module] [-- CMD ARGS...]
+        Probe $IPCAM_URL/video with backoff, run ffmpeg (or CMD) and restart it
+        when the stream drops. Logs one `key=value` line per state transition.
+  frames [--config PATH] [--url URL | --file PATH [--boundary B]] [--max N] [--save DIR]
+        Parse the MJPEG stream and list each frame (optionally saving the JPEGs).
+  fuzz-mjpeg [--fixtures DIR] [--iterations N] [--seed S]
+        Check the MJPEG parser against the synthetic streams, re-chunked and mutated.
+  snapshot [--config PATH] [--dir DIR] [--every SECS] [--count N]
+        Save a JPEG every SECS into DIR/YYYY-MM-DD/HH-MM-SS.mmm.jpg (UTC), pruning as it goes.
+  snapshots list|prune [--config PATH] [--dir DIR] [--keep-last N]
+            [--keep-hourly-hours H] [--max-mb MB] [--dry-run]
+        Show each snapshot with its retention verdict, or delete the expired ones.
+  status [--config PATH] [--json] [--no-probe] [--fixtures DIR]
+        Unit state and last exit code, /dev/video10, v4l2loopback and camera reachability.
+        --fixtures reads DIR/<unit>.show and treats DIR/root as / (see fixtures/status).
+  detect [--root DIR] [--json]
+        Tell which setup script generation is installed (gen1, gen2, v2 or mixed).
+  migrate [--root DIR] [--dry-run]
+        Upgrade any generation to the v2 layout, keeping IPCAM_URL. Old files are
+        copied to ROOT/var/backups/ipcam/migrate-<time>/ first.
+  stub [--port N] [--frame-ms MS] [--script up:SECS,down:SECS,...]
+        Serve a fake camera on 127.0.0.1 that follows the up/down script in a loop.
+  help  Show this message.
+";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("supervise") => cmd_supervise(&args[1..]),
+        Some("frames") => cmd_frames(&args[1..]),
+        Some("fuzz-mjpeg") => cmd_fuzz_mjpeg(&args[1..]),
+        Some("snapshot") => cmd_snapshot(&args[1..]),
+        Some("snapshots") => cmd_snapshots(&args[1..]),
+        Some("status") => cmd_status(&args[1..]),
+        Some("detect") => cmd_detect(&args[1..]),
+        Some("migrate") => cmd_migrate(&args[1..]),
+        Some("stub") => cmd_stub(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") | None => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(format!("unknown command {:?}\n\n{}", other, USAGE)),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            eprintln!("ipcam: {}", msg);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// Splits `args` into `--flag value` options and whatever follows `--`.
+struct Args {
+    flags: Vec<(String, Option<String>)>,
+    rest: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: &[String], switches: &[&str]) -> Result<Args, String> {
+        let mut flags = Vec::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--" {
+                return Ok(Args { flags, rest: iter.cloned().collect() });
+            }
+            if !arg.starts_with("--") {
+                return Err(format!("unexpected argument {:?}", arg));
+            }
+            if switches.contains(&arg.as_str()) {
+                flags.push((arg.clone(), None));
+            } else {
+                let value = iter.next().ok_or_else(|| format!("{} needs a value", arg))?;
+                flags.push((arg.clone(), Some(value.clone())));
+            }
+        }
+        Ok(Args { flags, rest: Vec::new() })
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.flags.iter().rev().find(|(k, _)| k == name).and_then(|(_, v)| v.as_deref())
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.flags.iter().any(|(k, _)| k == name)
+    }
+
+    fn number<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.value(name)
+            .map(|v| v.parse::<T>().map_err(|_| format!("{} expects a number, got {:?}", name, v)))
+            .transpose()
+    }
+
+    fn config(&self) -> Result<IpcamConfig, String> {
+        let path = PathBuf::from(self.value("--config").unwrap_or(CONFIG_FILE));
+        IpcamConfig::load(&path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+fn cmd_supervise(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw, &["--reload-module"])?;
+    let config = args.config()?;
+
+    let mut options = SupervisorOptions::ffmpeg_defaults(&config);
+    if !args.rest.is_empty() {
+        options.command = args.rest.clone();
+    }
+    options.max_cycles = args.number("--max-cycles")?;
+    if let Some(secs) = config.seconds("IPCAM_STABLE_AFTER").map_err(|e| e.to_string())? {
+        options.stable_after = secs;
+    }
+    if args.has("--reload-module") {
+        // What start-ipcam.sh does before exec'ing ffmpeg.
+        options.pre_start = ["sh", "-c", "modprobe -r v4l2loopback || true; \
+            modprobe v4l2loopback devices=1 video_nr=10 card_label=IPCam exclusive_caps=1"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+    }
+
+    logfmt::event(
+        "info",
+        "config",
+        &[
+            ("url", config.video_url()),
+            ("probe_timeout_ms", config.probe_timeout.as_millis().to_string()),
+            ("backoff_initial_ms", config.backoff_initial.as_millis().to_string()),
+            ("backoff_max_ms", config.backoff_max.as_millis().to_string()),
+        ],
+    );
+    let probe = HttpProbe { url: config.video_url(), timeout: config.probe_timeout };
+    let backoff = Backoff::new(config.backoff_initial, config.backoff_max);
+    Supervisor::new(probe, backoff, options).run();
+    Ok(())
+}
+
+fn cmd_frames(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw, &[])?;
+    let max = args.number::<usize>("--max")?;
+    let save_dir = args.value("--save").map(PathBuf::from);
+    if let Some(dir) = &save_dir {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+
+    let (source, reader): (String, Box<dyn Read>) = if let Some(path) = args.value("--file") {
+        let file = fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+        (path.to_string(), Box::new(file))
+    } else {
+        let config = args.config()?;
+        let url = args.value("--url").map(str::to_string).unwrap_or_else(|| config.video_url());
+        let resp = http::request("GET", &url, config.probe_timeout).map_err(|e| format!("{}: {}", url, e))?;
+        if !resp.is_success() {
+            return Err(format!("{}: HTTP {}", url, resp.status));
+        }
+        let content_type = resp.header("Content-Type").unwrap_or("").to_string();
+        let frames = mjpeg::MjpegReader::from_content_type(resp.body, &content_type).map_err(|e| e.to_string())?;
+        return list_frames(&url, frames, max, save_dir.as_deref());
+    };
+    let boundary = args.value("--boundary").unwrap_or(stub::BOUNDARY);
+    list_frames(&source, mjpeg::MjpegReader::new(reader, boundary), max, save_dir.as_deref())
+}
+
+fn list_frames<R: Read>(
+    source: &str,
+    frames: mjpeg::MjpegReader<R>,
+    max: Option<usize>,
+    save_dir: Option<&Path>,
+) -> Result<(), String> {
+    let (mut good, mut bad) = (0usize, 0usize);
+    for item in frames {
+        match item {
+            Ok(frame) => {
+                good += 1;
+                println!(
+                    "frame {:>4}  offset {:>8}  {:>7} bytes  {}",
+                    good,
+                    frame.offset,
+                    frame.data.len(),
+                    frame.header("Content-Type").unwrap_or("-")
+                );
+                if let Some(dir) = save_dir {
+                    let path = dir.join(format!("frame-{:05}.jpg", good));
+                    fs::write(&path, &frame.data).map_err(|e| format!("{}: {}", path.display(), e))?;
+                }
+            }
+            Err(e) => {
+                bad += 1;
+                println!("error       {}", e);
+            }
+        }
+        if max.is_some_and(|max| good >= max) {
+            break;
+        }
+    }
+    println!("{}: {} frames, {} errors", source, good, bad);
+    Ok(())
+}
+
+fn cmd_fuzz_mjpeg(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw, &[])?;
+    let dir = PathBuf::from(args.value("--fixtures").unwrap_or("fixtures"));
+    let iterations = args.number::<u32>("--iterations")?.unwrap_or(500);
+    let seed = args.number::<u64>("--seed")?.unwrap_or(1);
+    let failures = fuzz::run(&dir, stub::BOUNDARY, iterations, seed).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    if failures > 0 {
+        return Err(format!("{} fuzz check(s) failed (seed {})", failures, seed));
+    }
+    println!("all checks passed ({} iterations per fixture, seed {})", iterations, seed);
+    Ok(())
+}
+
+// Config-file snapshot settings, with command-line overrides on top.
+fn snapshot_settings(args: &Args) -> Result<(IpcamConfig, snapshot::SnapshotSettings), String> {
+    let config = args.config()?;
+    let mut settings = snapshot::SnapshotSettings::from_config(&config).map_err(|e| e.to_string())?;
+    if let Some(dir) = args.value("--dir") {
+        settings.dir = PathBuf::from(dir);
+    }
+    if let Some(secs) = args.number::<f64>("--every")? {
+        if !secs.is_finite() || secs <= 0.0 {
+            return Err("--every must be a positive number of seconds".into());
+        }
+        settings.interval = Duration::from_secs_f64(secs);
+    }
+    if let Some(n) = args.number::<usize>("--keep-last")? {
+        settings.policy.keep_last = n;
+    }
+    if let Some(h) = args.number::<u64>("--keep-hourly-hours")? {
+        settings.policy.keep_hourly_hours = h;
+    }
+    if let Some(mb) = args.number::<u64>("--max-mb")? {
+        settings.policy.max_bytes = Some(mb.checked_mul(1024 * 1024).ok_or("--max-mb is more bytes than fit in 64 bits")?);
+    }
+    Ok((config, settings))
+}
+
+fn cmd_snapshot(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw, &[])?;
+    let (config, settings) = snapshot_settings(&args)?;
+    let count = args.number::<u64>("--count")?;
+    logfmt::event(
+        "info",
+        "config",
+        &[
+            ("url", config.ipcam_url.clone()),
+            ("dir", settings.dir.display().to_string()),
+            ("interval_ms", settings.interval.as_millis().to_string()),
+        ],
+    );
+    snapshot::capture_loop(&config, &settings, count);
+    Ok(())
+}
+
+fn cmd_snapshots(raw: &[String]) -> Result<(), String> {
+    let (action, rest) = raw.split_first().ok_or("snapshots needs `list` or `prune`")?;
+    let args = Args::parse(rest, &["--dry-run"])?;
+    let (_, settings) = snapshot_settings(&args)?;
+    let now = snapshot::now_unix();
+    let dir = &settings.dir;
+    match action.as_str() {
+        "list" => {
+            let snapshots = retention::list(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+            let verdicts = retention::plan(&snapshots, &settings.policy, now);
+            let mut kept = 0u64;
+            for (snap, verdict) in snapshots.iter().zip(&verdicts) {
+                println!(
+                    "{}  {:>8} bytes  {:<26} {}",
+                    retention::format_utc(snap.taken_at),
+                    snap.bytes,
+                    verdict.label(),
+                    snap.path.strip_prefix(dir).unwrap_or(&snap.path).display()
+                );
+                if verdict.keep() {
+                    kept += snap.bytes;
+                }
+            }
+            let total: u64 = snapshots.iter().map(|s| s.bytes).sum();
+            println!(
+                "{} snapshots, {} bytes; {} kept ({} bytes) under keep_last={} keep_hourly_hours={} max_bytes={}",
+                snapshots.len(),
+                total,
+                verdicts.iter().filter(|v| v.keep()).count(),
+                kept,
+                settings.policy.keep_last,
+                settings.policy.keep_hourly_hours,
+                settings.policy.max_bytes.map_or("none".to_string(), |b| b.to_string())
+            );
+            Ok(())
+        }
+        "prune" => {
+            let dry_run = args.has("--dry-run");
+            let (files, bytes) = snapshot::prune(dir, &settings.policy, now, dry_run)
+                .map_err(|e| format!("{}: {}", dir.display(), e))?;
+            println!("{} {} snapshots ({} bytes)", if dry_run { "would delete" } else { "deleted" }, files, bytes);
+            Ok(())
+        }
+        other => Err(format!("unknown snapshots action {:?} (use list or prune)", other)),
+    }
+}
+
+fn cmd_status(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw, &["--json", "--no-probe"])?;
+    let config = args.config()?;
+    let (systemd, host) = status::readers(args.value("--fixtures").map(Path::new));
+    let mut probe = HttpProbe { url: config.video_url(), timeout: config.probe_timeout };
+    let probe: Option<&mut dyn supervisor::Probe> = if args.has("--no-probe") { None } else { Some(&mut probe) };
+    let report = status::gather(systemd.as_ref(), host.as_ref(), &config.video_url(), probe);
+    if args.has("--json") {
+        println!("{}", status::render_json(&report));
+    } else {
+        print!("{}", status::render_text(&report));
+    }
+    Ok(())
+}
+
+fn cmd_detect(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw, &["--json"])?;
+    let root = PathBuf::from(args.value("--root").unwrap_or("/"));
+    let found = migrate::detect(&root).map_err(|e| format!("{}: {}", root.display(), e))?;
+    if args.has("--json") {
+        let files: Vec<String> = found
+            .files
+            .iter()
+            .map(|(path, state)| format!("{{\"path\":{},\"state\":\"{:?}\"}}", status::json_string(path), state))
+            .collect();
+        let missing: Vec<String> = found.missing_packages.iter().map(|p| status::json_string(p)).collect();
+        println!(
+            "{{\"generation\":{},\"files\":[{}],\"ipcam_url\":{},\"missing_packages\":[{}]}}",
+            status::json_string(&found.generation.to_string()),
+            files.join(","),
+            found.ipcam_url.as_deref().map_or("null".to_string(), status::json_string),
+            missing.join(",")
+        );
+        return Ok(());
+    }
+    println!("installed: {}", found.generation);
+    for (path, state) in &found.files {
+        println!("  {:<56} {:?}", path, state);
+    }
+    println!("  IPCAM_URL: {}", found.ipcam_url.as_deref().unwrap_or("(not set in /etc/ipcam.conf)"));
+    if !found.missing_packages.is_empty() {
+        println!("  missing packages: {}", found.missing_packages.join(" "));
+    }
+    Ok(())
+}
+
+fn cmd_migrate(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw, &["--dry-run"])?;
+    let root = PathBuf::from(args.value("--root").unwrap_or("/"));
+    let dry_run = args.has("--dry-run");
+    let stamp = snapshot::now_unix().to_string();
+    let report = migrate::migrate(&root, &stamp, dry_run).map_err(|e| format!("{}: {}", root.display(), e))?;
+    println!("migrating from {} (IPCAM_URL={})", report.from, report.ipcam_url);
+    if report.written.is_empty() {
+        println!("  all files already match the current layout");
+    }
+    for path in &report.written {
+        println!("  {} {}", if dry_run { "would rewrite" } else { "rewrote" }, path);
+    }
+    if let Some(dir) = &report.backup_dir {
+        println!("  backups in {}", dir.display());
+    }
+    if !report.follow_up.is_empty() {
+        println!("then run:");
+        for cmd in &report.follow_up {
+            println!("  {}", cmd);
+        }
+    }
+    Ok(())
+}
+
+fn cmd_stub(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw, &[])?;
+    let port = args.number::<u16>("--port")?.unwrap_or(8080);
+    let frame_ms = args.number::<u64>("--frame-ms")?.unwrap_or(100);
+    let script = match args.value("--script") {
+        Some(spec) => stub::parse_script(spec)?,
+        None => Vec::new(),
+    };
+
+    let camera = stub::StubCamera::start(port, Duration::from_millis(frame_ms))
+        .map_err(|e| format!("cannot bind 127.0.0.1:{}: {}", port, e))?;
+    println!("Stub camera at {}/video", camera.base_url());
+    if script.is_empty() {
+        loop {
+            std::thread::sleep(Duration::from_secs(3600));
+        }
+    }
+    loop {
+        for &(up, duration) in &script {
+            logfmt::event("info", "stub", &[("up", up.to_string()), ("for_ms", duration.as_millis().to_string())]);
+            camera.play(&[(up, duration)]);
+        }
+    }
+}
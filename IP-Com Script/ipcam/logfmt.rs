@@ -0,0 +1,31 @@
+// logfmt.rs
+// Structured `key=value` log lines on stderr. journald keeps them as-is,
+// and `journalctl -u ipcam-stream -o cat | grep event=state` gives a timeline.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn event(level: &str, event: &str, fields: &[(&str, String)]) {
+    eprintln!("{}", format_line(level, event, fields));
+}
+
+pub fn format_line(level: &str, event: &str, fields: &[(&str, String)]) -> String {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let mut line = format!("ts={} level={} event={}", ts, level, event);
+    for (key, value) in fields {
+        line.push(' ');
+        line.push_str(key);
+        line.push('=');
+        line.push_str(&quote(value));
+    }
+    line
+}
+
+fn quote(value: &str) -> String {
+    if !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+        return value.to_string();
+    }
+    format!("{:?}", value)
+}
@@ -0,0 +1,498 @@
+// migrate.rs
+// Tells apart the installs left behind by the three setup scripts and upgrades
+// any of them to the current (Latest-setup-ipcam-serviceV2.sh) layout.
+//
+//   generation  script                        start-ipcam.sh                 ipcam-stream.service
+//   gen1        ipcam-service.sh              BASE_URL fallback chain,       Restart=always, RestartSec=5
+//                                             no camera probe
+//   gen2        setup-ipcam-service.sh        curl probe, `exit 42`          Restart=on-failure,
+//                                                                            SuccessExitStatus=42
+//   v2          Latest-setup-ipcam-serviceV2  same files as gen2             same as gen2
+//
+// gen2 and v2 write byte-identical files; v2 additionally installs the `ffmpeg`
+// and `curl` packages that the gen2 script already calls. So the two are told
+// apart by the dpkg database, and "migrating" gen2 means installing those packages.
+// Detection only looks at what is installed, so a gen1 install that `migrate` has
+// rewritten reads as gen2 until the packages it lists as a follow-up are installed.
+//
+// Everything is resolved against a root directory, "/" on a real machine, so
+// fixtures/installs/<generation>/root can stand in for one.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::{parse_shell_vars, DEFAULT_IPCAM_URL};
+
+pub const SCRIPT_FILE: &str = "/usr/local/bin/start-ipcam.sh";
+pub const SERVICE_FILE: &str = "/etc/systemd/system/ipcam-stream.service";
+pub const PATH_FILE: &str = "/etc/systemd/system/ipcam-stream.path";
+pub const PATH_OVERRIDE_CONF: &str = "/etc/systemd/system/ipcam-stream.path.d/override.conf";
+pub const CONFIG_FILE: &str = "/etc/ipcam.conf";
+pub const BACKUP_DIR: &str = "/var/backups/ipcam";
+pub const REQUIRED_PACKAGES: [&str; 4] = ["v4l2loopback-dkms", "v4l2loopback-utils", "ffmpeg", "curl"];
+
+// The files Latest-setup-ipcam-serviceV2.sh writes, with its variables expanded.
+pub const CURRENT_SCRIPT: &str = r#"#!/bin/bash
+set -e
+
+CONFIG_FILE="/etc/ipcam.conf"
+
+# Load IPCAM_URL from config
+if [ -f "$CONFIG_FILE" ]; then
+    source "$CONFIG_FILE"
+fi
+
+: "${IPCAM_URL:=http://192.168.0.198:8080}"
+
+# Check if IP cam is online before starting
+if curl --silent --fail --head "$IPCAM_URL/video" > /dev/null; then
+    echo "✅ Camera found at $IPCAM_URL/video, starting ffmpeg..."
+
+    sudo modprobe -r v4l2loopback || true
+    sudo modprobe v4l2loopback devices=1 video_nr=10 card_label="IPCam" exclusive_caps=1
+
+    exec ffmpeg -re -i "$IPCAM_URL/video" -vcodec rawvideo -pix_fmt yuv420p -f v4l2 /dev/video10
+else
+    echo "❌ Camera not reachable at $IPCAM_URL/video. Exiting."
+    exit 42
+fi
+"#;
+
+pub const CURRENT_SERVICE: &str = "[Unit]
+Description=Android IP Camera to V4L2 Virtual Device
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+ExecStart=/usr/local/bin/start-ipcam.sh
+Restart=on-failure
+RestartSec=10
+StartLimitInterval=60
+StartLimitBurst=6
+SuccessExitStatus=42
+StandardOutput=journal
+StandardError=journal
+
+[Install]
+WantedBy=multi-user.target
+";
+
+pub const CURRENT_PATH: &str = "[Unit]
+Description=Watch /etc/ipcam.conf and restart ipcam-stream.service on changes
+
+[Path]
+PathChanged=/etc/ipcam.conf
+
+[Install]
+WantedBy=multi-user.target
+";
+
+pub const CURRENT_PATH_OVERRIDE: &str = "[Path]
+Unit=ipcam-stream.service
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Generation {
+    NotInstalled,
+    Gen1,
+    Gen2,
+    V2,
+    Mixed, // Files from different generations, or hand-edited beyond recognition.
+}
+
+impl fmt::Display for Generation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Generation::NotInstalled => "not installed",
+            Generation::Gen1 => "gen1 (ipcam-service.sh)",
+            Generation::Gen2 => "gen2 (setup-ipcam-service.sh)",
+            Generation::V2 => "v2 (Latest-setup-ipcam-serviceV2.sh)",
+            Generation::Mixed => "mixed / unrecognised",
+        })
+    }
+}
+
+// What one file on disk looks like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileState {
+    Missing,
+    Current,  // Byte-identical to what v2 writes.
+    Gen1,     // Recognisably gen1.
+    Modified, // Present, from the gen2/v2 family, but edited.
+    Unknown,  // Present, matches no generation.
+}
+
+#[derive(Debug, Clone)]
+pub struct Detection {
+    pub generation: Generation,
+    pub files: Vec<(&'static str, FileState)>,
+    pub ipcam_url: Option<String>, // From /etc/ipcam.conf, if set there.
+    pub missing_packages: Vec<&'static str>,
+}
+
+fn on_root(root: &Path, path: &str) -> PathBuf {
+    root.join(path.trim_start_matches('/'))
+}
+
+fn read_optional(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn classify_script(text: &str) -> FileState {
+    if text == CURRENT_SCRIPT {
+        FileState::Current
+    } else if text.contains("BASE_URL=") && !text.contains("exit 42") {
+        FileState::Gen1
+    } else if text.contains("exit 42") && text.contains("curl --silent --fail --head") {
+        FileState::Modified
+    } else {
+        FileState::Unknown
+    }
+}
+
+fn classify_service(text: &str) -> FileState {
+    if text == CURRENT_SERVICE {
+        FileState::Current
+    } else if text.contains("Restart=always") && !text.contains("SuccessExitStatus=42") {
+        FileState::Gen1
+    } else if text.contains("SuccessExitStatus=42") {
+        FileState::Modified
+    } else {
+        FileState::Unknown
+    }
+}
+
+fn classify_exact(text: &str, current: &str) -> FileState {
+    if text == current {
+        FileState::Current
+    } else {
+        FileState::Modified
+    }
+}
+
+// Packages marked "install ok installed" in <root>/var/lib/dpkg/status.
+pub fn installed_packages(root: &Path) -> io::Result<Vec<String>> {
+    let status = read_optional(&on_root(root, "/var/lib/dpkg/status"))?.unwrap_or_default();
+    let mut installed = Vec::new();
+    for stanza in status.split("\n\n") {
+        let field = |name: &str| {
+            stanza.lines().find_map(|l| l.strip_prefix(name).map(|v| v.trim().to_string()))
+        };
+        if let (Some(pkg), Some(state)) = (field("Package:"), field("Status:")) {
+            if state.ends_with(" installed") {
+                installed.push(pkg);
+            }
+        }
+    }
+    Ok(installed)
+}
+
+pub fn detect(root: &Path) -> io::Result<Detection> {
+    let read = |p: &str| read_optional(&on_root(root, p));
+    let script = read(SCRIPT_FILE)?.map_or(FileState::Missing, |t| classify_script(&t));
+    let service = read(SERVICE_FILE)?.map_or(FileState::Missing, |t| classify_service(&t));
+    let path = read(PATH_FILE)?.map_or(FileState::Missing, |t| classify_exact(&t, CURRENT_PATH));
+    let overr = read(PATH_OVERRIDE_CONF)?.map_or(FileState::Missing, |t| classify_exact(&t, CURRENT_PATH_OVERRIDE));
+    let ipcam_url = read(CONFIG_FILE)?
+        .and_then(|t| parse_shell_vars(&t).remove("IPCAM_URL"))
+        .filter(|u| !u.is_empty());
+
+    let installed = installed_packages(root)?;
+    let missing_packages: Vec<&'static str> =
+        REQUIRED_PACKAGES.iter().copied().filter(|p| !installed.iter().any(|i| i == p)).collect();
+
+    // The path unit and its override are identical in every generation, so only
+    // the script and the service decide.
+    let generation = match (script, service) {
+        (FileState::Missing, FileState::Missing) => Generation::NotInstalled,
+        (FileState::Gen1, FileState::Gen1) => Generation::Gen1,
+        (FileState::Current, FileState::Current) => {
+            if missing_packages.iter().any(|p| *p == "ffmpeg" || *p == "curl") {
+                Generation::Gen2
+            } else {
+                Generation::V2
+            }
+        }
+        _ => Generation::Mixed,
+    };
+
+    Ok(Detection {
+        generation,
+        files: vec![(SCRIPT_FILE, script), (SERVICE_FILE, service), (PATH_FILE, path), (PATH_OVERRIDE_CONF, overr)],
+        ipcam_url,
+        missing_packages,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationReport {
+    pub from: Generation,
+    pub backup_dir: Option<PathBuf>,
+    pub written: Vec<&'static str>,
+    pub ipcam_url: String,
+    pub follow_up: Vec<String>, // Commands the operator still has to run.
+}
+
+// Rewrites every managed file that differs from the current layout. All of them
+// are copied to <root>/var/backups/ipcam/migrate-<stamp>/<original path> before
+// the first one is touched, so a failed write never leaves an unsaved original. /etc/ipcam.conf is left alone when it already sets IPCAM_URL, so the
+// configured camera (and any other keys) survive the upgrade.
+pub fn migrate(root: &Path, stamp: &str, dry_run: bool) -> io::Result<MigrationReport> {
+    let detection = detect(root)?;
+    if detection.generation == Generation::NotInstalled {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no ipcam install found; run Latest-setup-ipcam-serviceV2.sh instead",
+        ));
+    }
+
+    let ipcam_url = detection.ipcam_url.clone().unwrap_or_else(|| DEFAULT_IPCAM_URL.to_string());
+    let config_text = read_optional(&on_root(root, CONFIG_FILE))?;
+    let new_config = match &config_text {
+        Some(_) if detection.ipcam_url.is_some() => None,
+        Some(text) => {
+            let separator = if text.is_empty() || text.ends_with('\n') { "" } else { "\n" };
+            Some(format!("{}{}IPCAM_URL={}\n", text, separator, ipcam_url))
+        }
+        None => Some(format!("IPCAM_URL={}\n", ipcam_url)),
+    };
+
+    let mut targets: Vec<(&'static str, String)> = vec![
+        (SCRIPT_FILE, CURRENT_SCRIPT.to_string()),
+        (SERVICE_FILE, CURRENT_SERVICE.to_string()),
+        (PATH_FILE, CURRENT_PATH.to_string()),
+        (PATH_OVERRIDE_CONF, CURRENT_PATH_OVERRIDE.to_string()),
+    ];
+    if let Some(config) = new_config {
+        targets.push((CONFIG_FILE, config));
+    }
+
+    let mut changes = Vec::new();
+    for (path, content) in targets {
+        let old = read_optional(&on_root(root, path))?;
+        if old.as_deref() != Some(content.as_str()) {
+            changes.push((path, old.is_some(), content));
+        }
+    }
+    let written: Vec<&'static str> = changes.iter().map(|&(path, _, _)| path).collect();
+
+    let backup_dir = on_root(root, BACKUP_DIR).join(format!("migrate-{}", stamp));
+    let mut backed_up = false;
+    if !dry_run {
+        for &(path, existed, _) in &changes {
+            if existed {
+                let backup = backup_dir.join(path.trim_start_matches('/'));
+                fs::create_dir_all(backup.parent().unwrap())?;
+                fs::copy(on_root(root, path), &backup)?;
+                backed_up = true;
+            }
+        }
+        for (path, _, content) in changes {
+            let target = on_root(root, path);
+            fs::create_dir_all(target.parent().unwrap())?;
+            fs::write(&target, content)?;
+            if path == SCRIPT_FILE {
+                make_executable(&target)?;
+            }
+        }
+    }
+
+    let mut follow_up = Vec::new();
+    if !detection.missing_packages.is_empty() {
+        follow_up.push(format!("sudo apt install -y {}", detection.missing_packages.join(" ")));
+    }
+    if !written.is_empty() {
+        follow_up.push("sudo systemctl daemon-reload".to_string());
+        follow_up.push("sudo systemctl enable --now ipcam-stream.path".to_string());
+        follow_up.push("sudo systemctl restart ipcam-stream.service".to_string());
+    }
+
+    Ok(MigrationReport {
+        from: detection.generation,
+        backup_dir: if backed_up { Some(backup_dir) } else { None },
+        written,
+        ipcam_url,
+        follow_up,
+    })
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::process;
+
+    // A scratch copy of fixtures/installs/<generation>/root, removed on drop.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn of(generation: &str, test: &str) -> Scratch {
+            let dir = std::env::temp_dir().join(format!("ipcam-migrate-{}-{}", process::id(), test));
+            let _ = fs::remove_dir_all(&dir);
+            copy_tree(&fixture(generation), &dir).unwrap();
+            Scratch(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn fixture(generation: &str) -> PathBuf {
+        crate::fixtures_dir().join("installs").join(generation).join("root")
+    }
+
+    fn copy_tree(from: &Path, to: &Path) -> io::Result<()> {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+            } else {
+                fs::copy(entry.path(), to.join(entry.file_name()))?;
+            }
+        }
+        Ok(())
+    }
+
+    // Every file under `dir` except the backups, keyed by relative path.
+    fn contents(dir: &Path, prefix: &Path, out: &mut BTreeMap<PathBuf, Vec<u8>>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let entry = entry.unwrap();
+            let rel = prefix.join(entry.file_name());
+            if rel.starts_with(BACKUP_DIR.trim_start_matches('/')) {
+                continue;
+            }
+            if entry.file_type().unwrap().is_dir() {
+                contents(&entry.path(), &rel, out);
+            } else {
+                out.insert(rel, fs::read(entry.path()).unwrap());
+            }
+        }
+    }
+
+    fn snapshot(dir: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+        let mut out = BTreeMap::new();
+        contents(dir, Path::new(""), &mut out);
+        out
+    }
+
+    fn states(detection: &Detection) -> Vec<FileState> {
+        detection.files.iter().map(|&(_, state)| state).collect()
+    }
+
+    #[test]
+    fn detects_each_fixture_generation() {
+        let gen1 = detect(&fixture("gen1")).unwrap();
+        assert_eq!(gen1.generation, Generation::Gen1);
+        assert_eq!(states(&gen1)[..2], [FileState::Gen1, FileState::Gen1]);
+        assert_eq!(gen1.ipcam_url.as_deref(), Some("http://10.0.0.42:8080"));
+        assert_eq!(gen1.missing_packages, ["ffmpeg", "curl"]);
+
+        let gen2 = detect(&fixture("gen2")).unwrap();
+        assert_eq!(gen2.generation, Generation::Gen2);
+        assert!(states(&gen2).iter().all(|&s| s == FileState::Current));
+        assert_eq!(gen2.missing_packages, ["ffmpeg", "curl"]);
+
+        let v2 = detect(&fixture("v2")).unwrap();
+        assert_eq!(v2.generation, Generation::V2);
+        assert!(v2.missing_packages.is_empty());
+
+        let empty = detect(&fixture("no-such-generation")).unwrap();
+        assert_eq!(empty.generation, Generation::NotInstalled);
+        assert!(states(&empty).iter().all(|&s| s == FileState::Missing));
+    }
+
+    #[test]
+    fn migrated_gen1_has_v2_files_with_packages_listed_separately() {
+        let scratch = Scratch::of("gen1", "gen1");
+        let report = migrate(&scratch.0, "1", false).unwrap();
+        assert_eq!(report.from, Generation::Gen1);
+        assert!(report.written.contains(&SCRIPT_FILE) && report.written.contains(&SERVICE_FILE));
+        assert!(!report.written.contains(&CONFIG_FILE)); // IPCAM_URL was already set.
+        assert_eq!(report.ipcam_url, "http://10.0.0.42:8080");
+        assert_eq!(report.follow_up[0], "sudo apt install -y ffmpeg curl");
+        let backup = report.backup_dir.unwrap();
+        for path in &report.written {
+            let original = fs::read(fixture("gen1").join(path.trim_start_matches('/'))).unwrap();
+            assert_eq!(fs::read(backup.join(path.trim_start_matches('/'))).unwrap(), original, "{}", path);
+        }
+
+        // The files are v2's now; with ffmpeg and curl still missing that is a gen2 install.
+        let after = detect(&scratch.0).unwrap();
+        assert_eq!(after.generation, Generation::Gen2);
+        assert!(states(&after).iter().all(|&s| s == FileState::Current));
+        assert_eq!(after.missing_packages, ["ffmpeg", "curl"]);
+        assert_eq!(after.ipcam_url.as_deref(), Some("http://10.0.0.42:8080"));
+    }
+
+    #[test]
+    fn migrating_twice_gives_the_same_result() {
+        for generation in ["gen1", "gen2", "v2"] {
+            let scratch = Scratch::of(generation, &format!("twice-{}", generation));
+            migrate(&scratch.0, "1", false).unwrap();
+            let (files, detection) = (snapshot(&scratch.0), detect(&scratch.0).unwrap());
+
+            let again = migrate(&scratch.0, "2", false).unwrap();
+            assert!(again.written.is_empty(), "{}: {:?}", generation, again.written);
+            assert!(again.backup_dir.is_none());
+            assert_eq!(snapshot(&scratch.0), files, "{}", generation);
+            let redetected = detect(&scratch.0).unwrap();
+            assert_eq!(redetected.generation, detection.generation, "{}", generation);
+            assert_eq!(states(&redetected), states(&detection));
+        }
+    }
+
+    #[test]
+    fn a_backup_dir_does_not_change_the_detected_generation() {
+        let scratch = Scratch::of("gen2", "backup-dir");
+        fs::create_dir_all(on_root(&scratch.0, BACKUP_DIR).join("migrate-0")).unwrap();
+        assert_eq!(detect(&scratch.0).unwrap().generation, Generation::Gen2);
+    }
+
+    #[test]
+    fn gen2_migration_only_asks_for_packages() {
+        let scratch = Scratch::of("gen2", "gen2");
+        let report = migrate(&scratch.0, "1", false).unwrap();
+        assert_eq!(report.from, Generation::Gen2);
+        assert!(report.written.is_empty());
+        assert_eq!(report.follow_up, ["sudo apt install -y ffmpeg curl"]);
+        assert_eq!(detect(&scratch.0).unwrap().generation, Generation::Gen2);
+    }
+
+    #[test]
+    fn dry_run_touches_nothing() {
+        let scratch = Scratch::of("gen1", "dry-run");
+        let before = snapshot(&scratch.0);
+        let report = migrate(&scratch.0, "1", true).unwrap();
+        assert!(!report.written.is_empty());
+        assert!(report.backup_dir.is_none());
+        assert_eq!(snapshot(&scratch.0), before);
+        assert_eq!(detect(&scratch.0).unwrap().generation, Generation::Gen1);
+    }
+
+    #[test]
+    fn nothing_to_migrate_without_an_install() {
+        let err = migrate(&fixture("no-such-generation"), "1", true).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
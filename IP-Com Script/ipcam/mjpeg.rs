@@ -0,0 +1,346 @@
+// mjpeg.rs
+// Streaming parser for `multipart/x-mixed-replace` MJPEG, the format IP Webcam
+// serves on `/video`. Until now ffmpeg was the only thing reading that stream.
+//
+// A stream looks like this (the preamble and the Content-Length header are optional):
+//
+//   --BOUNDARY\r\n
+//   Content-Type: image/jpeg\r\n
+//   Content-Length: 5012\r\n
+//   \r\n
+//   <5012 bytes of JPEG, FF D8 ... FF D9>\r\n
+//   --BOUNDARY\r\n
+//   ...
+//
+// `MjpegReader` pulls bytes from any `Read` in whatever chunk sizes it is given,
+// and yields one `Frame` per part. A broken part is reported as an error and the
+// reader then resynchronises on the next boundary, so one bad frame does not end
+// the stream.
+
+use std::fmt;
+use std::io::{self, Read};
+
+const MAX_HEADER_BYTES: usize = 8 * 1024;
+const DEFAULT_MAX_PART_BYTES: usize = 16 * 1024 * 1024;
+const READ_CHUNK: usize = 16 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub headers: Vec<(String, String)>,
+    pub data: Vec<u8>,
+    pub offset: u64, // Byte offset of the part's boundary line in the stream.
+}
+
+impl Frame {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub enum MjpegError {
+    Io(io::Error),
+    NotMultipart(String),                      // Content-Type was something else.
+    Malformed { offset: u64, reason: String }, // One part was bad; iteration continues.
+    Truncated { offset: u64 },                 // The stream ended inside a part.
+}
+
+impl fmt::Display for MjpegError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MjpegError::Io(e) => write!(f, "I/O error: {}", e),
+            MjpegError::NotMultipart(ct) => write!(f, "not a multipart stream (Content-Type: {})", ct),
+            MjpegError::Malformed { offset, reason } => write!(f, "malformed part at byte {}: {}", offset, reason),
+            MjpegError::Truncated { offset } => write!(f, "stream ended inside the part at byte {}", offset),
+        }
+    }
+}
+
+impl From<io::Error> for MjpegError {
+    fn from(e: io::Error) -> MjpegError {
+        MjpegError::Io(e)
+    }
+}
+
+// `multipart/x-mixed-replace;boundary=Ba4oTvQMY8ew04N8dcnM` -> `Ba4oTvQMY8ew04N8dcnM`.
+// Accepts quoted values and cameras that put the leading `--` into the parameter.
+pub fn boundary_from_content_type(content_type: &str) -> Result<String, MjpegError> {
+    let mut params = content_type.split(';');
+    let mime = params.next().unwrap_or("").trim();
+    if !mime.to_ascii_lowercase().starts_with("multipart/") {
+        return Err(MjpegError::NotMultipart(content_type.to_string()));
+    }
+    for param in params {
+        if let Some((key, value)) = param.split_once('=') {
+            if key.trim().eq_ignore_ascii_case("boundary") {
+                let value = value.trim().trim_matches('"');
+                let value = value.strip_prefix("--").unwrap_or(value);
+                if !value.is_empty() {
+                    return Ok(value.to_string());
+                }
+            }
+        }
+    }
+    Err(MjpegError::NotMultipart(format!("{} (no boundary parameter)", content_type)))
+}
+
+pub struct MjpegReader<R: Read> {
+    inner: R,
+    delimiter: Vec<u8>, // "--" + boundary
+    buf: Vec<u8>,
+    start: usize,       // First unconsumed byte in `buf`.
+    consumed: u64,      // Stream offset of `buf[start]`.
+    eof: bool,
+    finished: bool,     // Saw the closing `--BOUNDARY--` or hit EOF.
+    max_part_bytes: usize,
+}
+
+impl<R: Read> MjpegReader<R> {
+    pub fn new(inner: R, boundary: &str) -> MjpegReader<R> {
+        MjpegReader {
+            inner,
+            delimiter: format!("--{}", boundary).into_bytes(),
+            buf: Vec::new(),
+            start: 0,
+            consumed: 0,
+            eof: false,
+            finished: false,
+            max_part_bytes: DEFAULT_MAX_PART_BYTES,
+        }
+    }
+
+    pub fn from_content_type(inner: R, content_type: &str) -> Result<MjpegReader<R>, MjpegError> {
+        Ok(MjpegReader::new(inner, &boundary_from_content_type(content_type)?))
+    }
+
+    fn pending(&self) -> &[u8] {
+        &self.buf[self.start..]
+    }
+
+    fn consume(&mut self, n: usize) {
+        self.start += n;
+        self.consumed += n as u64;
+        // Compact once the dead prefix dominates, so memory stays bounded by one part.
+        if self.start > READ_CHUNK && self.start * 2 > self.buf.len() {
+            self.buf.drain(..self.start);
+            self.start = 0;
+        }
+    }
+
+    // Reads one more chunk. Returns false at end of stream.
+    fn fill(&mut self) -> io::Result<bool> {
+        if self.eof {
+            return Ok(false);
+        }
+        let old_len = self.buf.len();
+        self.buf.resize(old_len + READ_CHUNK, 0);
+        let n = loop {
+            match self.inner.read(&mut self.buf[old_len..]) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.buf.truncate(old_len);
+                    return Err(e);
+                }
+            }
+        };
+        self.buf.truncate(old_len + n);
+        if n == 0 {
+            self.eof = true;
+        }
+        Ok(n > 0)
+    }
+
+    // Position (relative to `start`) of the next delimiter at the start of a line,
+    // reading more input as needed. Searching resumes where the last scan stopped.
+    fn find_delimiter(&mut self, from: usize) -> io::Result<Option<usize>> {
+        let mut scan_from = from;
+        loop {
+            if let Some(i) = find_at_line_start(self.pending(), &self.delimiter, scan_from) {
+                return Ok(Some(i));
+            }
+            let len = self.pending().len();
+            scan_from = len.saturating_sub(self.delimiter.len() + 2).max(from);
+            if len - from > self.max_part_bytes + MAX_HEADER_BYTES {
+                return Ok(None);
+            }
+            if !self.fill()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn next_frame(&mut self) -> Result<Option<Frame>, MjpegError> {
+        // 1. Skip the preamble, or the tail of the previous part, up to the delimiter line.
+        let Some(at) = self.find_delimiter(0)? else {
+            let trailing = self.pending().iter().any(|b| !b.is_ascii_whitespace());
+            let offset = self.consumed;
+            self.consume(self.pending().len());
+            self.finished = true;
+            return if trailing && !self.eof {
+                Err(MjpegError::Malformed { offset, reason: "no boundary within the part size limit".into() })
+            } else {
+                Ok(None)
+            };
+        };
+        self.consume(at);
+        let part_offset = self.consumed;
+
+        // 2. The delimiter line: `--BOUNDARY\r\n`, or `--BOUNDARY--` to close the stream.
+        while self.pending().len() < self.delimiter.len() + 2 && self.fill()? {}
+        let after = &self.pending()[self.delimiter.len().min(self.pending().len())..];
+        if after.starts_with(b"--") {
+            self.consume(self.pending().len());
+            self.finished = true;
+            return Ok(None);
+        }
+        let line_end = loop {
+            if let Some(i) = self.pending().iter().position(|&b| b == b'\n') {
+                break i + 1;
+            }
+            if self.pending().len() > MAX_HEADER_BYTES || !self.fill()? {
+                self.finished = self.eof;
+                self.consume(self.delimiter.len().min(self.pending().len()));
+                return Err(MjpegError::Truncated { offset: part_offset });
+            }
+        };
+        self.consume(line_end);
+
+        // 3. Part headers, up to the first empty line.
+        let mut headers = Vec::new();
+        loop {
+            let line_end = loop {
+                if let Some(i) = self.pending().iter().position(|&b| b == b'\n') {
+                    break i + 1;
+                }
+                if self.pending().len() > MAX_HEADER_BYTES {
+                    return Err(MjpegError::Malformed { offset: part_offset, reason: "part headers too long".into() });
+                }
+                if !self.fill()? {
+                    self.finished = true;
+                    return Err(MjpegError::Truncated { offset: part_offset });
+                }
+            };
+            let line = String::from_utf8_lossy(&self.pending()[..line_end]).trim_end().to_string();
+            self.consume(line_end);
+            if line.is_empty() {
+                break;
+            }
+            match line.split_once(':') {
+                Some((k, v)) => headers.push((k.trim().to_string(), v.trim().to_string())),
+                None => {
+                    return Err(MjpegError::Malformed {
+                        offset: part_offset,
+                        reason: format!("not a header line: {:?}", line),
+                    })
+                }
+            }
+            if headers.len() > 64 {
+                return Err(MjpegError::Malformed { offset: part_offset, reason: "too many part headers".into() });
+            }
+        }
+
+        // 4. The body: exactly Content-Length bytes when given, otherwise up to the next delimiter.
+        let declared = headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("Content-Length"))
+            .map(|(_, v)| v.parse::<usize>());
+        let data = match declared {
+            Some(Ok(len)) if len <= self.max_part_bytes => {
+                while self.pending().len() < len {
+                    if !self.fill()? {
+                        self.finished = true;
+                        self.consume(self.pending().len());
+                        return Err(MjpegError::Truncated { offset: part_offset });
+                    }
+                }
+                let data = self.pending()[..len].to_vec();
+                self.consume(len);
+                data
+            }
+            Some(Ok(len)) => {
+                return Err(MjpegError::Malformed {
+                    offset: part_offset,
+                    reason: format!("Content-Length {} exceeds the {} byte limit", len, self.max_part_bytes),
+                })
+            }
+            Some(Err(_)) => {
+                return Err(MjpegError::Malformed { offset: part_offset, reason: "unparseable Content-Length".into() })
+            }
+            None => match self.find_delimiter(0)? {
+                Some(end) => {
+                    let mut body = &self.pending()[..end];
+                    // The CRLF before the delimiter belongs to the delimiter, not the body.
+                    if body.ends_with(b"\r\n") {
+                        body = &body[..body.len() - 2];
+                    } else if body.ends_with(b"\n") {
+                        body = &body[..body.len() - 1];
+                    }
+                    let data = body.to_vec();
+                    self.consume(end);
+                    data
+                }
+                None => {
+                    self.finished = self.eof;
+                    let len = self.pending().len();
+                    self.consume(len);
+                    return Err(if self.eof {
+                        MjpegError::Truncated { offset: part_offset }
+                    } else {
+                        MjpegError::Malformed { offset: part_offset, reason: "part exceeds the size limit".into() }
+                    });
+                }
+            },
+        };
+
+        if !looks_like_jpeg(&data) {
+            return Err(MjpegError::Malformed {
+                offset: part_offset,
+                reason: format!("{} byte body is not a complete JPEG (missing FF D8 .. FF D9)", data.len()),
+            });
+        }
+        Ok(Some(Frame { headers, data, offset: part_offset }))
+    }
+}
+
+impl<R: Read> Iterator for MjpegReader<R> {
+    type Item = Result<Frame, MjpegError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.next_frame() {
+            Ok(Some(frame)) => Some(Ok(frame)),
+            Ok(None) => None,
+            Err(MjpegError::Io(e)) => {
+                // The reader is in an unknown state after an I/O error; stop here.
+                self.finished = true;
+                Some(Err(MjpegError::Io(e)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+// JPEG starts with SOI (FF D8) and ends with EOI (FF D9); cameras sometimes pad
+// the end with a few zero or whitespace bytes, which is tolerated.
+pub fn looks_like_jpeg(data: &[u8]) -> bool {
+    let end = data
+        .iter()
+        .rposition(|&b| !(b == 0 || b.is_ascii_whitespace()))
+        .map_or(0, |i| i + 1);
+    let data = &data[..end];
+    data.len() >= 4 && data.starts_with(&[0xFF, 0xD8]) && data.ends_with(&[0xFF, 0xD9])
+}
+
+fn find_at_line_start(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if haystack.len() < needle.len() {
+        return None;
+    }
+    (from..=haystack.len() - needle.len())
+        .find(|&i| (i == 0 || haystack[i - 1] == b'\n') && &haystack[i..i + needle.len()] == needle)
+}
@@ -0,0 +1,276 @@
+// retention.rs
+// Which snapshots to keep. Snapshots live in dated directories:
+//
+//   <snapshot dir>/2026-10-19/14-05-30.250.jpg      (UTC, to the millisecond)
+//
+// The milliseconds keep two captures in the same second apart; names without them
+// (`14-05-30.jpg`, written by earlier versions) are still listed.
+//
+// The policy keeps the union of:
+//   - the newest `keep_last` snapshots,
+//   - the newest snapshot of every hour within the last `keep_hourly_hours` hours,
+// and then enforces `max_bytes` by dropping the oldest kept snapshots until the
+// total fits. The very newest snapshot is never deleted.
+//
+// `plan` is a pure function of the listing and the current time, so a policy can
+// be checked against a made-up listing without touching the disk.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub taken_at: u64, // Unix seconds, from the path.
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    pub keep_last: usize,
+    pub keep_hourly_hours: u64,
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    KeepLatest, // Among the newest `keep_last`.
+    KeepHourly, // Newest of its hour, inside the hourly window.
+    Expired,    // Matched no keep rule.
+    OverQuota,  // Would have been kept, but the disk budget ran out.
+}
+
+impl Verdict {
+    pub fn keep(self) -> bool {
+        matches!(self, Verdict::KeepLatest | Verdict::KeepHourly)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Verdict::KeepLatest => "keep (latest)",
+            Verdict::KeepHourly => "keep (hourly)",
+            Verdict::Expired => "delete (expired)",
+            Verdict::OverQuota => "delete (over disk budget)",
+        }
+    }
+}
+
+// Returns one verdict per snapshot, in the same order as `snapshots`.
+pub fn plan(snapshots: &[Snapshot], policy: &RetentionPolicy, now: u64) -> Vec<Verdict> {
+    let mut order: Vec<usize> = (0..snapshots.len()).collect();
+    // Newest first; ties broken by path so the result does not depend on listing order.
+    order.sort_by(|&a, &b| {
+        snapshots[b].taken_at.cmp(&snapshots[a].taken_at).then(snapshots[b].path.cmp(&snapshots[a].path))
+    });
+
+    let mut verdicts = vec![Verdict::Expired; snapshots.len()];
+    let window = policy.keep_hourly_hours * 3600;
+    let mut hours_seen = HashSet::new();
+    for (rank, &i) in order.iter().enumerate() {
+        let snap = &snapshots[i];
+        // keep_last = 0 still keeps the newest one.
+        if rank < policy.keep_last.max(1) {
+            verdicts[i] = Verdict::KeepLatest;
+        }
+        if now.saturating_sub(snap.taken_at) < window
+            && hours_seen.insert(snap.taken_at / 3600)
+            && verdicts[i] != Verdict::KeepLatest
+        {
+            verdicts[i] = Verdict::KeepHourly;
+        }
+    }
+
+    if let Some(max) = policy.max_bytes {
+        let mut total = 0u64;
+        for (rank, &i) in order.iter().enumerate() {
+            if !verdicts[i].keep() {
+                continue;
+            }
+            total += snapshots[i].bytes;
+            if total > max && rank > 0 {
+                verdicts[i] = Verdict::OverQuota;
+                total -= snapshots[i].bytes;
+            }
+        }
+    }
+    verdicts
+}
+
+// Lists `<dir>/YYYY-MM-DD/HH-MM-SS[.mmm].jpg`, oldest first. Other files are ignored.
+pub fn list(dir: &Path) -> io::Result<Vec<Snapshot>> {
+    let mut snapshots = Vec::new();
+    let days = match fs::read_dir(dir) {
+        Ok(days) => days,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(snapshots),
+        Err(e) => return Err(e),
+    };
+    for day in days {
+        let day = day?;
+        let day_name = day.file_name().to_string_lossy().to_string();
+        if !day.file_type()?.is_dir() || parse_date(&day_name).is_none() {
+            continue;
+        }
+        for entry in fs::read_dir(day.path())? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(taken_at) = parse_snapshot_time(&day_name, &name) {
+                snapshots.push(Snapshot { path: entry.path(), taken_at, bytes: entry.metadata()?.len() });
+            }
+        }
+    }
+    snapshots.sort_by(|a, b| a.taken_at.cmp(&b.taken_at).then(a.path.cmp(&b.path)));
+    Ok(snapshots)
+}
+
+// Relative path for a snapshot taken `since_epoch`: "2026-10-19/14-05-30.250.jpg".
+pub fn snapshot_relpath(since_epoch: Duration) -> PathBuf {
+    let unix_secs = since_epoch.as_secs();
+    let (y, mo, d) = civil_from_days((unix_secs / 86_400) as i64);
+    let secs = unix_secs % 86_400;
+    PathBuf::from(format!("{:04}-{:02}-{:02}", y, mo, d)).join(format!(
+        "{:02}-{:02}-{:02}.{:03}.jpg",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        since_epoch.subsec_millis()
+    ))
+}
+
+pub fn format_utc(unix_secs: u64) -> String {
+    let (y, mo, d) = civil_from_days((unix_secs / 86_400) as i64);
+    let secs = unix_secs % 86_400;
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}Z", y, mo, d, secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+fn parse_date(name: &str) -> Option<i64> {
+    let mut parts = name.split('-');
+    let (y, m, d) = (parts.next()?.parse().ok()?, parts.next()?.parse().ok()?, parts.next()?.parse().ok()?);
+    if parts.next().is_some() || name.len() != 10 || !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    Some(days_from_civil(y, m, d))
+}
+
+fn parse_snapshot_time(day_name: &str, file_name: &str) -> Option<u64> {
+    let days = parse_date(day_name)?;
+    let stem = file_name.strip_suffix(".jpg")?;
+    let stem = match stem.split_once('.') {
+        Some((hms, millis)) if millis.len() == 3 && millis.bytes().all(|b| b.is_ascii_digit()) => hms,
+        Some(_) => return None,
+        None => stem,
+    };
+    let mut parts = stem.split('-');
+    let (h, m, s): (u64, u64, u64) =
+        (parts.next()?.parse().ok()?, parts.next()?.parse().ok()?, parts.next()?.parse().ok()?);
+    if parts.next().is_some() || stem.len() != 8 || h > 23 || m > 59 || s > 60 {
+        return None;
+    }
+    u64::try_from(days).ok().map(|days| days * 86_400 + h * 3600 + m * 60 + s)
+}
+
+// Howard Hinnant's days <-> civil date algorithms (proleptic Gregorian, UTC).
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(m <= 2), m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 100 * 3600 + 3000; // 50 minutes into hour 100.
+
+    fn snap(taken_at: u64, bytes: u64) -> Snapshot {
+        Snapshot { path: PathBuf::from(format!("{}.jpg", taken_at)), taken_at, bytes }
+    }
+
+    fn policy(keep_last: usize, keep_hourly_hours: u64, max_bytes: Option<u64>) -> RetentionPolicy {
+        RetentionPolicy { keep_last, keep_hourly_hours, max_bytes }
+    }
+
+    #[test]
+    fn keep_last_keeps_the_newest_n_in_any_listing_order() {
+        let snapshots = [snap(NOW - 30, 1), snap(NOW - 10, 1), snap(NOW - 40, 1), snap(NOW - 20, 1)];
+        let verdicts = plan(&snapshots, &policy(2, 0, None), NOW);
+        use Verdict::*;
+        assert_eq!(verdicts, [Expired, KeepLatest, Expired, KeepLatest]);
+    }
+
+    #[test]
+    fn hourly_window_keeps_the_newest_of_each_hour_inside_it() {
+        let snapshots = [
+            snap(NOW - 100, 1),        // Hour 100, newest overall.
+            snap(NOW - 200, 1),        // Hour 100 again.
+            snap(99 * 3600 + 10, 1),   // Newest of hour 99.
+            snap(99 * 3600 + 5, 1),    // Older in hour 99.
+            snap(96 * 3600 + 5, 1),    // Hour 96, outside a 3 hour window.
+        ];
+        let verdicts = plan(&snapshots, &policy(1, 3, None), NOW);
+        use Verdict::*;
+        assert_eq!(verdicts, [KeepLatest, Expired, KeepHourly, Expired, Expired]);
+    }
+
+    #[test]
+    fn max_bytes_drops_the_oldest_kept_snapshots_first() {
+        let snapshots = [snap(NOW - 30, 40), snap(NOW - 20, 40), snap(NOW - 10, 40), snap(NOW - 5, 10)];
+        let verdicts = plan(&snapshots, &policy(4, 0, Some(60)), NOW);
+        use Verdict::*;
+        assert_eq!(verdicts, [OverQuota, OverQuota, KeepLatest, KeepLatest]);
+    }
+
+    #[test]
+    fn newest_snapshot_is_never_deleted() {
+        let snapshots = [snap(NOW - 7200, 10), snap(NOW - 3600, 500)];
+        for p in [policy(0, 0, None), policy(0, 0, Some(1)), policy(3, 24, Some(0))] {
+            let verdicts = plan(&snapshots, &p, NOW);
+            assert!(verdicts[1].keep(), "{:?} -> {:?}", p, verdicts);
+        }
+    }
+
+    #[test]
+    fn relpath_round_trips_through_the_listing_parser() {
+        let at = 1_760_882_730; // 2025-10-19 14:05:30Z
+        let rel = snapshot_relpath(Duration::from_millis(at * 1000 + 25));
+        assert_eq!(rel, PathBuf::from("2025-10-19/14-05-30.025.jpg"));
+        assert_eq!(format_utc(at), "2025-10-19 14:05:30Z");
+        let day = rel.parent().unwrap().to_str().unwrap();
+        let file = rel.file_name().unwrap().to_str().unwrap();
+        assert_eq!(parse_snapshot_time(day, file), Some(at));
+    }
+
+    #[test]
+    fn names_without_milliseconds_are_still_listed() {
+        assert_eq!(parse_snapshot_time("2025-10-19", "14-05-30.jpg"), Some(1_760_882_730));
+        for bad in ["14-05-30.5.jpg", "14-05-30.abc.jpg", "14-05-30.025.1.jpg", "14-05-30.jpg.part"] {
+            assert_eq!(parse_snapshot_time("2025-10-19", bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn same_second_captures_order_by_their_milliseconds() {
+        let second = |millis| Snapshot { path: snapshot_relpath(Duration::from_millis(NOW * 1000 + millis)), taken_at: NOW, bytes: 1 };
+        let snapshots = [second(900), second(100)];
+        use Verdict::*;
+        assert_eq!(plan(&snapshots, &policy(1, 0, None), NOW), [KeepLatest, Expired]);
+    }
+}
@@ -0,0 +1,270 @@
+// snapshot.rs
+// Saves a still frame every N seconds next to the /dev/video10 feed, then applies
+// the retention policy. Settings come from /etc/ipcam.conf:
+//
+//   IPCAM_SNAPSHOT_DIR=/var/lib/ipcam/snapshots
+//   IPCAM_SNAPSHOT_INTERVAL=60        # seconds between captures
+//   IPCAM_KEEP_LAST=100               # always keep the newest N
+//   IPCAM_KEEP_HOURLY_HOURS=24        # plus one per hour for this many hours
+//   IPCAM_MAX_DISK_MB=500             # hard cap on the whole directory
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::IpcamConfig;
+use crate::http;
+use crate::logfmt;
+use crate::mjpeg::{self, MjpegReader};
+use crate::retention::{self, RetentionPolicy};
+
+pub const DEFAULT_SNAPSHOT_DIR: &str = "/var/lib/ipcam/snapshots";
+const MAX_SHOT_BYTES: u64 = 16 * 1024 * 1024;
+
+pub struct SnapshotSettings {
+    pub dir: PathBuf,
+    pub interval: Duration,
+    pub policy: RetentionPolicy,
+}
+
+impl SnapshotSettings {
+    pub fn from_config(config: &IpcamConfig) -> io::Result<SnapshotSettings> {
+        Ok(SnapshotSettings {
+            dir: PathBuf::from(config.text("IPCAM_SNAPSHOT_DIR").unwrap_or(DEFAULT_SNAPSHOT_DIR)),
+            interval: config.seconds("IPCAM_SNAPSHOT_INTERVAL")?.unwrap_or(Duration::from_secs(60)),
+            policy: RetentionPolicy {
+                keep_last: config.number("IPCAM_KEEP_LAST")?.unwrap_or(100) as usize,
+                keep_hourly_hours: config.number("IPCAM_KEEP_HOURLY_HOURS")?.unwrap_or(24),
+                max_bytes: Some(config.megabytes("IPCAM_MAX_DISK_MB")?.unwrap_or(500 * 1024 * 1024)),
+            },
+        })
+    }
+}
+
+pub fn since_epoch() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+pub fn now_unix() -> u64 {
+    since_epoch().as_secs()
+}
+
+// One JPEG from the camera: `/shot.jpg` first, and if that is missing or not a
+// JPEG, the first good frame of the `/video` MJPEG stream.
+pub fn fetch_jpeg(config: &IpcamConfig) -> Result<Vec<u8>, String> {
+    let shot_err = match fetch_shot(&config.shot_url(), config.probe_timeout) {
+        Ok(jpeg) => return Ok(jpeg),
+        Err(e) => e,
+    };
+    let resp = http::request("GET", &config.video_url(), config.probe_timeout)
+        .map_err(|e| format!("{} ({}); {}: {}", config.shot_url(), shot_err, config.video_url(), e))?;
+    if !resp.is_success() {
+        return Err(format!("{}: HTTP {}", config.video_url(), resp.status));
+    }
+    let content_type = resp.header("Content-Type").unwrap_or("").to_string();
+    let frames = MjpegReader::from_content_type(resp.body, &content_type).map_err(|e| e.to_string())?;
+    match frames.take(8).flatten().next() {
+        Some(frame) => Ok(frame.data),
+        None => Err(format!("no complete frame from {}", config.video_url())),
+    }
+}
+
+fn fetch_shot(url: &str, timeout: Duration) -> Result<Vec<u8>, String> {
+    let resp = http::request("GET", url, timeout).map_err(|e| e.to_string())?;
+    if !resp.is_success() {
+        return Err(format!("HTTP {}", resp.status));
+    }
+    let declared = resp.header("Content-Length").and_then(|v| v.parse::<u64>().ok());
+    let mut body = Vec::new();
+    resp.body
+        .take(declared.unwrap_or(MAX_SHOT_BYTES).min(MAX_SHOT_BYTES))
+        .read_to_end(&mut body)
+        .map_err(|e| e.to_string())?;
+    if !mjpeg::looks_like_jpeg(&body) {
+        return Err(format!("{} byte response is not a JPEG", body.len()));
+    }
+    Ok(body)
+}
+
+// Writes `jpeg` under its dated path. Goes through a temporary file so a
+// half-written snapshot is never picked up by `list`.
+pub fn save(dir: &Path, taken_at: Duration, jpeg: &[u8]) -> io::Result<PathBuf> {
+    let path = dir.join(retention::snapshot_relpath(taken_at));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("jpg.part");
+    fs::write(&tmp, jpeg)?;
+    fs::rename(&tmp, &path)?;
+    Ok(path)
+}
+
+// Deletes what the policy does not keep, then removes emptied day directories.
+// Returns (deleted files, bytes freed).
+pub fn prune(dir: &Path, policy: &RetentionPolicy, now: u64, dry_run: bool) -> io::Result<(usize, u64)> {
+    let snapshots = retention::list(dir)?;
+    let verdicts = retention::plan(&snapshots, policy, now);
+    let (mut files, mut bytes) = (0, 0);
+    for (snap, verdict) in snapshots.iter().zip(&verdicts) {
+        if verdict.keep() {
+            continue;
+        }
+        if !dry_run {
+            fs::remove_file(&snap.path)?;
+            if let Some(day) = snap.path.parent() {
+                let _ = fs::remove_dir(day); // Only succeeds once the day is empty.
+            }
+        }
+        files += 1;
+        bytes += snap.bytes;
+    }
+    Ok((files, bytes))
+}
+
+// Captures every `settings.interval` until `count` snapshots were attempted
+// (None = forever). A failed capture is logged and retried at the next tick.
+pub fn capture_loop(config: &IpcamConfig, settings: &SnapshotSettings, count: Option<u64>) {
+    let mut attempts = 0u64;
+    let mut next_tick = Instant::now();
+    while count.is_none_or(|max| attempts < max) {
+        attempts += 1;
+        let taken_at = since_epoch();
+        match fetch_jpeg(config).and_then(|jpeg| save(&settings.dir, taken_at, &jpeg).map_err(|e| e.to_string())) {
+            Ok(path) => logfmt::event("info", "snapshot", &[("path", path.display().to_string())]),
+            Err(e) => logfmt::event("warn", "snapshot_failed", &[("error", e)]),
+        }
+        match prune(&settings.dir, &settings.policy, taken_at.as_secs(), false) {
+            Ok((0, _)) => {}
+            Ok((files, bytes)) => {
+                logfmt::event("info", "pruned", &[("files", files.to_string()), ("bytes", bytes.to_string())])
+            }
+            Err(e) => logfmt::event("warn", "prune_failed", &[("error", e.to_string())]),
+        }
+
+        if count.is_some_and(|max| attempts >= max) {
+            break;
+        }
+        // Fixed-rate schedule; a slow capture does not push later ones back.
+        next_tick += settings.interval;
+        let now = Instant::now();
+        if next_tick > now {
+            thread::sleep(next_tick - now);
+        } else {
+            next_tick = now;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::{StubCamera, FRAME_JPEG};
+    use std::process;
+
+    // An empty directory under the system temp dir, removed on drop.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(test: &str) -> Scratch {
+            let dir = std::env::temp_dir().join(format!("ipcam-snapshot-{}-{}", process::id(), test));
+            let _ = fs::remove_dir_all(&dir);
+            Scratch(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn camera_config(camera: &StubCamera) -> IpcamConfig {
+        IpcamConfig { ipcam_url: camera.base_url(), probe_timeout: Duration::from_secs(2), ..IpcamConfig::defaults() }
+    }
+
+    // 5ms apart, so no two captures share a millisecond.
+    fn settings(dir: &Path, keep_last: usize, keep_hourly_hours: u64) -> SnapshotSettings {
+        let policy = RetentionPolicy { keep_last, keep_hourly_hours, max_bytes: None };
+        SnapshotSettings { dir: dir.to_path_buf(), interval: Duration::from_millis(5), policy }
+    }
+
+    #[test]
+    fn fetch_jpeg_gets_the_stub_shot_and_fails_once_it_is_down() {
+        let camera = StubCamera::start(0, Duration::from_millis(50)).unwrap();
+        assert_eq!(fetch_jpeg(&camera_config(&camera)).unwrap(), FRAME_JPEG);
+        camera.set_up(false);
+        thread::sleep(Duration::from_millis(100)); // The stub closes its socket within 10ms.
+        assert!(fetch_jpeg(&camera_config(&camera)).is_err());
+    }
+
+    #[test]
+    fn capture_loop_writes_one_dated_jpeg_per_attempt() {
+        let camera = StubCamera::start(0, Duration::from_millis(50)).unwrap();
+        let scratch = Scratch::new("capture");
+        let before = now_unix();
+        capture_loop(&camera_config(&camera), &settings(&scratch.0, 10, 0), Some(3));
+        let after = now_unix();
+
+        let snapshots = retention::list(&scratch.0).unwrap();
+        assert_eq!(snapshots.len(), 3, "{:?}", snapshots);
+        for snap in &snapshots {
+            assert!((before..=after).contains(&snap.taken_at), "{:?}", snap);
+            let day = retention::snapshot_relpath(Duration::from_secs(snap.taken_at));
+            assert_eq!(snap.path.parent(), scratch.0.join(day).parent());
+            assert_eq!(fs::read(&snap.path).unwrap(), FRAME_JPEG);
+        }
+    }
+
+    #[test]
+    fn two_saves_in_the_same_second_keep_both_files() {
+        let scratch = Scratch::new("same-second");
+        let second = Duration::from_secs(1_760_882_730);
+        let first = save(&scratch.0, second + Duration::from_millis(100), b"first").unwrap();
+        let second = save(&scratch.0, second + Duration::from_millis(900), b"second").unwrap();
+        assert_ne!(first, second);
+        assert_eq!((fs::read(&first).unwrap(), fs::read(&second).unwrap()), (b"first".to_vec(), b"second".to_vec()));
+    }
+
+    #[test]
+    fn capture_loop_prunes_old_snapshots_and_their_emptied_days() {
+        let scratch = Scratch::new("prune");
+        let three_days_ago = since_epoch() - Duration::from_secs(3 * 86_400);
+        let old = [
+            save(&scratch.0, three_days_ago, FRAME_JPEG).unwrap(),
+            save(&scratch.0, three_days_ago + Duration::from_secs(60), FRAME_JPEG).unwrap(),
+        ];
+        let camera = StubCamera::start(0, Duration::from_millis(50)).unwrap();
+        capture_loop(&camera_config(&camera), &settings(&scratch.0, 1, 24), Some(1));
+
+        let snapshots = retention::list(&scratch.0).unwrap();
+        assert_eq!(snapshots.len(), 1, "{:?}", snapshots);
+        assert!(snapshots[0].taken_at > three_days_ago.as_secs());
+        assert!(old.iter().all(|path| !path.exists()));
+        assert!(!old[0].parent().unwrap().exists(), "the emptied day directory is removed");
+    }
+
+    #[test]
+    fn a_dry_run_prune_reports_without_deleting() {
+        let scratch = Scratch::new("dry-run");
+        let now = since_epoch();
+        let old = save(&scratch.0, now - Duration::from_secs(86_400), b"old").unwrap();
+        save(&scratch.0, now, b"new").unwrap();
+        let policy = RetentionPolicy { keep_last: 1, keep_hourly_hours: 0, max_bytes: None };
+        assert_eq!(prune(&scratch.0, &policy, now.as_secs(), true).unwrap(), (1, 3));
+        assert!(old.exists());
+        assert_eq!(prune(&scratch.0, &policy, now.as_secs(), false).unwrap(), (1, 3));
+        assert!(!old.exists());
+    }
+
+    #[test]
+    fn a_disk_cap_too_large_for_64_bits_is_a_config_error() {
+        let mut config = IpcamConfig::defaults();
+        config.raw.insert("IPCAM_MAX_DISK_MB".into(), u64::MAX.to_string());
+        let err = SnapshotSettings::from_config(&config).err().expect("an overflowing cap is rejected");
+        assert!(err.to_string().contains("IPCAM_MAX_DISK_MB"), "{}", err);
+        config.raw.insert("IPCAM_MAX_DISK_MB".into(), "2".into());
+        assert_eq!(SnapshotSettings::from_config(&config).unwrap().policy.max_bytes, Some(2 * 1024 * 1024));
+    }
+}
@@ -0,0 +1,328 @@
+// status.rs
+// `ipcam status`: everything you would otherwise check by hand with
+// `systemctl is-active` / `is-enabled`, `ls /dev/video10`, `lsmod` and curl.
+//
+// The systemd and sysfs readers are traits so the report can be produced from
+// fixture files (see fixtures/status/) instead of the live machine.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::VIDEO_DEVICE;
+use crate::supervisor::Probe;
+
+pub const UNITS: [&str; 2] = ["ipcam-stream.service", "ipcam-stream.path"];
+pub const CAMERA_UNREACHABLE_EXIT: i32 = 42; // start-ipcam.sh: "Camera not reachable ... Exiting."
+
+// Properties of one unit, as printed by `systemctl show -p ... UNIT`.
+pub trait SystemdReader {
+    fn show(&self, unit: &str) -> io::Result<BTreeMap<String, String>>;
+}
+
+// Device nodes and kernel modules.
+pub trait HostReader {
+    fn path_exists(&self, path: &str) -> bool;
+    fn module_loaded(&self, module: &str) -> bool;
+}
+
+const SHOW_PROPERTIES: &str = "LoadState,ActiveState,SubState,UnitFileState,Result,ExecMainCode,ExecMainStatus";
+
+pub struct Systemctl;
+
+impl SystemdReader for Systemctl {
+    fn show(&self, unit: &str) -> io::Result<BTreeMap<String, String>> {
+        let out = Command::new("systemctl").args(["show", "-p", SHOW_PROPERTIES, unit]).output()?;
+        if !out.status.success() {
+            return Err(io::Error::other(String::from_utf8_lossy(&out.stderr).trim().replace('\n', "; ")));
+        }
+        Ok(parse_show(&String::from_utf8_lossy(&out.stdout)))
+    }
+}
+
+// Reads `<dir>/<unit>.show`, captured with `systemctl show -p ... <unit> > <unit>.show`.
+pub struct SystemdFixtures(pub PathBuf);
+
+impl SystemdReader for SystemdFixtures {
+    fn show(&self, unit: &str) -> io::Result<BTreeMap<String, String>> {
+        Ok(parse_show(&fs::read_to_string(self.0.join(format!("{}.show", unit)))?))
+    }
+}
+
+// The live host is `HostRoot("/")`; a fixture directory stands in for `/` otherwise.
+pub struct HostRoot(pub PathBuf);
+
+impl HostReader for HostRoot {
+    fn path_exists(&self, path: &str) -> bool {
+        self.0.join(path.trim_start_matches('/')).exists()
+    }
+
+    fn module_loaded(&self, module: &str) -> bool {
+        self.0.join("sys/module").join(module).is_dir()
+    }
+}
+
+pub fn parse_show(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitStatus {
+    pub name: String,
+    pub active: String,         // active / inactive / failed / activating ...
+    pub sub: String,            // running / dead / waiting / auto-restart ...
+    pub enabled: String,        // enabled / disabled / static / not-found
+    pub last_exit: Option<i32>, // ExecMainStatus, services only.
+    pub error: Option<String>,
+}
+
+impl UnitStatus {
+    pub fn exit_meaning(&self) -> Option<&'static str> {
+        match self.last_exit? {
+            0 => Some("clean exit"),
+            CAMERA_UNREACHABLE_EXIT => Some("camera unreachable (start-ipcam.sh exit 42)"),
+            _ => Some("ffmpeg or script failure"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusReport {
+    pub units: Vec<UnitStatus>,
+    pub video_device: bool,
+    pub module_loaded: bool,
+    pub camera_url: String,
+    pub camera: Option<Result<(), String>>, // None when probing was skipped.
+}
+
+impl StatusReport {
+    pub fn healthy(&self) -> bool {
+        self.units.iter().all(|u| u.error.is_none() && u.enabled == "enabled")
+            && self.units.iter().any(|u| u.name.ends_with(".service") && u.active == "active")
+            && self.video_device
+            && self.module_loaded
+            && !matches!(self.camera, Some(Err(_)))
+    }
+}
+
+pub fn gather(
+    systemd: &dyn SystemdReader,
+    host: &dyn HostReader,
+    camera_url: &str,
+    probe: Option<&mut dyn Probe>,
+) -> StatusReport {
+    let units = UNITS
+        .iter()
+        .map(|&name| match systemd.show(name) {
+            Ok(props) => {
+                let get = |k: &str| props.get(k).cloned().unwrap_or_default();
+                let not_found = get("LoadState") == "not-found";
+                // ExecMainCode 1 is CLD_EXITED; otherwise ExecMainStatus holds a signal number or nothing.
+                let exited = get("ExecMainCode") == "1";
+                UnitStatus {
+                    name: name.to_string(),
+                    active: get("ActiveState"),
+                    sub: get("SubState"),
+                    enabled: if not_found { "not-found".to_string() } else { get("UnitFileState") },
+                    last_exit: if exited { get("ExecMainStatus").parse().ok() } else { None },
+                    error: if not_found { Some("unit is not installed".to_string()) } else { None },
+                }
+            }
+            Err(e) => UnitStatus {
+                name: name.to_string(),
+                active: String::new(),
+                sub: String::new(),
+                enabled: String::new(),
+                last_exit: None,
+                error: Some(e.to_string()),
+            },
+        })
+        .collect();
+    StatusReport {
+        units,
+        video_device: host.path_exists(VIDEO_DEVICE),
+        module_loaded: host.module_loaded("v4l2loopback"),
+        camera_url: camera_url.to_string(),
+        camera: probe.map(|p| p.probe()),
+    }
+}
+
+pub fn render_text(report: &StatusReport) -> String {
+    let mut out = String::new();
+    let mark = |ok: bool| if ok { "ok  " } else { "FAIL" };
+    for unit in &report.units {
+        match &unit.error {
+            Some(e) => writeln!(out, "{} {:<22} {}", mark(false), unit.name, e).unwrap(),
+            None => {
+                let ok = unit.enabled == "enabled" && unit.active != "failed";
+                write!(out, "{} {:<22} {} ({}), {}", mark(ok), unit.name, unit.active, unit.sub, unit.enabled).unwrap();
+                if let (Some(code), Some(meaning)) = (unit.last_exit, unit.exit_meaning()) {
+                    write!(out, ", last exit {}: {}", code, meaning).unwrap();
+                }
+                out.push('\n');
+            }
+        }
+    }
+    writeln!(out, "{} {:<22} {}", mark(report.video_device), VIDEO_DEVICE,
+        if report.video_device { "present" } else { "missing" }).unwrap();
+    writeln!(out, "{} {:<22} {}", mark(report.module_loaded), "v4l2loopback",
+        if report.module_loaded { "loaded" } else { "not loaded" }).unwrap();
+    match &report.camera {
+        None => writeln!(out, "-    {:<22} not probed", report.camera_url).unwrap(),
+        Some(Ok(())) => writeln!(out, "ok   {:<22} reachable", report.camera_url).unwrap(),
+        Some(Err(e)) => writeln!(out, "FAIL {:<22} unreachable: {}", report.camera_url, e).unwrap(),
+    }
+    writeln!(out, "overall: {}", if report.healthy() { "healthy" } else { "needs attention" }).unwrap();
+    out
+}
+
+pub fn render_json(report: &StatusReport) -> String {
+    let opt = |s: &Option<String>| s.as_deref().map_or("null".to_string(), json_string);
+    let units: Vec<String> = report
+        .units
+        .iter()
+        .map(|u| {
+            format!(
+                "{{\"name\":{},\"active\":{},\"sub\":{},\"enabled\":{},\"last_exit\":{},\"last_exit_meaning\":{},\"error\":{}}}",
+                json_string(&u.name),
+                json_string(&u.active),
+                json_string(&u.sub),
+                json_string(&u.enabled),
+                u.last_exit.map_or("null".to_string(), |c| c.to_string()),
+                u.exit_meaning().map_or("null".to_string(), json_string),
+                opt(&u.error)
+            )
+        })
+        .collect();
+    let (reachable, camera_error) = match &report.camera {
+        None => ("null".to_string(), "null".to_string()),
+        Some(Ok(())) => ("true".to_string(), "null".to_string()),
+        Some(Err(e)) => ("false".to_string(), json_string(e)),
+    };
+    format!(
+        "{{\"units\":[{}],\"video_device\":{{\"path\":{},\"present\":{}}},\"v4l2loopback_loaded\":{},\
+         \"camera\":{{\"url\":{},\"reachable\":{},\"error\":{}}},\"healthy\":{}}}",
+        units.join(","),
+        json_string(VIDEO_DEVICE),
+        report.video_device,
+        report.module_loaded,
+        json_string(&report.camera_url),
+        reachable,
+        camera_error,
+        report.healthy()
+    )
+}
+
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Convenience for the command line: fixture directory or the live machine.
+pub fn readers(fixtures: Option<&Path>) -> (Box<dyn SystemdReader>, Box<dyn HostReader>) {
+    match fixtures {
+        Some(dir) => (Box::new(SystemdFixtures(dir.to_path_buf())), Box::new(HostRoot(dir.join("root")))),
+        None => (Box::new(Systemctl), Box::new(HostRoot(PathBuf::from("/")))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Answer(Result<(), String>);
+
+    impl Probe for Answer {
+        fn probe(&mut self) -> Result<(), String> {
+            self.0.clone()
+        }
+    }
+
+    const URL: &str = "http://192.168.1.50:8080/video";
+
+    // fixtures/status/<name>.
+    fn report(name: &str, probe: Option<&mut dyn Probe>) -> StatusReport {
+        let dir = crate::fixtures_dir().join("status").join(name);
+        let (systemd, host) = readers(Some(&dir));
+        gather(systemd.as_ref(), host.as_ref(), URL, probe)
+    }
+
+    #[test]
+    fn healthy_fixture() {
+        let report = report("healthy", Some(&mut Answer(Ok(()))));
+        assert!(report.healthy());
+        assert!(report.units.iter().all(|u| u.error.is_none() && u.enabled == "enabled"));
+        assert_eq!(report.units[0].last_exit, None); // ExecMainCode=0: still running, no exit yet.
+
+        let text = render_text(&report);
+        assert!(text.contains("ok   ipcam-stream.service   active (running), enabled\n"), "{}", text);
+        assert!(text.contains("ok   /dev/video10           present\n"), "{}", text);
+        assert!(text.ends_with("overall: healthy\n"), "{}", text);
+        let json = render_json(&report);
+        assert!(json.contains("\"camera\":{\"url\":\"http://192.168.1.50:8080/video\",\"reachable\":true,\"error\":null}"), "{}", json);
+        assert!(json.ends_with("\"healthy\":true}"), "{}", json);
+    }
+
+    #[test]
+    fn camera_down_fixture() {
+        let report = report("camera-down", Some(&mut Answer(Err("connection refused".into()))));
+        assert!(!report.healthy());
+        assert_eq!(report.units[0].last_exit, Some(CAMERA_UNREACHABLE_EXIT));
+        assert!(!report.video_device && !report.module_loaded);
+
+        let text = render_text(&report);
+        assert!(text.contains("inactive (dead), enabled, last exit 42: camera unreachable (start-ipcam.sh exit 42)"), "{}", text);
+        assert!(text.contains("FAIL /dev/video10           missing\n"), "{}", text);
+        assert!(text.contains("unreachable: connection refused\n"), "{}", text);
+        assert!(text.ends_with("overall: needs attention\n"), "{}", text);
+        let json = render_json(&report);
+        assert!(json.contains("\"last_exit\":42,\"last_exit_meaning\":\"camera unreachable (start-ipcam.sh exit 42)\""), "{}", json);
+        assert!(json.contains("\"reachable\":false,\"error\":\"connection refused\""), "{}", json);
+        assert!(json.ends_with("\"healthy\":false}"), "{}", json);
+    }
+
+    #[test]
+    fn not_installed_fixture() {
+        let report = report("not-installed", None);
+        assert!(!report.healthy());
+        assert!(report.units.iter().all(|u| u.enabled == "not-found" && u.error.as_deref() == Some("unit is not installed")));
+
+        let text = render_text(&report);
+        assert!(text.contains("FAIL ipcam-stream.path      unit is not installed\n"), "{}", text);
+        assert!(text.contains("not probed\n"), "{}", text);
+        let json = render_json(&report);
+        assert!(json.contains("\"reachable\":null,\"error\":null"), "{}", json);
+        assert!(json.ends_with("\"healthy\":false}"), "{}", json);
+    }
+
+    #[test]
+    fn missing_fixture_directory_is_reported_per_unit() {
+        let report = report("no-such-fixture", None);
+        assert!(!report.healthy());
+        assert!(report.units.iter().all(|u| u.error.is_some()));
+    }
+
+    #[test]
+    fn json_string_escapes_control_characters() {
+        assert_eq!(json_string("a\"b\\c\n\u{1}"), "\"a\\\"b\\\\c\\n\\u0001\"");
+    }
+}
@@ -0,0 +1,193 @@
+// stub.rs
+// A fake IP Webcam on loopback that can be switched up and down, so the
+// tooling can be exercised without a phone on the network.
+//
+//   HEAD/GET /video     multipart/x-mixed-replace MJPEG, one frame every `frame_interval`
+//   GET      /shot.jpg  a single JPEG
+//
+// "Down" means the listening socket is closed (connection refused, like a phone
+// that left the Wi-Fi) and every open stream is cut.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+pub const BOUNDARY: &str = "Ba4oTvQMY8ew04N8dcnM"; // What IP Webcam sends.
+pub const FRAME_JPEG: &[u8] = include_bytes!("fixtures/frame.jpg");
+
+pub struct StubCamera {
+    addr: SocketAddr,
+    up: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    generation: Arc<AtomicU64>, // Bumped on every up->down so streams notice.
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl StubCamera {
+    // Binds 127.0.0.1:`port` (0 picks a free port) and starts in the "up" state.
+    pub fn start(port: u16, frame_interval: Duration) -> io::Result<StubCamera> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let addr = listener.local_addr()?;
+        let up = Arc::new(AtomicBool::new(true));
+        let stop = Arc::new(AtomicBool::new(false));
+        let generation = Arc::new(AtomicU64::new(0));
+
+        let (up_t, stop_t, gen_t) = (up.clone(), stop.clone(), generation.clone());
+        let handle = thread::spawn(move || {
+            let mut listener = Some(listener);
+            while !stop_t.load(Ordering::SeqCst) {
+                if !up_t.load(Ordering::SeqCst) {
+                    listener = None;
+                    thread::sleep(Duration::from_millis(10));
+                    continue;
+                }
+                if listener.is_none() {
+                    // std sets SO_REUSEADDR on Unix, so rebinding the same port works.
+                    match TcpListener::bind(addr) {
+                        Ok(l) => listener = Some(l),
+                        Err(_) => {
+                            thread::sleep(Duration::from_millis(10));
+                            continue;
+                        }
+                    }
+                }
+                let l = listener.as_ref().unwrap();
+                l.set_nonblocking(true).ok();
+                match l.accept() {
+                    Ok((stream, _)) => {
+                        let (up_c, gen_c) = (up_t.clone(), gen_t.clone());
+                        let my_gen = gen_t.load(Ordering::SeqCst);
+                        thread::spawn(move || {
+                            let _ = serve(stream, &up_c, &gen_c, my_gen, frame_interval);
+                        });
+                    }
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(5));
+                    }
+                    Err(_) => thread::sleep(Duration::from_millis(5)),
+                }
+            }
+        });
+
+        Ok(StubCamera { addr, up, stop, generation, handle: Some(handle) })
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn set_up(&self, up: bool) {
+        if !up && self.up.load(Ordering::SeqCst) {
+            self.generation.fetch_add(1, Ordering::SeqCst);
+        }
+        self.up.store(up, Ordering::SeqCst);
+    }
+
+    // Plays `phases` once, e.g. [(false, 2s), (true, 5s)].
+    pub fn play(&self, phases: &[(bool, Duration)]) {
+        for &(up, duration) in phases {
+            self.set_up(up);
+            thread::sleep(duration);
+        }
+    }
+}
+
+impl Drop for StubCamera {
+    fn drop(&mut self) {
+        self.set_up(false);
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// Parses "up:5,down:2.5,up:10" (seconds) into phases.
+pub fn parse_script(spec: &str) -> Result<Vec<(bool, Duration)>, String> {
+    spec.split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|step| {
+            let (state, secs) = step
+                .trim()
+                .split_once(':')
+                .ok_or_else(|| format!("expected up:SECS or down:SECS, got {:?}", step))?;
+            let up = match state {
+                "up" => true,
+                "down" => false,
+                other => return Err(format!("unknown state {:?} (use up or down)", other)),
+            };
+            let secs: f64 = secs.parse().map_err(|_| format!("bad duration in {:?}", step))?;
+            if !secs.is_finite() || secs < 0.0 {
+                return Err(format!("bad duration in {:?}", step));
+            }
+            Ok((up, Duration::from_secs_f64(secs)))
+        })
+        .collect()
+}
+
+fn serve(
+    stream: TcpStream,
+    up: &AtomicBool,
+    generation: &AtomicU64,
+    my_gen: u64,
+    frame_interval: Duration,
+) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+    let mut out = stream;
+
+    match path {
+        "/video" => {
+            write!(
+                out,
+                "HTTP/1.1 200 OK\r\nContent-Type: multipart/x-mixed-replace;boundary={}\r\nConnection: close\r\n\r\n",
+                BOUNDARY
+            )?;
+            if method == "HEAD" {
+                return Ok(());
+            }
+            let alive = || up.load(Ordering::SeqCst) && generation.load(Ordering::SeqCst) == my_gen;
+            while alive() {
+                write!(
+                    out,
+                    "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                    BOUNDARY,
+                    FRAME_JPEG.len()
+                )?;
+                out.write_all(FRAME_JPEG)?;
+                out.write_all(b"\r\n")?;
+                out.flush()?;
+                thread::sleep(frame_interval);
+            }
+            Ok(())
+        }
+        "/shot.jpg" => {
+            write!(
+                out,
+                "HTTP/1.1 200 OK\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                FRAME_JPEG.len()
+            )?;
+            if method != "HEAD" {
+                out.write_all(FRAME_JPEG)?;
+            }
+            Ok(())
+        }
+        _ => write!(out, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"),
+    }
+}
@@ -0,0 +1,324 @@
+// supervisor.rs
+// Probe the camera, start ffmpeg once it answers, and restart ffmpeg when the
+// stream drops. start-ipcam.sh probes once and exits 42, leaving the retries
+// to systemd; here the retry loop lives in-process with exponential backoff.
+//
+// State machine (every arrow is logged as `event=state from=.. to=..`):
+//
+//   probing --ok--> starting --spawned--> streaming --ffmpeg exited--> stream_dropped
+//      ^  \                 \                                               |
+//      |   fail--> camera_down  spawn failed --> camera_down                |
+//      +-----------(after backoff)--------------------------------------------+
+
+use std::fmt;
+use std::process::{Child, Command, ExitStatus};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::{IpcamConfig, VIDEO_DEVICE};
+use crate::http;
+use crate::logfmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Idle,
+    Probing,
+    CameraDown,
+    Starting,
+    Streaming,
+    StreamDropped,
+    Stopped,
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            State::Idle => "idle",
+            State::Probing => "probing",
+            State::CameraDown => "camera_down",
+            State::Starting => "starting",
+            State::Streaming => "streaming",
+            State::StreamDropped => "stream_dropped",
+            State::Stopped => "stopped",
+        };
+        f.write_str(name)
+    }
+}
+
+// Doubling delay, capped at `max`. `reset` after a healthy run.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Backoff {
+        Backoff { initial, max, current: initial }
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.current.min(self.max);
+        self.current = self.current.saturating_mul(2).min(self.max);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.current = self.initial;
+    }
+}
+
+// Anything that can say whether the camera is up. The real one does an HTTP
+// HEAD; a scripted one makes the state machine easy to drive by hand.
+pub trait Probe {
+    fn probe(&mut self) -> Result<(), String>;
+}
+
+pub struct HttpProbe {
+    pub url: String,
+    pub timeout: Duration,
+}
+
+impl Probe for HttpProbe {
+    fn probe(&mut self) -> Result<(), String> {
+        http::head_ok(&self.url, self.timeout).map(|_| ())
+    }
+}
+
+pub struct SupervisorOptions {
+    pub command: Vec<String>,     // The streaming child, argv[0] first.
+    pub pre_start: Vec<String>,   // Optional command run before every start (e.g. modprobe).
+    pub stable_after: Duration,   // A child that lived this long counts as a healthy run.
+    pub poll_interval: Duration,  // How often a running child is checked.
+    pub max_cycles: Option<u32>,  // Stop after this many probe attempts (None = forever).
+}
+
+impl SupervisorOptions {
+    // The same ffmpeg line start-ipcam.sh `exec`s.
+    pub fn ffmpeg_defaults(config: &IpcamConfig) -> SupervisorOptions {
+        SupervisorOptions {
+            command: vec![
+                "ffmpeg".into(), "-re".into(), "-i".into(), config.video_url(),
+                "-vcodec".into(), "rawvideo".into(), "-pix_fmt".into(), "yuv420p".into(),
+                "-f".into(), "v4l2".into(), VIDEO_DEVICE.into(),
+            ],
+            pre_start: Vec::new(),
+            stable_after: Duration::from_secs(10),
+            poll_interval: Duration::from_millis(200),
+            max_cycles: None,
+        }
+    }
+}
+
+pub struct Supervisor<P: Probe> {
+    probe: P,
+    backoff: Backoff,
+    options: SupervisorOptions,
+    state: State,
+    starts: u32, // Children spawned, the first one included.
+}
+
+impl<P: Probe> Supervisor<P> {
+    pub fn new(probe: P, backoff: Backoff, options: SupervisorOptions) -> Supervisor<P> {
+        Supervisor { probe, backoff, options, state: State::Idle, starts: 0 }
+    }
+
+    fn transition(&mut self, to: State, mut fields: Vec<(&str, String)>) {
+        let mut all = vec![("from", self.state.to_string()), ("to", to.to_string())];
+        all.append(&mut fields);
+        let level = match to {
+            State::CameraDown | State::StreamDropped => "warn",
+            _ => "info",
+        };
+        logfmt::event(level, "state", &all);
+        self.state = to;
+    }
+
+    fn wait_out_backoff(&mut self) {
+        let delay = self.backoff.next_delay();
+        logfmt::event("info", "backoff", &[("retry_in_ms", delay.as_millis().to_string())]);
+        thread::sleep(delay);
+    }
+
+    // Runs until `max_cycles` probes have been made, or forever.
+    pub fn run(&mut self) {
+        let mut cycles = 0u32;
+        loop {
+            if let Some(max) = self.options.max_cycles {
+                if cycles >= max {
+                    break;
+                }
+            }
+            cycles += 1;
+
+            self.transition(State::Probing, vec![("cycle", cycles.to_string())]);
+            if let Err(reason) = self.probe.probe() {
+                self.transition(State::CameraDown, vec![("reason", reason)]);
+                self.wait_out_backoff();
+                continue;
+            }
+
+            self.transition(State::Starting, vec![("starts", self.starts.to_string())]);
+            if !self.options.pre_start.is_empty() {
+                if let Err(e) = run_to_completion(&self.options.pre_start) {
+                    logfmt::event("warn", "pre_start_failed", &[("error", e)]);
+                }
+            }
+            let mut child = match spawn(&self.options.command) {
+                Ok(child) => child,
+                Err(e) => {
+                    self.transition(State::CameraDown, vec![("reason", format!("spawn failed: {}", e))]);
+                    self.wait_out_backoff();
+                    continue;
+                }
+            };
+            self.starts += 1;
+            self.transition(State::Streaming, vec![("pid", child.id().to_string())]);
+
+            let started = Instant::now();
+            let status = self.monitor(&mut child);
+            let uptime = started.elapsed();
+            self.transition(
+                State::StreamDropped,
+                vec![("exit", describe_exit(&status)), ("uptime_ms", uptime.as_millis().to_string())],
+            );
+
+            // A child that died right away is treated like an unreachable camera,
+            // so a crash loop backs off instead of hammering the camera.
+            if uptime >= self.options.stable_after {
+                self.backoff.reset();
+            } else {
+                self.wait_out_backoff();
+            }
+        }
+        self.transition(State::Stopped, vec![("starts", self.starts.to_string())]);
+    }
+
+    fn monitor(&mut self, child: &mut Child) -> Result<ExitStatus, String> {
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => return Ok(status),
+                Ok(None) => thread::sleep(self.options.poll_interval),
+                Err(e) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(e.to_string());
+                }
+            }
+        }
+    }
+}
+
+fn spawn(argv: &[String]) -> std::io::Result<Child> {
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "empty command"))?;
+    Command::new(program).args(args).spawn()
+}
+
+fn run_to_completion(argv: &[String]) -> Result<(), String> {
+    let status = spawn(argv).and_then(|mut c| c.wait()).map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(describe_exit(&Ok(status)))
+    }
+}
+
+fn describe_exit(status: &Result<ExitStatus, String>) -> String {
+    match status {
+        Ok(s) => match s.code() {
+            Some(code) => code.to_string(),
+            None => "signal".to_string(),
+        },
+        Err(e) => format!("wait failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::{parse_script, StubCamera};
+
+    // Plays the next phase of a stub script before every probe, then asks the
+    // stub over HTTP like the real supervisor would. Records what each probe saw.
+    struct ScriptedStub {
+        camera: StubCamera,
+        script: Vec<(bool, Duration)>,
+        http: HttpProbe,
+        seen: Vec<bool>,
+    }
+
+    impl ScriptedStub {
+        fn new(script: &str) -> ScriptedStub {
+            let camera = StubCamera::start(0, Duration::from_millis(20)).unwrap();
+            let http = HttpProbe { url: format!("{}/video", camera.base_url()), timeout: Duration::from_secs(2) };
+            ScriptedStub { camera, script: parse_script(script).unwrap(), http, seen: Vec::new() }
+        }
+    }
+
+    impl Probe for ScriptedStub {
+        fn probe(&mut self) -> Result<(), String> {
+            let phase = self.script[self.seen.len() % self.script.len()];
+            self.camera.play(&[phase]); // The phase length gives the stub time to (un)bind.
+            let result = self.http.probe();
+            self.seen.push(result.is_ok());
+            result
+        }
+    }
+
+    fn supervise(script: &str, cycles: u32) -> Supervisor<ScriptedStub> {
+        let options = SupervisorOptions {
+            command: vec!["sh".into(), "-c".into(), "exit 0".into()],
+            pre_start: Vec::new(),
+            stable_after: Duration::from_secs(60),
+            poll_interval: Duration::from_millis(5),
+            max_cycles: Some(cycles),
+        };
+        let backoff = Backoff::new(Duration::from_millis(1), Duration::from_millis(4));
+        let mut supervisor = Supervisor::new(ScriptedStub::new(script), backoff, options);
+        supervisor.run();
+        supervisor
+    }
+
+    #[test]
+    fn camera_up_starts_the_stream_every_cycle() {
+        let supervisor = supervise("up:0.05", 3);
+        assert_eq!(supervisor.probe.seen, [true, true, true]);
+        assert_eq!(supervisor.starts, 3);
+        assert_eq!(supervisor.state, State::Stopped);
+    }
+
+    #[test]
+    fn camera_down_never_starts_the_stream() {
+        let supervisor = supervise("down:0.05", 3);
+        assert_eq!(supervisor.probe.seen, [false, false, false]);
+        assert_eq!(supervisor.starts, 0);
+        assert_eq!(supervisor.state, State::Stopped);
+    }
+
+    #[test]
+    fn flapping_camera_starts_the_stream_only_while_up() {
+        let supervisor = supervise("up:0.05,down:0.05", 5);
+        assert_eq!(supervisor.probe.seen, [true, false, true, false, true]);
+        assert_eq!(supervisor.starts, 3);
+        assert_eq!(supervisor.state, State::Stopped);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap_and_resets() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(300));
+        let delays: Vec<u128> = (0..4).map(|_| backoff.next_delay().as_millis()).collect();
+        assert_eq!(delays, [100, 200, 300, 300]);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn backoff_near_duration_max_saturates_instead_of_overflowing() {
+        let mut backoff = Backoff::new(Duration::MAX / 2 + Duration::from_secs(1), Duration::MAX);
+        backoff.next_delay();
+        assert_eq!(backoff.next_delay(), Duration::MAX);
+    }
+}